@@ -1,8 +1,11 @@
 use kast_event::{Event, WindowEvent, WindowEventPayload};
 use kast_graphics::VulkanContext;
+use kast_renderer::Renderer;
+use kast_resources::AssetServer;
 use kast_windowing::{EventLoopHandler, WindowManager};
+use kast_world::{IntoSystem, NonSendMut, ResMut, System};
 
-use crate::{AppBuilder, AppContext, AppState};
+use crate::{AppBuilder, AppContext, AppState, Time};
 
 /// The main application container.
 ///
@@ -11,14 +14,20 @@ use crate::{AppBuilder, AppContext, AppState};
 pub struct App {
     state: Box<dyn AppState>,
     context: AppContext,
+    /// Runs `prepare_assets` before each update.
+    prepare_assets: Box<dyn System>,
     exit_requested: bool,
 }
 
 impl App {
-    pub(crate) fn new(state: Box<dyn AppState>, context: AppContext) -> Self {
+    pub(crate) fn new(state: Box<dyn AppState>, mut context: AppContext) -> Self {
+        let mut prepare_assets: Box<dyn System> = Box::new(prepare_assets.into_system());
+        prepare_assets.initialize(&mut context.world);
+
         Self {
             state,
             context,
+            prepare_assets,
             exit_requested: false,
         }
     }
@@ -56,7 +65,7 @@ impl App {
     /// handle a graphics backend needs) is only created once the event loop is
     /// active, which is when `on_resume` first fires.
     fn init_renderer(&mut self, window_manager: &WindowManager) {
-        if self.context.renderer.is_ready() {
+        if self.context.renderer().is_ready() {
             return;
         }
 
@@ -69,12 +78,19 @@ impl App {
 
         let size = &window.config().size;
         match VulkanContext::new("kast", winit_window, size.width, size.height) {
-            Ok(context) => self.context.renderer.attach(Box::new(context)),
+            Ok(context) => self.context.renderer_mut().attach(Box::new(context)),
             Err(error) => eprintln!("Failed to initialize renderer: {error}"),
         }
     }
 }
 
+/// Applies finished loads and file changes, then hands whatever changed to
+/// the renderer to upload.
+fn prepare_assets(mut assets: ResMut<AssetServer>, mut renderer: NonSendMut<Renderer>) {
+    assets.update();
+    renderer.prepare_assets(&mut assets);
+}
+
 /// Implement the event-loop handler trait so `WindowManager` can call into the
 /// `App` for events and idle ticks.
 impl EventLoopHandler for App {
@@ -98,7 +114,7 @@ impl EventLoopHandler for App {
             ..
         }) = &event
         {
-            self.context.renderer.resize(*width, *height);
+            self.context.renderer_mut().resize(*width, *height);
         }
 
         self.with_context(window_manager, |state, context| {
@@ -107,20 +123,28 @@ impl EventLoopHandler for App {
     }
 
     fn on_update(&mut self, window_manager: &mut WindowManager) {
+        if let Some(time) = self.context.resource_mut::<Time>() {
+            time.update();
+        }
+
+        self.prepare_assets.run(&mut self.context.world);
+
         self.with_context(window_manager, |state, context| {
             state.on_update(context);
+            context.schedule.run(&mut context.world);
+            context.world.clear_trackers();
         });
     }
 
     fn on_render(&mut self, window_manager: &mut WindowManager) {
         self.with_context(window_manager, |state, context| {
-            if !context.renderer.begin_frame() {
+            if !context.renderer_mut().begin_frame() {
                 return;
             }
 
             state.on_render(context);
 
-            context.renderer.end_frame();
+            context.renderer_mut().end_frame();
         });
     }
 
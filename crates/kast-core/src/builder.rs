@@ -1,19 +1,39 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use kast_graphics::shader::ShaderLoader;
+use kast_graphics::texture::TextureLoader;
 use kast_renderer::Renderer;
+use kast_resources::{AssetLoader, AssetServer, Vfs, VfsSource};
 use kast_windowing::{WindowConfig, WindowManager};
+use kast_world::{IntoSystemConfig, Schedule, World, propagate_transforms};
+
+use crate::{App, AppContext, AppState, Time, state::EmptyState};
 
-use crate::{App, AppContext, AppState, state::EmptyState};
+type RegisterLoader = Box<dyn FnOnce(&mut AssetServer)>;
+type MountSource = Box<dyn FnOnce(&Vfs)>;
 
 /// Builder for configuring an `App` before running it.
 ///
 /// The builder pattern allows you to set various configuration options before constructing the final application.
 pub struct AppBuilder {
     window_configs: Vec<WindowConfig>,
+    schedule: Schedule,
+    asset_root: PathBuf,
+    asset_sources: Vec<MountSource>,
+    asset_loaders: Vec<RegisterLoader>,
+    watch_assets: bool,
 }
 
 impl Default for AppBuilder {
     fn default() -> Self {
         Self {
             window_configs: Vec::new(),
+            schedule: Schedule::new(),
+            asset_root: PathBuf::from("assets"),
+            asset_sources: Vec::new(),
+            asset_loaders: Vec::new(),
+            watch_assets: cfg!(debug_assertions),
         }
     }
 }
@@ -28,6 +48,61 @@ impl AppBuilder {
         self
     }
 
+    /// Add a system to the schedule stepped on every update.
+    ///
+    /// Systems may also be added later through `AppContext::schedule`; those
+    /// that move entities should be ordered `.before("propagate_transforms")`.
+    pub fn with_system<Marker>(mut self, system: impl IntoSystemConfig<Marker>) -> Self {
+        self.schedule.add_system(system);
+
+        self
+    }
+
+    /// Set the directory the `AssetServer` loads from, `assets` by default.
+    ///
+    /// It's mounted at the root of the asset `Vfs`, under any source added
+    /// with `with_asset_source`.
+    pub fn with_asset_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.asset_root = root.into();
+
+        self
+    }
+
+    /// Mount another source of assets, such as a packed archive, under
+    /// `point` in the asset `Vfs`.
+    ///
+    /// Sources added later override earlier ones and the asset root for the
+    /// files they share.
+    pub fn with_asset_source(
+        mut self,
+        point: impl AsRef<Path>,
+        source: impl VfsSource + 'static,
+    ) -> Self {
+        let point = point.as_ref().to_path_buf();
+        self.asset_sources
+            .push(Box::new(move |vfs| vfs.mount(point, source)));
+
+        self
+    }
+
+    /// Reload assets when their files change, on by default in debug builds.
+    pub fn with_asset_watching(mut self, enabled: bool) -> Self {
+        self.watch_assets = enabled;
+
+        self
+    }
+
+    /// Register a loader for a custom asset format.
+    ///
+    /// Loaders added here take precedence over the built-in ones for the
+    /// same extension and asset type.
+    pub fn with_asset_loader(mut self, loader: impl AssetLoader) -> Self {
+        self.asset_loaders
+            .push(Box::new(move |assets| assets.register_loader(loader)));
+
+        self
+    }
+
     /// Build the final `App` with a specific state.
     ///
     /// If no windows were configured, a default window is created
@@ -43,11 +118,41 @@ impl AppBuilder {
             window_manager.queue_window(config);
         }
 
+        // Added last so that systems writing `Transform` run before it.
+        self.schedule
+            .add_system(propagate_transforms.label("propagate_transforms"));
+
+        let mut world = World::new();
+        world.insert_non_send_resource(Renderer::new());
+        world.insert_resource(Time::new());
+        let vfs = Vfs::with_directory(self.asset_root);
+        for mount in self.asset_sources {
+            mount(&vfs);
+        }
+        let mut assets = AssetServer::with_vfs(Arc::new(vfs));
+        assets.register_loader(TextureLoader);
+        assets.register_loader(ShaderLoader);
+        for register in self.asset_loaders {
+            register(&mut assets);
+        }
+        if self.watch_assets
+            && assets
+                .vfs()
+                .directories()
+                .iter()
+                .any(|(_, directory)| directory.is_dir())
+            && let Err(error) = assets.watch_for_changes()
+        {
+            eprintln!("Failed to watch assets for changes: {error}");
+        }
+        world.insert_resource(assets);
+
         App::new(
             Box::new(state),
             AppContext {
                 window_manager,
-                renderer: Renderer::new(),
+                world,
+                schedule: self.schedule,
                 exit_requested: false,
             },
         )
@@ -1,18 +1,25 @@
 pub mod app;
 pub mod builder;
 pub mod state;
+pub mod time;
 
 pub use app::App;
 pub use builder::AppBuilder;
 use kast_renderer::Renderer;
+use kast_resources::AssetServer;
 pub use kast_windowing::*;
+/// Re-exported so `#[derive(Reflect)]` works for crates that only depend
+/// on `kast` or `kast-core`.
+pub use kast_world as world;
+use kast_world::{Resource, Schedule, World};
 pub use state::AppState;
+pub use time::Time;
 
 /// Commonly used types that users typically want to import.
 ///
 /// Use this with `use kast_core::prelude::*;` to get started quickly.
 pub mod prelude {
-    pub use crate::{App, AppBuilder, AppContext, AppState};
+    pub use crate::{App, AppBuilder, AppContext, AppState, Time};
     pub use kast_event::Event;
     pub use kast_graphics::{
         GraphicsContext,
@@ -20,17 +27,35 @@ pub mod prelude {
         descriptors::*,
         enums::*,
         handle::*,
+        resources::GpuResources,
+        shader::{Shader, ShaderPipeline, ShaderPipelineDescriptor},
+        texture::{Texture, TextureSettings},
+    };
+    pub use kast_math::{Color, IVec2, Lerp, Mat3, Mat4, Quat, Transform, UVec2, Vec2, Vec3, Vec4};
+    pub use kast_resources::{
+        ArchiveSource, AssetError, AssetEvent, AssetLoader, AssetServer, DirectorySource,
+        LoadContext, LoadState, MemorySource, Vfs, VfsError, VfsSource,
     };
     pub use kast_windowing::{PhysicalSize, WindowConfig, WindowManager, WindowMode};
+    pub use kast_world::{
+        Added, Changed, Children, Commands, Entity, GlobalTransform, IntoSystemConfig, Mut,
+        NonSend, NonSendMut, Or, Parent, Query, Reflect, RemovedComponents, Res, ResMut, Schedule,
+        TypeRegistry, With, Without, World, apply_deferred,
+    };
 }
 
 /// The application context passed to state callbacks.
 ///
 /// This provides access to various engine subsystems like the window manager,
 /// and allows states to request application exit.
+///
+/// `world` holds every entity, component and resource (including the
+/// `Renderer` and `Time`), and `schedule` holds the systems the app steps
+/// against it after each `AppState::on_update`.
 pub struct AppContext {
-    pub renderer: Renderer,
     pub window_manager: WindowManager,
+    pub world: World,
+    pub schedule: Schedule,
     pub(crate) exit_requested: bool,
 }
 
@@ -42,6 +67,49 @@ impl AppContext {
         self.exit_requested = true;
     }
 
+    /// Shared access to a resource stored in the world.
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.world.resource()
+    }
+
+    /// Mutable access to a resource stored in the world.
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.world.resource_mut()
+    }
+
+    /// Store a resource in the world, replacing any previous value.
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> Option<R> {
+        self.world.insert_resource(resource)
+    }
+
+    /// The renderer, stored in the world as a non-send resource.
+    pub fn renderer(&self) -> &Renderer {
+        self.world
+            .non_send_resource()
+            .expect("the renderer resource was removed from the world")
+    }
+
+    /// Mutable access to the renderer, e.g. to reach its `GraphicsContext`.
+    pub fn renderer_mut(&mut self) -> &mut Renderer {
+        self.world
+            .non_send_resource_mut()
+            .expect("the renderer resource was removed from the world")
+    }
+
+    /// The asset server, stored in the world as a resource.
+    pub fn assets(&self) -> &AssetServer {
+        self.world
+            .resource()
+            .expect("the asset server resource was removed from the world")
+    }
+
+    /// Mutable access to the asset server, e.g. to `load` assets.
+    pub fn assets_mut(&mut self) -> &mut AssetServer {
+        self.world
+            .resource_mut()
+            .expect("the asset server resource was removed from the world")
+    }
+
     /// Check if exit has been requested.
     pub(crate) fn should_exit(&self) -> bool {
         self.exit_requested
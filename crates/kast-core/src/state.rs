@@ -32,7 +32,8 @@ pub trait AppState {
     /// Called each frame to update game logic.
     ///
     /// This runs before rendering and is where you should update entity
-    /// positions, process AI, handle physics, etc.
+    /// positions, process AI, handle physics, etc. The systems in
+    /// `AppContext::schedule` are stepped right after this returns.
     fn on_update(&mut self, _context: &mut AppContext) {}
 
     /// Called each frame to perform rendering.
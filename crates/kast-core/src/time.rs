@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+/// Frame timing, stored as a resource in `AppContext::world`.
+///
+/// The app advances it at the start of every update, so systems can read it
+/// with `Res<Time>`.
+pub struct Time {
+    startup: Instant,
+    last_update: Option<Instant>,
+    delta: Duration,
+    frame_count: u64,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Time {
+    pub fn new() -> Self {
+        Self {
+            startup: Instant::now(),
+            last_update: None,
+            delta: Duration::ZERO,
+            frame_count: 0,
+        }
+    }
+
+    /// Time elapsed between the previous update and the current one.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_secs(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Time elapsed since the app started.
+    pub fn elapsed(&self) -> Duration {
+        self.last_update
+            .map_or(Duration::ZERO, |last| last - self.startup)
+    }
+
+    /// Number of updates since the app started.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub(crate) fn update(&mut self) {
+        let now = Instant::now();
+        self.delta = self.last_update.map_or(Duration::ZERO, |last| now - last);
+        self.last_update = Some(now);
+        self.frame_count += 1;
+    }
+}
@@ -224,13 +224,16 @@ impl VulkanImage {
         }
     }
 
-    /// Copies data from a buffer to this image.
+    /// Copies data from a buffer to this image, filling every mip level.
+    ///
+    /// The buffer holds the levels tightly packed one after the other, largest
+    /// first, at 4 bytes per texel, which all supported formats use.
     ///
     /// # Arguments
     /// * `command_buffer` - The command buffer to record the copy into
     /// * `buffer` - The source buffer
-    /// * `width` - The width of the region to copy
-    /// * `height` - The height of the region to copy
+    /// * `width` - The width of the first mip level
+    /// * `height` - The height of the first mip level
     pub fn copy_from_buffer(
         &self,
         command_buffer: &VulkanCommandBuffer,
@@ -238,29 +241,38 @@ impl VulkanImage {
         width: u32,
         height: u32,
     ) {
-        let region = VkBufferImageCopy {
-            bufferOffset: 0,
-            bufferRowLength: 0,
-            bufferImageHeight: 0,
-            imageSubresource: VkImageSubresourceLayers {
-                aspectMask: VK_IMAGE_ASPECT_COLOR_BIT,
-                mipLevel: 0,
-                baseArrayLayer: 0,
-                layerCount: 1,
-            },
-            imageOffset: VkOffset3D { x: 0, y: 0, z: 0 },
-            imageExtent: VkExtent3D {
-                width,
-                height,
-                depth: 1,
-            },
-        };
+        let mut offset = 0;
+        let regions: Vec<VkBufferImageCopy> = (0..self.mip_levels)
+            .map(|level| {
+                let width = (width >> level).max(1);
+                let height = (height >> level).max(1);
+                let region = VkBufferImageCopy {
+                    bufferOffset: offset,
+                    bufferRowLength: 0,
+                    bufferImageHeight: 0,
+                    imageSubresource: VkImageSubresourceLayers {
+                        aspectMask: VK_IMAGE_ASPECT_COLOR_BIT,
+                        mipLevel: level,
+                        baseArrayLayer: 0,
+                        layerCount: 1,
+                    },
+                    imageOffset: VkOffset3D { x: 0, y: 0, z: 0 },
+                    imageExtent: VkExtent3D {
+                        width,
+                        height,
+                        depth: 1,
+                    },
+                };
+                offset += u64::from(width) * u64::from(height) * 4;
+                region
+            })
+            .collect();
 
         command_buffer.copy_buffer_to_image(
             buffer,
             self.handle,
             VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL,
-            &[region],
+            &regions,
         );
     }
 
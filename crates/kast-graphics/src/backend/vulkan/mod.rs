@@ -1,3 +1,4 @@
+use kast_math::Color;
 use kast_resources::Pool;
 use raw_window_handle::HasDisplayHandle;
 use vk_bindings::*;
@@ -36,10 +37,10 @@ struct FrameData {
 }
 
 pub struct VulkanContext {
-    buffers: Pool<VulkanBuffer>,
-    textures: Pool<VulkanImage>,
-    pipelines: Pool<VulkanPipeline>,
-    samplers: Pool<VulkanSampler>,
+    buffers: Pool<VulkanBuffer, GpuBuffer>,
+    textures: Pool<VulkanImage, GpuTexture>,
+    pipelines: Pool<VulkanPipeline, GpuPipeline>,
+    samplers: Pool<VulkanSampler, GpuSampler>,
 
     bindless_system: BindlessDescriptorSystem,
 
@@ -93,8 +94,7 @@ impl GraphicsContext for VulkanContext {
         )
         .map_err(|e| GraphicsError::OutOfMemory(e))?;
 
-        let (index, generation) = self.buffers.insert(buffer);
-        Ok(BufferHandle { index, generation })
+        Ok(self.buffers.insert(buffer))
     }
 
     fn create_texture(
@@ -134,8 +134,7 @@ impl GraphicsContext for VulkanContext {
         );
         image.bindless_slot = Some(bindless_slot);
 
-        let (index, generation) = self.textures.insert(image);
-        Ok(TextureHandle { index, generation })
+        Ok(self.textures.insert(image))
     }
 
     fn create_sampler(
@@ -175,8 +174,7 @@ impl GraphicsContext for VulkanContext {
             .update_sampler(bindless_slot, sampler.handle);
         sampler.bindless_slot = Some(bindless_slot);
 
-        let (index, generation) = self.samplers.insert(sampler);
-        Ok(SamplerHandle { index, generation })
+        Ok(self.samplers.insert(sampler))
     }
 
     fn create_pipeline(
@@ -302,8 +300,7 @@ impl GraphicsContext for VulkanContext {
 
         core::mem::forget(pipeline_layout);
 
-        let (index, generation) = self.pipelines.insert(pipeline);
-        Ok(PipelineHandle { index, generation })
+        Ok(self.pipelines.insert(pipeline))
     }
 
     fn upload_buffer(
@@ -315,7 +312,7 @@ impl GraphicsContext for VulkanContext {
             buffers, allocator, ..
         } = self;
 
-        if let Some(buffer) = buffers.get(handle.index, handle.generation) {
+        if let Some(buffer) = buffers.get(handle) {
             buffer
                 .write_data(allocator, data)
                 .map_err(GraphicsError::Unknown)
@@ -327,7 +324,7 @@ impl GraphicsContext for VulkanContext {
     fn upload_texture(&mut self, handle: TextureHandle, data: &[u8]) -> Result<(), GraphicsError> {
         let texture = self
             .textures
-            .get(handle.index, handle.generation)
+            .get(handle)
             .ok_or_else(|| GraphicsError::Unknown("Texture not found".to_string()))?;
 
         let command_raw = self
@@ -344,7 +341,7 @@ impl GraphicsContext for VulkanContext {
             VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL,
         );
 
-        if let Some(texture) = self.textures.get(handle.index, handle.generation) {
+        if let Some(texture) = self.textures.get(handle) {
             let staging_index = {
                 self.upload_context
                     .get_staging_buffer_index(&mut self.allocator, data.len() as u64)
@@ -372,7 +369,7 @@ impl GraphicsContext for VulkanContext {
             );
         }
 
-        if self.textures.get(handle.index, handle.generation).is_none() {
+        if self.textures.get(handle).is_none() {
             return Err(GraphicsError::Unknown("Texture not found".to_string()));
         }
 
@@ -384,13 +381,13 @@ impl GraphicsContext for VulkanContext {
     }
 
     fn destroy_buffer(&mut self, handle: BufferHandle) {
-        if let Some(buffer) = self.buffers.remove(handle.index, handle.generation) {
+        if let Some(buffer) = self.buffers.remove(handle) {
             buffer.destroy(&mut self.deletion_queue);
         }
     }
 
     fn destroy_texture(&mut self, handle: TextureHandle) {
-        if let Some(texture) = self.textures.remove(handle.index, handle.generation) {
+        if let Some(texture) = self.textures.remove(handle) {
             if let Some(slot) = texture.bindless_slot {
                 self.bindless_system.free_texture_slot(slot);
             }
@@ -399,7 +396,7 @@ impl GraphicsContext for VulkanContext {
     }
 
     fn destroy_sampler(&mut self, handle: SamplerHandle) {
-        if let Some(sampler) = self.samplers.remove(handle.index, handle.generation) {
+        if let Some(sampler) = self.samplers.remove(handle) {
             if let Some(slot) = sampler.bindless_slot {
                 self.bindless_system.free_sampler_slot(slot);
             }
@@ -407,7 +404,7 @@ impl GraphicsContext for VulkanContext {
     }
 
     fn destroy_pipeline(&mut self, handle: PipelineHandle) {
-        self.pipelines.remove(handle.index, handle.generation);
+        self.pipelines.remove(handle);
     }
 
     fn begin_frame(&mut self) -> Result<(), GraphicsError> {
@@ -472,7 +469,10 @@ impl GraphicsContext for VulkanContext {
         for pass in passes {
             let mut clear_values = Vec::new();
             for color_attachment in &pass.descriptor.color_attachments {
-                let color = color_attachment.clear_color.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+                let color = color_attachment
+                    .clear_color
+                    .unwrap_or(Color::BLACK)
+                    .to_array();
                 clear_values.push(VkClearValue {
                     color: VkClearColorValue { float32: color },
                 });
@@ -504,10 +504,7 @@ impl GraphicsContext for VulkanContext {
             command_buffer.set_scissor(0, 0, swapchain.extent.width, swapchain.extent.height);
 
             for call in &pass.draw_list.calls {
-                if let Some(pipeline) = self
-                    .pipelines
-                    .get(call.pipeline.index, call.pipeline.generation)
-                {
+                if let Some(pipeline) = self.pipelines.get(call.pipeline) {
                     if pipeline.bind_point == VK_PIPELINE_BIND_POINT_GRAPHICS {
                         command_buffer.bind_graphics_pipeline(pipeline.handle);
 
@@ -530,18 +527,12 @@ impl GraphicsContext for VulkanContext {
                             );
                         }
 
-                        if let Some(vertex_buffer) = self
-                            .buffers
-                            .get(call.vertex_buffer.index, call.vertex_buffer.generation)
-                        {
+                        if let Some(vertex_buffer) = self.buffers.get(call.vertex_buffer) {
                             command_buffer.bind_vertex_buffers(0, &[vertex_buffer.handle], &[0]);
                         }
 
                         if let Some(index_buffer) = call.index_buffer {
-                            if let Some(ib) = self
-                                .buffers
-                                .get(index_buffer.index, index_buffer.generation)
-                            {
+                            if let Some(ib) = self.buffers.get(index_buffer) {
                                 command_buffer.bind_index_buffer(
                                     ib.handle,
                                     0,
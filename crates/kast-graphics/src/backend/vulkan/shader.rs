@@ -1,3 +1,6 @@
+use std::path::Path;
+
+use kast_resources::Vfs;
 use vk_bindings::*;
 
 use crate::backend::vulkan::device::VulkanDevice;
@@ -57,17 +60,30 @@ impl VulkanShader {
 
     /// Creates a new shader module from a SPIR-V file.
     ///
-    /// This is a convenience function that reads the file and calls `new()`.
+    /// This is a convenience function that reads the file through the virtual
+    /// file system and calls `new()`, so shaders can come from loose files or
+    /// packed archives alike.
     ///
     /// # Arguments
     /// * `device` - The Vulkan device.
-    /// * `file_path` - The path to the SPIR-V shader file.
+    /// * `vfs` - The virtual file system to read from.
+    /// * `file_path` - The path to the SPIR-V shader file within `vfs`.
     ///
     /// # Returns
     /// A new VulkanShader or an error if file reading or shader creation fails.
-    pub fn from_file(device: &VulkanDevice, file_path: &str) -> Result<Self, String> {
-        let bytecode = std::fs::read(file_path)
-            .map_err(|e| format!("Failed to read shader file '{}': {}", file_path, e))?;
+    pub fn from_file(
+        device: &VulkanDevice,
+        vfs: &Vfs,
+        file_path: impl AsRef<Path>,
+    ) -> Result<Self, String> {
+        let file_path = file_path.as_ref();
+        let bytecode = vfs.read(file_path).map_err(|e| {
+            format!(
+                "Failed to read shader file '{}': {}",
+                file_path.display(),
+                e
+            )
+        })?;
 
         Self::new(device, &bytecode)
     }
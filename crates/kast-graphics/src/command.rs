@@ -1,3 +1,5 @@
+use kast_math::Color;
+
 use crate::handle::*;
 
 pub struct DrawCall {
@@ -24,7 +26,8 @@ impl DrawList {
 
 pub struct ColorAttachment {
     pub target: Option<TextureHandle>,
-    pub clear_color: Option<[f32; 4]>,
+    /// Linear; the GPU encodes it when the target is sRGB.
+    pub clear_color: Option<Color>,
 }
 
 pub struct DepthAttachment {
@@ -11,6 +11,7 @@ pub enum MemoryProperties {
     HostCoherent,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TextureFormat {
     Rgba8Srgb,
     Rgba8Unorm,
@@ -1,23 +1,15 @@
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-pub struct BufferHandle {
-    pub index: u32,
-    pub generation: u32,
-}
+use kast_resources::Handle;
+pub use kast_resources::{StrongHandle, WeakHandle};
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-pub struct TextureHandle {
-    pub index: u32,
-    pub generation: u32,
-}
+// Markers naming what each handle points to, independent of the backend
+// types actually stored.
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-pub struct PipelineHandle {
-    pub index: u32,
-    pub generation: u32,
-}
+pub enum GpuBuffer {}
+pub enum GpuTexture {}
+pub enum GpuPipeline {}
+pub enum GpuSampler {}
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
-pub struct SamplerHandle {
-    pub index: u32,
-    pub generation: u32,
-}
+pub type BufferHandle = Handle<GpuBuffer>;
+pub type TextureHandle = Handle<GpuTexture>;
+pub type PipelineHandle = Handle<GpuPipeline>;
+pub type SamplerHandle = Handle<GpuSampler>;
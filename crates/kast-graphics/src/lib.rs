@@ -8,6 +8,9 @@ pub mod command;
 pub mod descriptors;
 pub mod enums;
 pub mod handle;
+pub mod resources;
+pub mod shader;
+pub mod texture;
 
 pub trait GraphicsContext {
     fn create_buffer(
@@ -28,6 +31,8 @@ pub trait GraphicsContext {
     ) -> Result<PipelineHandle, GraphicsError>;
 
     fn upload_buffer(&mut self, handle: BufferHandle, data: &[u8]) -> Result<(), GraphicsError>;
+    /// `data` holds every mip level the texture was created with, tightly
+    /// packed one after the other, largest first.
     fn upload_texture(&mut self, handle: TextureHandle, data: &[u8]) -> Result<(), GraphicsError>;
 
     fn destroy_buffer(&mut self, handle: BufferHandle);
@@ -0,0 +1,61 @@
+use kast_resources::{RefCounts, StrongHandle};
+
+use crate::{GraphicsContext, handle::*};
+
+/// Reference counting for GPU objects created through a `GraphicsContext`.
+///
+/// Wrapping a handle with one of the `track_*` methods ties the object's
+/// lifetime to the returned `StrongHandle`s. Once the last one drops, the
+/// object is destroyed by the next `release_unused`, which the renderer calls
+/// at the end of every frame.
+///
+/// Untracked handles keep the old behavior: they live until destroyed by
+/// hand or until the context itself is dropped.
+#[derive(Default)]
+pub struct GpuResources {
+    buffers: RefCounts<GpuBuffer>,
+    textures: RefCounts<GpuTexture>,
+    pipelines: RefCounts<GpuPipeline>,
+    samplers: RefCounts<GpuSampler>,
+}
+
+impl GpuResources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn track_buffer(&self, handle: BufferHandle) -> StrongHandle<GpuBuffer> {
+        self.buffers.track(handle)
+    }
+
+    pub fn track_texture(&self, handle: TextureHandle) -> StrongHandle<GpuTexture> {
+        self.textures.track(handle)
+    }
+
+    pub fn track_pipeline(&self, handle: PipelineHandle) -> StrongHandle<GpuPipeline> {
+        self.pipelines.track(handle)
+    }
+
+    pub fn track_sampler(&self, handle: SamplerHandle) -> StrongHandle<GpuSampler> {
+        self.samplers.track(handle)
+    }
+
+    /// Destroys every tracked object whose last strong handle has dropped.
+    ///
+    /// Backends defer the actual destruction until in-flight frames are done
+    /// with the object, so this is safe to call mid-frame.
+    pub fn release_unused(&self, context: &mut dyn GraphicsContext) {
+        for handle in self.buffers.drain_released() {
+            context.destroy_buffer(handle);
+        }
+        for handle in self.textures.drain_released() {
+            context.destroy_texture(handle);
+        }
+        for handle in self.pipelines.drain_released() {
+            context.destroy_pipeline(handle);
+        }
+        for handle in self.samplers.drain_released() {
+            context.destroy_sampler(handle);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use kast_resources::{Asset, AssetError, AssetLoader, Handle, LoadContext, StrongHandle};
+
+use crate::{
+    GraphicsContext, GraphicsError,
+    descriptors::{DepthStencilState, PipelineDescriptor, VertexAttribute},
+    enums::*,
+    handle::*,
+    resources::GpuResources,
+};
+
+const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+/// Compiled SPIR-V for one shader stage.
+#[derive(Clone)]
+pub struct Shader {
+    /// Shared, so building pipelines doesn't copy the code.
+    pub spirv: Arc<[u8]>,
+}
+
+impl Shader {
+    /// Checks that `bytes` look like little-endian SPIR-V.
+    pub fn from_spirv(bytes: &[u8]) -> Result<Self, AssetError> {
+        let magic = bytes
+            .first_chunk::<4>()
+            .map(|magic| u32::from_le_bytes(*magic));
+        if !bytes.len().is_multiple_of(4) || magic != Some(SPIRV_MAGIC) {
+            return Err(AssetError::Decode("not SPIR-V".to_string()));
+        }
+
+        Ok(Self {
+            spirv: bytes.into(),
+        })
+    }
+}
+
+impl Asset for Shader {}
+
+/// Loads compiled `.spv` files into `Shader`s.
+pub struct ShaderLoader;
+
+impl AssetLoader for ShaderLoader {
+    type Asset = Shader;
+    type Settings = ();
+
+    fn extensions(&self) -> &[&str] {
+        &["spv"]
+    }
+
+    fn load(
+        &self,
+        bytes: &[u8],
+        _settings: &(),
+        _context: &mut LoadContext,
+    ) -> Result<Shader, AssetError> {
+        Shader::from_spirv(bytes)
+    }
+}
+
+/// Everything `PipelineDescriptor` holds, but owned and with shader assets in
+/// place of code, so the pipeline can be rebuilt when they change.
+pub struct ShaderPipelineDescriptor {
+    pub vertex_shader: StrongHandle<Shader>,
+    pub fragment_shader: StrongHandle<Shader>,
+    pub vertex_layout: Vec<VertexAttribute>,
+    pub topology: PrimitiveTopology,
+    pub polygon_mode: PolygonMode,
+    pub cull_mode: CullMode,
+    pub blend_mode: BlendMode,
+    pub depth_stencil: DepthStencilState,
+}
+
+impl ShaderPipelineDescriptor {
+    /// A pipeline with the same defaults as `PipelineDescriptor`.
+    pub fn new(vertex_shader: StrongHandle<Shader>, fragment_shader: StrongHandle<Shader>) -> Self {
+        Self {
+            vertex_shader,
+            fragment_shader,
+            vertex_layout: Vec::new(),
+            topology: PrimitiveTopology::default(),
+            polygon_mode: PolygonMode::default(),
+            cull_mode: CullMode::default(),
+            blend_mode: BlendMode::default(),
+            depth_stencil: DepthStencilState::default(),
+        }
+    }
+}
+
+/// A pipeline built from shader assets.
+///
+/// Add one to the `AssetServer` and the renderer builds it once both shaders
+/// are loaded, then rebuilds it whenever either is reloaded. Until then
+/// `handle` returns `None`.
+pub struct ShaderPipeline {
+    pub descriptor: ShaderPipelineDescriptor,
+    gpu: Option<StrongHandle<GpuPipeline>>,
+}
+
+impl ShaderPipeline {
+    pub fn new(descriptor: ShaderPipelineDescriptor) -> Self {
+        Self {
+            descriptor,
+            gpu: None,
+        }
+    }
+
+    /// The GPU pipeline from the last successful `build`.
+    pub fn handle(&self) -> Option<PipelineHandle> {
+        self.gpu.as_ref().map(StrongHandle::id)
+    }
+
+    pub fn uses(&self, shader: Handle<Shader>) -> bool {
+        self.descriptor.vertex_shader.id() == shader
+            || self.descriptor.fragment_shader.id() == shader
+    }
+
+    /// Creates the GPU pipeline from the given shader code, replacing the
+    /// previous one, which is released once unused. On failure the previous
+    /// pipeline is kept.
+    pub fn build(
+        &mut self,
+        context: &mut dyn GraphicsContext,
+        resources: &GpuResources,
+        vertex_shader: &Shader,
+        fragment_shader: &Shader,
+    ) -> Result<(), GraphicsError> {
+        let descriptor = &self.descriptor;
+        let handle = context.create_pipeline(&PipelineDescriptor {
+            vertex_shader: &vertex_shader.spirv,
+            fragment_shader: &fragment_shader.spirv,
+            vertex_layout: &descriptor.vertex_layout,
+            topology: descriptor.topology,
+            polygon_mode: descriptor.polygon_mode,
+            cull_mode: descriptor.cull_mode,
+            blend_mode: descriptor.blend_mode,
+            depth_stencil: descriptor.depth_stencil.clone(),
+        })?;
+
+        self.gpu = Some(resources.track_pipeline(handle));
+        Ok(())
+    }
+}
+
+impl Asset for ShaderPipeline {}
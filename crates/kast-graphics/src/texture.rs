@@ -0,0 +1,147 @@
+use kast_resources::{Asset, AssetError, AssetLoader, LoadContext, StrongHandle};
+use kast_texture::{ColorSpace, Image};
+
+use crate::{
+    GraphicsContext, GraphicsError, descriptors::TextureDescriptor, enums::TextureFormat,
+    handle::*, resources::GpuResources,
+};
+
+/// A 2D image, decoded on the CPU and uploaded to the GPU on demand.
+///
+/// Loading one through the `AssetServer` only decodes it; the renderer then
+/// uploads every freshly loaded texture on the main thread. The GPU copy is
+/// reference counted, so it is destroyed along with the asset.
+pub struct Texture {
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+    /// 1 unless mipmaps were generated.
+    pub mip_levels: u32,
+    /// Tightly packed rows of pixels, top to bottom, for each mip level in
+    /// turn, largest first.
+    pub data: Vec<u8>,
+    gpu: Option<StrongHandle<GpuTexture>>,
+}
+
+impl Texture {
+    pub fn new(width: u32, height: u32, format: TextureFormat, data: Vec<u8>) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            mip_levels: 1,
+            data,
+            gpu: None,
+        }
+    }
+
+    /// A texture holding `image`, with its mip levels.
+    pub fn from_image(image: Image) -> Self {
+        let format = match image.color_space {
+            ColorSpace::Srgb => TextureFormat::Rgba8Srgb,
+            ColorSpace::Linear => TextureFormat::Rgba8Unorm,
+        };
+
+        let mut texture = Self::new(image.width, image.height, format, image.data);
+        texture.mip_levels = image.mip_levels;
+        texture
+    }
+
+    /// Decodes a PNG of any color type into 8-bit sRGB RGBA.
+    pub fn from_png(bytes: &[u8]) -> Result<Self, AssetError> {
+        Image::from_png(bytes)
+            .map(Self::from_image)
+            .map_err(|error| AssetError::Decode(error.to_string()))
+    }
+
+    /// Decodes an image baked with its mipmaps by `Image::to_ktex`.
+    pub fn from_ktex(bytes: &[u8]) -> Result<Self, AssetError> {
+        Image::from_ktex(bytes)
+            .map(Self::from_image)
+            .map_err(|error| AssetError::Decode(error.to_string()))
+    }
+
+    /// The GPU texture, once `upload` has run.
+    pub fn handle(&self) -> Option<TextureHandle> {
+        self.gpu.as_ref().map(StrongHandle::id)
+    }
+
+    pub fn is_uploaded(&self) -> bool {
+        self.gpu.is_some()
+    }
+
+    /// Creates the GPU texture and copies the pixels into it, replacing any
+    /// previous upload. The old GPU texture is released once unused.
+    pub fn upload(
+        &mut self,
+        context: &mut dyn GraphicsContext,
+        resources: &GpuResources,
+    ) -> Result<(), GraphicsError> {
+        let handle = context.create_texture(&TextureDescriptor {
+            width: self.width,
+            height: self.height,
+            format: self.format,
+            mip_levels: self.mip_levels,
+        })?;
+        let handle = resources.track_texture(handle);
+        context.upload_texture(handle.id(), &self.data)?;
+
+        self.gpu = Some(handle);
+        Ok(())
+    }
+}
+
+impl Asset for Texture {}
+
+pub struct TextureSettings {
+    /// Whether the pixels of a PNG are sRGB-encoded colors, as opposed to
+    /// linear data such as normal maps. PNGs named like `*.linear.png` are
+    /// linear either way.
+    ///
+    /// KTEX files record the color space they were baked in, which their
+    /// mipmaps depend on, so this doesn't apply to them.
+    pub srgb: bool,
+}
+
+impl Default for TextureSettings {
+    fn default() -> Self {
+        Self { srgb: true }
+    }
+}
+
+/// Loads PNG files, and textures baked with their mipmaps into KTEX files,
+/// into `Texture`s.
+pub struct TextureLoader;
+
+impl AssetLoader for TextureLoader {
+    type Asset = Texture;
+    type Settings = TextureSettings;
+
+    fn extensions(&self) -> &[&str] {
+        &["png", "ktex"]
+    }
+
+    fn load(
+        &self,
+        bytes: &[u8],
+        settings: &TextureSettings,
+        context: &mut LoadContext,
+    ) -> Result<Texture, AssetError> {
+        let path = context.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("ktex"))
+        {
+            return Texture::from_ktex(bytes);
+        }
+
+        let mut image =
+            Image::from_png(bytes).map_err(|error| AssetError::Decode(error.to_string()))?;
+        image.color_space = if settings.srgb {
+            ColorSpace::from_path(path)
+        } else {
+            ColorSpace::Linear
+        };
+        Ok(Texture::from_image(image))
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{Mat4, Sphere, Vec3};
+
+/// An axis-aligned bounding box, from its `min` to its `max` corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub const fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center_half_extents(center: Vec3, half_extents: Vec3) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    /// The smallest box around `points`, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        Some(points.fold(Self::new(first, first), |aabb, point| {
+            Self::new(aabb.min.min(point), aabb.max.max(point))
+        }))
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        self.size() * 0.5
+    }
+
+    /// Inclusive of the faces.
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+            && point.z >= self.min.z
+            && point.z <= self.max.z
+    }
+
+    /// Whether the boxes overlap; touching faces count.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+            && self.min.z <= other.max.z
+            && self.max.z >= other.min.z
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        sphere.intersects_aabb(self)
+    }
+
+    /// The smallest box containing both.
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// The overlap of both boxes, or `None` if they don't intersect.
+    pub fn intersection(&self, other: &Aabb) -> Option<Self> {
+        self.intersects(other)
+            .then(|| Self::new(self.min.max(other.min), self.max.min(other.max)))
+    }
+
+    /// Grows every face outwards by `amount`, or shrinks it if negative.
+    pub fn expand(&self, amount: f32) -> Self {
+        Self::new(
+            self.min - Vec3::splat(amount),
+            self.max + Vec3::splat(amount),
+        )
+    }
+
+    /// The point of the box nearest to `point`, which is `point` itself if
+    /// it is inside.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        point.clamp(self.min, self.max)
+    }
+
+    pub fn distance_squared_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance_squared(point)
+    }
+
+    /// The box around this one after `transform`, such as a mesh's local
+    /// bounds moved into world space. Rotations make it looser than the
+    /// transformed shape.
+    pub fn transformed(&self, transform: &Mat4) -> Self {
+        let center = transform.transform_point3(self.center());
+        let half = self.half_extents();
+
+        // Each world axis spans the absolute contributions of every local
+        // axis.
+        let x = transform.x_axis.truncate().abs() * half.x;
+        let y = transform.y_axis.truncate().abs() * half.y;
+        let z = transform.z_axis.truncate().abs() * half.z;
+
+        Self::from_center_half_extents(center, x + y + z)
+    }
+}
@@ -0,0 +1,28 @@
+//! The kernels behind the hot `Vec4`, `Mat4` and `Quat` operations.
+//!
+//! `sse2` is used on x86 targets with SSE2, which includes every x86_64
+//! target; `scalar` everywhere else, or anywhere when the `scalar-math`
+//! feature is enabled. Both are public so they can be tested against each
+//! other, but they aren't meant to be called directly.
+
+pub mod scalar;
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2"
+))]
+pub mod sse2;
+
+#[cfg(any(
+    feature = "scalar-math",
+    not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ))
+))]
+pub(crate) use scalar as active;
+#[cfg(all(
+    not(feature = "scalar-math"),
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2"
+))]
+pub(crate) use sse2 as active;
@@ -0,0 +1,166 @@
+//! Portable kernels, one component at a time.
+
+use crate::{Mat4, Quat, Vec3, Vec4};
+
+#[inline]
+pub fn vec4_add(a: Vec4, b: Vec4) -> Vec4 {
+    Vec4::new(a.x + b.x, a.y + b.y, a.z + b.z, a.w + b.w)
+}
+
+#[inline]
+pub fn vec4_sub(a: Vec4, b: Vec4) -> Vec4 {
+    Vec4::new(a.x - b.x, a.y - b.y, a.z - b.z, a.w - b.w)
+}
+
+#[inline]
+pub fn vec4_mul(a: Vec4, b: Vec4) -> Vec4 {
+    Vec4::new(a.x * b.x, a.y * b.y, a.z * b.z, a.w * b.w)
+}
+
+#[inline]
+pub fn vec4_div(a: Vec4, b: Vec4) -> Vec4 {
+    Vec4::new(a.x / b.x, a.y / b.y, a.z / b.z, a.w / b.w)
+}
+
+#[inline]
+pub fn vec4_dot(a: Vec4, b: Vec4) -> f32 {
+    a.x * b.x + a.y * b.y + a.z * b.z + a.w * b.w
+}
+
+#[inline]
+pub fn mat4_mul_vec4(m: &Mat4, v: Vec4) -> Vec4 {
+    let (x, y, z, w) = (m.x_axis, m.y_axis, m.z_axis, m.w_axis);
+    Vec4::new(
+        x.x * v.x + y.x * v.y + z.x * v.z + w.x * v.w,
+        x.y * v.x + y.y * v.y + z.y * v.z + w.y * v.w,
+        x.z * v.x + y.z * v.y + z.z * v.z + w.z * v.w,
+        x.w * v.x + y.w * v.y + z.w * v.z + w.w * v.w,
+    )
+}
+
+#[inline]
+pub fn mat4_mul(a: &Mat4, b: &Mat4) -> Mat4 {
+    Mat4::from_cols(
+        mat4_mul_vec4(a, b.x_axis),
+        mat4_mul_vec4(a, b.y_axis),
+        mat4_mul_vec4(a, b.z_axis),
+        mat4_mul_vec4(a, b.w_axis),
+    )
+}
+
+#[inline]
+pub fn mat4_transpose(m: &Mat4) -> Mat4 {
+    let [x, y, z, w] = m.to_cols_array_2d();
+    Mat4::from_cols_array_2d(&[
+        [x[0], y[0], z[0], w[0]],
+        [x[1], y[1], z[1], w[1]],
+        [x[2], y[2], z[2], w[2]],
+        [x[3], y[3], z[3], w[3]],
+    ])
+}
+
+#[inline]
+pub fn mat4_determinant(m: &Mat4) -> f32 {
+    let (s, c) = minors(m);
+    s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+}
+
+/// Cofactor expansion; `None` if the matrix is singular.
+#[inline]
+pub fn mat4_inverse(matrix: &Mat4) -> Option<Mat4> {
+    let det = mat4_determinant(matrix);
+    if det == 0.0 || !det.is_finite() {
+        return None;
+    }
+
+    let (s, c) = minors(matrix);
+    let m = matrix.to_cols_array_2d();
+    let adjugate = [
+        [
+            m[1][1] * c[5] - m[1][2] * c[4] + m[1][3] * c[3],
+            -m[0][1] * c[5] + m[0][2] * c[4] - m[0][3] * c[3],
+            m[3][1] * s[5] - m[3][2] * s[4] + m[3][3] * s[3],
+            -m[2][1] * s[5] + m[2][2] * s[4] - m[2][3] * s[3],
+        ],
+        [
+            -m[1][0] * c[5] + m[1][2] * c[2] - m[1][3] * c[1],
+            m[0][0] * c[5] - m[0][2] * c[2] + m[0][3] * c[1],
+            -m[3][0] * s[5] + m[3][2] * s[2] - m[3][3] * s[1],
+            m[2][0] * s[5] - m[2][2] * s[2] + m[2][3] * s[1],
+        ],
+        [
+            m[1][0] * c[4] - m[1][1] * c[2] + m[1][3] * c[0],
+            -m[0][0] * c[4] + m[0][1] * c[2] - m[0][3] * c[0],
+            m[3][0] * s[4] - m[3][1] * s[2] + m[3][3] * s[0],
+            -m[2][0] * s[4] + m[2][1] * s[2] - m[2][3] * s[0],
+        ],
+        [
+            -m[1][0] * c[3] + m[1][1] * c[1] - m[1][2] * c[0],
+            m[0][0] * c[3] - m[0][1] * c[1] + m[0][2] * c[0],
+            -m[3][0] * s[3] + m[3][1] * s[1] - m[3][2] * s[0],
+            m[2][0] * s[3] - m[2][1] * s[1] + m[2][2] * s[0],
+        ],
+    ];
+
+    let inv_det = 1.0 / det;
+    Some(Mat4::from_cols_array_2d(
+        &adjugate.map(|column| column.map(|value| value * inv_det)),
+    ))
+}
+
+/// The 2x2 minors of the first two and last two columns, from which the
+/// determinant and the inverse are built.
+fn minors(m: &Mat4) -> ([f32; 6], [f32; 6]) {
+    let m = m.to_cols_array_2d();
+    let s = [
+        m[0][0] * m[1][1] - m[1][0] * m[0][1],
+        m[0][0] * m[1][2] - m[1][0] * m[0][2],
+        m[0][0] * m[1][3] - m[1][0] * m[0][3],
+        m[0][1] * m[1][2] - m[1][1] * m[0][2],
+        m[0][1] * m[1][3] - m[1][1] * m[0][3],
+        m[0][2] * m[1][3] - m[1][2] * m[0][3],
+    ];
+    let c = [
+        m[2][0] * m[3][1] - m[3][0] * m[2][1],
+        m[2][0] * m[3][2] - m[3][0] * m[2][2],
+        m[2][0] * m[3][3] - m[3][0] * m[2][3],
+        m[2][1] * m[3][2] - m[3][1] * m[2][2],
+        m[2][1] * m[3][3] - m[3][1] * m[2][3],
+        m[2][2] * m[3][3] - m[3][2] * m[2][3],
+    ];
+
+    (s, c)
+}
+
+/// The Hamilton product: `a * b` applies `b` first.
+#[inline]
+pub fn quat_mul(a: Quat, b: Quat) -> Quat {
+    Quat::from_xyzw(
+        a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+        a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+        a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+        a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+    )
+}
+
+/// Rotates `v` by the unit quaternion `q`.
+#[inline]
+pub fn quat_rotate(q: Quat, v: Vec3) -> Vec3 {
+    // v + 2w(u x v) + 2u x (u x v), with u the vector part of `q`.
+    let t = Vec3::new(
+        2.0 * (q.y * v.z - q.z * v.y),
+        2.0 * (q.z * v.x - q.x * v.z),
+        2.0 * (q.x * v.y - q.y * v.x),
+    );
+
+    Vec3::new(
+        v.x + q.w * t.x + (q.y * t.z - q.z * t.y),
+        v.y + q.w * t.y + (q.z * t.x - q.x * t.z),
+        v.z + q.w * t.z + (q.x * t.y - q.y * t.x),
+    )
+}
+
+#[inline]
+pub fn quat_dot(a: Quat, b: Quat) -> f32 {
+    a.x * b.x + a.y * b.y + a.z * b.z + a.w * b.w
+}
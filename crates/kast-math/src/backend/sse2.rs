@@ -0,0 +1,319 @@
+//! SSE2 kernels. Values are loaded into registers for each call, so the math
+//! types keep their plain `#[repr(C)]` layout.
+
+use crate::{Mat4, Quat, Vec3, Vec4};
+
+/// Safe wrappers around the `#[target_feature]` kernels below.
+macro_rules! export {
+    ($(fn $name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            #[inline]
+            pub fn $name($($arg: $ty),*) -> $ret {
+                // SAFETY: this module is only compiled when SSE2 is enabled
+                // for the whole target.
+                unsafe { kernels::$name($($arg),*) }
+            }
+        )*
+    };
+}
+
+export! {
+    fn vec4_add(a: Vec4, b: Vec4) -> Vec4;
+    fn vec4_sub(a: Vec4, b: Vec4) -> Vec4;
+    fn vec4_mul(a: Vec4, b: Vec4) -> Vec4;
+    fn vec4_div(a: Vec4, b: Vec4) -> Vec4;
+    fn vec4_dot(a: Vec4, b: Vec4) -> f32;
+    fn mat4_mul_vec4(m: &Mat4, v: Vec4) -> Vec4;
+    fn mat4_mul(a: &Mat4, b: &Mat4) -> Mat4;
+    fn mat4_transpose(m: &Mat4) -> Mat4;
+    fn mat4_inverse(m: &Mat4) -> Option<Mat4>;
+    fn quat_mul(a: Quat, b: Quat) -> Quat;
+    fn quat_rotate(q: Quat, v: Vec3) -> Vec3;
+    fn quat_dot(a: Quat, b: Quat) -> f32;
+}
+
+mod kernels {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    use crate::{Mat4, Quat, Vec3, Vec4};
+
+    /// The `_mm_shuffle_ps` immediate, like C's `_MM_SHUFFLE`: each argument
+    /// is the source lane for result lanes 3, 2, 1 and 0 in turn.
+    const fn mask(w: i32, z: i32, y: i32, x: i32) -> i32 {
+        (w << 6) | (z << 4) | (y << 2) | x
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn load(v: Vec4) -> __m128 {
+        _mm_setr_ps(v.x, v.y, v.z, v.w)
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn store(v: __m128) -> Vec4 {
+        let mut out = Vec4::ZERO;
+        // SAFETY: `Vec4` is `#[repr(C)]` with four `f32`s, and `_mm_storeu_ps`
+        // doesn't require alignment.
+        unsafe { _mm_storeu_ps((&mut out as *mut Vec4).cast(), v) };
+        out
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn load_quat(q: Quat) -> __m128 {
+        _mm_setr_ps(q.x, q.y, q.z, q.w)
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn store_quat(v: __m128) -> Quat {
+        let v = store(v);
+        Quat::from_xyzw(v.x, v.y, v.z, v.w)
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn load_cols(m: &Mat4) -> [__m128; 4] {
+        [
+            load(m.x_axis),
+            load(m.y_axis),
+            load(m.z_axis),
+            load(m.w_axis),
+        ]
+    }
+
+    /// Every lane holds `lane` of `v`.
+    macro_rules! splat {
+        ($v:expr, $lane:literal) => {
+            _mm_shuffle_ps::<{ mask($lane, $lane, $lane, $lane) }>($v, $v)
+        };
+    }
+
+    /// The dot product in every lane, summed as `(x + y) + (z + w)`.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn dot4(a: __m128, b: __m128) -> __m128 {
+        let products = _mm_mul_ps(a, b);
+        let pairs = _mm_add_ps(
+            products,
+            _mm_shuffle_ps::<{ mask(2, 3, 0, 1) }>(products, products),
+        );
+        _mm_add_ps(pairs, _mm_shuffle_ps::<{ mask(1, 0, 3, 2) }>(pairs, pairs))
+    }
+
+    /// `a.yzx * b.zxy - a.zxy * b.yzx`, ignoring `w`.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn cross3(a: __m128, b: __m128) -> __m128 {
+        let a_yzx = _mm_shuffle_ps::<{ mask(3, 0, 2, 1) }>(a, a);
+        let a_zxy = _mm_shuffle_ps::<{ mask(3, 1, 0, 2) }>(a, a);
+        let b_yzx = _mm_shuffle_ps::<{ mask(3, 0, 2, 1) }>(b, b);
+        let b_zxy = _mm_shuffle_ps::<{ mask(3, 1, 0, 2) }>(b, b);
+        _mm_sub_ps(_mm_mul_ps(a_yzx, b_zxy), _mm_mul_ps(a_zxy, b_yzx))
+    }
+
+    /// `cols * v`, accumulated in the same order as the scalar kernel.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    fn transform(cols: &[__m128; 4], v: __m128) -> __m128 {
+        let result = _mm_mul_ps(cols[0], splat!(v, 0));
+        let result = _mm_add_ps(result, _mm_mul_ps(cols[1], splat!(v, 1)));
+        let result = _mm_add_ps(result, _mm_mul_ps(cols[2], splat!(v, 2)));
+        _mm_add_ps(result, _mm_mul_ps(cols[3], splat!(v, 3)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn vec4_add(a: Vec4, b: Vec4) -> Vec4 {
+        store(_mm_add_ps(load(a), load(b)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn vec4_sub(a: Vec4, b: Vec4) -> Vec4 {
+        store(_mm_sub_ps(load(a), load(b)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn vec4_mul(a: Vec4, b: Vec4) -> Vec4 {
+        store(_mm_mul_ps(load(a), load(b)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn vec4_div(a: Vec4, b: Vec4) -> Vec4 {
+        store(_mm_div_ps(load(a), load(b)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn vec4_dot(a: Vec4, b: Vec4) -> f32 {
+        _mm_cvtss_f32(dot4(load(a), load(b)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn mat4_mul_vec4(m: &Mat4, v: Vec4) -> Vec4 {
+        store(transform(&load_cols(m), load(v)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn mat4_mul(a: &Mat4, b: &Mat4) -> Mat4 {
+        let cols = load_cols(a);
+        Mat4::from_cols(
+            store(transform(&cols, load(b.x_axis))),
+            store(transform(&cols, load(b.y_axis))),
+            store(transform(&cols, load(b.z_axis))),
+            store(transform(&cols, load(b.w_axis))),
+        )
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn mat4_transpose(m: &Mat4) -> Mat4 {
+        let [x, y, z, w] = load_cols(m);
+        let xy_low = _mm_unpacklo_ps(x, y);
+        let zw_low = _mm_unpacklo_ps(z, w);
+        let xy_high = _mm_unpackhi_ps(x, y);
+        let zw_high = _mm_unpackhi_ps(z, w);
+
+        Mat4::from_cols(
+            store(_mm_movelh_ps(xy_low, zw_low)),
+            store(_mm_movehl_ps(zw_low, xy_low)),
+            store(_mm_movelh_ps(xy_high, zw_high)),
+            store(_mm_movehl_ps(zw_high, xy_high)),
+        )
+    }
+
+    /// The 2x2 sub-determinants of the last two columns paired with the middle
+    /// two, as used by `mat4_inverse`. `$a`/`$b` pick the rows.
+    macro_rules! factor {
+        ($x:expr, $y:expr, $z:expr, $w:expr, $a:literal, $b:literal) => {{
+            let wz_a = _mm_shuffle_ps::<{ mask($a, $a, $a, $a) }>($w, $z);
+            let wz_b = _mm_shuffle_ps::<{ mask($b, $b, $b, $b) }>($w, $z);
+
+            let zy_b = _mm_shuffle_ps::<{ mask($b, $b, $b, $b) }>($z, $y);
+            let wz_a = _mm_shuffle_ps::<{ mask(2, 0, 0, 0) }>(wz_a, wz_a);
+            let wz_b = _mm_shuffle_ps::<{ mask(2, 0, 0, 0) }>(wz_b, wz_b);
+            let zy_a = _mm_shuffle_ps::<{ mask($a, $a, $a, $a) }>($z, $y);
+
+            _mm_sub_ps(_mm_mul_ps(zy_b, wz_a), _mm_mul_ps(wz_b, zy_a))
+        }};
+    }
+
+    /// Cofactor expansion laid out over the four lanes; `None` if the matrix is
+    /// singular.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn mat4_inverse(m: &Mat4) -> Option<Mat4> {
+        let [x, y, z, w] = load_cols(m);
+
+        let fac0 = factor!(x, y, z, w, 3, 2);
+        let fac1 = factor!(x, y, z, w, 3, 1);
+        let fac2 = factor!(x, y, z, w, 2, 1);
+        let fac3 = factor!(x, y, z, w, 3, 0);
+        let fac4 = factor!(x, y, z, w, 2, 0);
+        let fac5 = factor!(x, y, z, w, 1, 0);
+
+        let sign_a = _mm_setr_ps(-1.0, 1.0, -1.0, 1.0);
+        let sign_b = _mm_setr_ps(1.0, -1.0, 1.0, -1.0);
+
+        // Row `i` of the first two columns, as `[y, x, x, x]`.
+        let row = |yx: __m128| _mm_shuffle_ps::<{ mask(2, 2, 2, 0) }>(yx, yx);
+        let vec0 = row(_mm_shuffle_ps::<{ mask(0, 0, 0, 0) }>(y, x));
+        let vec1 = row(_mm_shuffle_ps::<{ mask(1, 1, 1, 1) }>(y, x));
+        let vec2 = row(_mm_shuffle_ps::<{ mask(2, 2, 2, 2) }>(y, x));
+        let vec3 = row(_mm_shuffle_ps::<{ mask(3, 3, 3, 3) }>(y, x));
+
+        let combine = |a: __m128, fa: __m128, b: __m128, fb: __m128, c: __m128, fc: __m128| {
+            _mm_add_ps(
+                _mm_sub_ps(_mm_mul_ps(a, fa), _mm_mul_ps(b, fb)),
+                _mm_mul_ps(c, fc),
+            )
+        };
+        let inv0 = _mm_mul_ps(sign_b, combine(vec1, fac0, vec2, fac1, vec3, fac2));
+        let inv1 = _mm_mul_ps(sign_a, combine(vec0, fac0, vec2, fac3, vec3, fac4));
+        let inv2 = _mm_mul_ps(sign_b, combine(vec0, fac1, vec1, fac3, vec3, fac5));
+        let inv3 = _mm_mul_ps(sign_a, combine(vec0, fac2, vec1, fac4, vec2, fac5));
+
+        // The first row of the adjugate, dotted with the first column.
+        let row0 = _mm_shuffle_ps::<{ mask(0, 0, 0, 0) }>(inv0, inv1);
+        let row1 = _mm_shuffle_ps::<{ mask(0, 0, 0, 0) }>(inv2, inv3);
+        let row2 = _mm_shuffle_ps::<{ mask(2, 0, 2, 0) }>(row0, row1);
+
+        let det = _mm_cvtss_f32(dot4(x, row2));
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let inv_det = _mm_set1_ps(1.0 / det);
+        Some(Mat4::from_cols(
+            store(_mm_mul_ps(inv0, inv_det)),
+            store(_mm_mul_ps(inv1, inv_det)),
+            store(_mm_mul_ps(inv2, inv_det)),
+            store(_mm_mul_ps(inv3, inv_det)),
+        ))
+    }
+
+    /// The Hamilton product: `a * b` applies `b` first.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn quat_mul(a: Quat, b: Quat) -> Quat {
+        let a = load_quat(a);
+        let b = load_quat(b);
+
+        let result = _mm_mul_ps(splat!(a, 3), b);
+        let b_wzyx = _mm_shuffle_ps::<{ mask(0, 1, 2, 3) }>(b, b);
+        let result = _mm_add_ps(
+            result,
+            _mm_mul_ps(
+                splat!(a, 0),
+                _mm_mul_ps(b_wzyx, _mm_setr_ps(1.0, -1.0, 1.0, -1.0)),
+            ),
+        );
+        let b_zwxy = _mm_shuffle_ps::<{ mask(1, 0, 3, 2) }>(b, b);
+        let result = _mm_add_ps(
+            result,
+            _mm_mul_ps(
+                splat!(a, 1),
+                _mm_mul_ps(b_zwxy, _mm_setr_ps(1.0, 1.0, -1.0, -1.0)),
+            ),
+        );
+        let b_yxwz = _mm_shuffle_ps::<{ mask(2, 3, 0, 1) }>(b, b);
+        let result = _mm_add_ps(
+            result,
+            _mm_mul_ps(
+                splat!(a, 2),
+                _mm_mul_ps(b_yxwz, _mm_setr_ps(-1.0, 1.0, 1.0, -1.0)),
+            ),
+        );
+
+        store_quat(result)
+    }
+
+    /// Rotates `v` by the unit quaternion `q`.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn quat_rotate(q: Quat, v: Vec3) -> Vec3 {
+        let q = load_quat(q);
+        let v = _mm_setr_ps(v.x, v.y, v.z, 0.0);
+
+        // v + 2w(u x v) + 2u x (u x v), with u the vector part of `q`.
+        let t = _mm_mul_ps(cross3(q, v), _mm_set1_ps(2.0));
+        let result = _mm_add_ps(_mm_add_ps(v, _mm_mul_ps(splat!(q, 3), t)), cross3(q, t));
+
+        store(result).truncate()
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    pub(super) fn quat_dot(a: Quat, b: Quat) -> f32 {
+        _mm_cvtss_f32(dot4(load_quat(a), load_quat(b)))
+    }
+}
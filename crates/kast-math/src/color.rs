@@ -0,0 +1,343 @@
+use crate::{Vec3, Vec4};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorError {
+    /// Not `RGB`, `RGBA`, `RRGGBB` or `RRGGBBAA` hex digits, optionally
+    /// after a `#`.
+    InvalidHex(String),
+}
+
+impl core::fmt::Display for ColorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl core::error::Error for ColorError {}
+
+/// An RGBA color in linear space, with straight (not premultiplied) alpha.
+///
+/// Linear values are what shaders, blending and lighting work with, so
+/// they are what `Color` stores and what `to_array` returns for vertex data,
+/// uniforms and clear colors. Colors picked in an editor or written as hex
+/// are usually sRGB; build those with `srgb`, `srgb_u8` or `hex`.
+///
+/// Components aren't clamped, so HDR values above 1 are allowed.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub const WHITE: Self = Self::rgb(1.0, 1.0, 1.0);
+    pub const BLACK: Self = Self::rgb(0.0, 0.0, 0.0);
+    pub const TRANSPARENT: Self = Self::rgba(0.0, 0.0, 0.0, 0.0);
+    pub const RED: Self = Self::rgb(1.0, 0.0, 0.0);
+    pub const GREEN: Self = Self::rgb(0.0, 1.0, 0.0);
+    pub const BLUE: Self = Self::rgb(0.0, 0.0, 1.0);
+    pub const YELLOW: Self = Self::rgb(1.0, 1.0, 0.0);
+    pub const CYAN: Self = Self::rgb(0.0, 1.0, 1.0);
+    pub const MAGENTA: Self = Self::rgb(1.0, 0.0, 1.0);
+
+    /// An opaque color from linear components.
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self::rgba(r, g, b, 1.0)
+    }
+
+    /// A color from linear components.
+    pub const fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// An opaque color from sRGB components in `0..=1`.
+    pub fn srgb(r: f32, g: f32, b: f32) -> Self {
+        Self::srgba(r, g, b, 1.0)
+    }
+
+    /// A color from sRGB components in `0..=1`. Alpha is always linear.
+    pub fn srgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::rgba(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a)
+    }
+
+    /// A color from 8-bit sRGB components, as stored in `Rgba8Srgb`
+    /// textures and image files.
+    pub fn srgb_u8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::srgba(unorm(r), unorm(g), unorm(b), unorm(a))
+    }
+
+    /// A color from 8-bit linear components, as stored in `Rgba8Unorm`
+    /// textures.
+    pub fn linear_u8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::rgba(unorm(r), unorm(g), unorm(b), unorm(a))
+    }
+
+    /// Parses sRGB hex as written in CSS and design tools: `RGB`, `RGBA`,
+    /// `RRGGBB` or `RRGGBBAA`, with or without a leading `#`.
+    pub fn hex(hex: &str) -> Result<Self, ColorError> {
+        let invalid = || ColorError::InvalidHex(hex.to_owned());
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let nibble = |i: usize| u8::from_str_radix(&digits[i..i + 1], 16).map_err(|_| invalid());
+        let byte = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid());
+
+        match digits.len() {
+            3 | 4 => {
+                // Each digit is doubled: `f80` is `ff8800`.
+                let alpha = if digits.len() == 4 { nibble(3)? } else { 0xf };
+                Ok(Self::srgb_u8(
+                    nibble(0)? * 0x11,
+                    nibble(1)? * 0x11,
+                    nibble(2)? * 0x11,
+                    alpha * 0x11,
+                ))
+            }
+            6 | 8 => {
+                let alpha = if digits.len() == 8 { byte(6)? } else { 0xff };
+                Ok(Self::srgb_u8(byte(0)?, byte(2)?, byte(4)?, alpha))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// A color from sRGB packed as `0xRRGGBBAA`.
+    pub fn from_u32(packed: u32) -> Self {
+        let [r, g, b, a] = packed.to_be_bytes();
+        Self::srgb_u8(r, g, b, a)
+    }
+
+    /// An opaque color from hue in degrees, and saturation and value in
+    /// `0..=1`. Like in color pickers, these describe the sRGB color.
+    pub fn hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let chroma = value * saturation;
+        let [r, g, b] = hue_to_rgb(hue, chroma, value - chroma);
+        Self::srgb(r, g, b)
+    }
+
+    /// An opaque color from hue in degrees, and saturation and lightness in
+    /// `0..=1`. Like in CSS, these describe the sRGB color.
+    pub fn hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let [r, g, b] = hue_to_rgb(hue, chroma, lightness - chroma * 0.5);
+        Self::srgb(r, g, b)
+    }
+
+    pub const fn with_alpha(self, a: f32) -> Self {
+        Self::rgba(self.r, self.g, self.b, a)
+    }
+
+    /// The linear components as `[r, g, b, a]`.
+    pub const fn to_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// The sRGB components as `[r, g, b, a]`. Alpha stays linear.
+    pub fn to_srgba(self) -> [f32; 4] {
+        [
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+            self.a,
+        ]
+    }
+
+    /// Clamped 8-bit sRGB components, for `Rgba8Srgb` textures and image
+    /// files.
+    pub fn to_srgb_u8(self) -> [u8; 4] {
+        self.to_srgba().map(to_unorm)
+    }
+
+    /// Clamped 8-bit linear components, for `Rgba8Unorm` textures.
+    pub fn to_linear_u8(self) -> [u8; 4] {
+        self.to_array().map(to_unorm)
+    }
+
+    /// Clamped sRGB packed as `0xRRGGBBAA`, the inverse of `from_u32`.
+    pub fn to_u32(self) -> u32 {
+        u32::from_be_bytes(self.to_srgb_u8())
+    }
+
+    /// Clamped sRGB as `#rrggbbaa`.
+    pub fn to_hex(self) -> String {
+        format!("#{:08x}", self.to_u32())
+    }
+
+    /// Hue in degrees, saturation and value of the sRGB color.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (hue, min, max) = self.hue();
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (hue, saturation, max)
+    }
+
+    /// Hue in degrees, saturation and lightness of the sRGB color.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (hue, min, max) = self.hue();
+        let lightness = (max + min) * 0.5;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        (hue, saturation, lightness)
+    }
+
+    /// The hue in degrees and the smallest and largest sRGB components.
+    fn hue(self) -> (f32, f32, f32) {
+        let [r, g, b, _] = self.to_srgba();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let hue = if chroma == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / chroma + 2.0)
+        } else {
+            60.0 * ((r - g) / chroma + 4.0)
+        };
+
+        (hue, min, max)
+    }
+
+    /// The color with its components multiplied by alpha, as blending with
+    /// `One, OneMinusSrcAlpha` expects.
+    pub fn premultiplied(self) -> Self {
+        Self::rgba(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// Undoes `premultiplied`. Fully transparent colors stay black.
+    pub fn unpremultiplied(self) -> Self {
+        if self.a == 0.0 {
+            return Self::TRANSPARENT;
+        }
+        Self::rgba(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
+    /// Perceived brightness of the linear color, from 0 to 1.
+    pub fn luminance(self) -> f32 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Self::WHITE
+    }
+}
+
+fn unorm(value: u8) -> f32 {
+    value as f32 / 255.0
+}
+
+fn to_unorm(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The sRGB components for `hue` in degrees, given the chroma and the amount
+/// added to every component.
+fn hue_to_rgb(hue: f32, chroma: f32, offset: f32) -> [f32; 3] {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let [r, g, b] = match sector as u32 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    };
+    [r + offset, g + offset, b + offset]
+}
+
+/// Tints component-wise, such as a texture color by a material color.
+impl std::ops::Mul for Color {
+    type Output = Self;
+
+    fn mul(self, rhs: Color) -> Self {
+        Self::rgba(
+            self.r * rhs.r,
+            self.g * rhs.g,
+            self.b * rhs.b,
+            self.a * rhs.a,
+        )
+    }
+}
+
+/// Scales the color components, leaving alpha alone.
+impl std::ops::Mul<f32> for Color {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self::rgba(self.r * rhs, self.g * rhs, self.b * rhs, self.a)
+    }
+}
+
+/// Adds every component, such as to accumulate light.
+impl std::ops::Add for Color {
+    type Output = Self;
+
+    fn add(self, rhs: Color) -> Self {
+        Self::rgba(
+            self.r + rhs.r,
+            self.g + rhs.g,
+            self.b + rhs.b,
+            self.a + rhs.a,
+        )
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> Self {
+        color.to_array()
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from([r, g, b, a]: [f32; 4]) -> Self {
+        Self::rgba(r, g, b, a)
+    }
+}
+
+impl From<Color> for Vec4 {
+    fn from(color: Color) -> Self {
+        Vec4::new(color.r, color.g, color.b, color.a)
+    }
+}
+
+impl From<Vec4> for Color {
+    fn from(v: Vec4) -> Self {
+        Self::rgba(v.x, v.y, v.z, v.w)
+    }
+}
+
+/// Opaque.
+impl From<Vec3> for Color {
+    fn from(v: Vec3) -> Self {
+        Self::rgb(v.x, v.y, v.z)
+    }
+}
@@ -0,0 +1,212 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::{Vec2, Vec3};
+
+/// A point type curves can be built from.
+pub trait CurvePoint:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self>
+{
+    fn distance(self, other: Self) -> f32;
+}
+
+impl CurvePoint for Vec2 {
+    fn distance(self, other: Self) -> f32 {
+        (self - other).length()
+    }
+}
+
+impl CurvePoint for Vec3 {
+    fn distance(self, other: Self) -> f32 {
+        (self - other).length()
+    }
+}
+
+/// A path parameterized by `t`, from 0 at its start to 1 at its end.
+///
+/// Equal steps in `t` don't cover equal distances; wrap the curve with
+/// `arc_length` to move along it at constant speed.
+pub trait Curve {
+    type Point: CurvePoint;
+
+    /// The point at `t`, which is clamped to `0..=1`.
+    fn sample(&self, t: f32) -> Self::Point;
+
+    /// The derivative at `t`: it points along the curve and its length is
+    /// the speed at which `sample` moves.
+    fn tangent(&self, t: f32) -> Self::Point;
+
+    /// Measures the curve at `samples` evenly spaced values of `t`. More
+    /// samples give more accurate distances on tight bends.
+    fn arc_length(self, samples: usize) -> ArcLength<Self>
+    where
+        Self: Sized,
+    {
+        ArcLength::new(self, samples)
+    }
+}
+
+/// A cubic Bezier curve, which starts at the first point, ends at the last,
+/// and is pulled towards the two in between.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubicBezier<P> {
+    pub points: [P; 4],
+}
+
+impl<P: CurvePoint> CubicBezier<P> {
+    pub const fn new(start: P, control_a: P, control_b: P, end: P) -> Self {
+        Self {
+            points: [start, control_a, control_b, end],
+        }
+    }
+}
+
+impl<P: CurvePoint> Curve for CubicBezier<P> {
+    type Point = P;
+
+    fn sample(&self, t: f32) -> P {
+        let t = t.clamp(0.0, 1.0);
+        let u = 1.0 - t;
+        let [p0, p1, p2, p3] = self.points;
+
+        p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+    }
+
+    fn tangent(&self, t: f32) -> P {
+        let t = t.clamp(0.0, 1.0);
+        let u = 1.0 - t;
+        let [p0, p1, p2, p3] = self.points;
+
+        (p1 - p0) * (3.0 * u * u) + (p2 - p1) * (6.0 * u * t) + (p3 - p2) * (3.0 * t * t)
+    }
+}
+
+/// A smooth curve through every one of its points, such as the waypoints
+/// of a camera rail. Each span between two points takes an equal share of
+/// `t`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatmullRom<P> {
+    pub points: Vec<P>,
+}
+
+impl<P: CurvePoint> CatmullRom<P> {
+    /// Panics if `points` is empty.
+    pub fn new(points: Vec<P>) -> Self {
+        assert!(!points.is_empty(), "a spline needs at least one point");
+        Self { points }
+    }
+
+    /// The four points around the span `t` falls in, with the ends repeated,
+    /// and the position within the span.
+    fn span(&self, t: f32) -> ([P; 4], f32) {
+        let last = self.points.len() - 1;
+        let scaled = t.clamp(0.0, 1.0) * last as f32;
+        let i = (scaled as usize).min(last.saturating_sub(1));
+
+        let point = |i: usize| self.points[i.min(last)];
+        let before = point(i.saturating_sub(1));
+        (
+            [before, point(i), point(i + 1), point(i + 2)],
+            scaled - i as f32,
+        )
+    }
+}
+
+impl<P: CurvePoint> Curve for CatmullRom<P> {
+    type Point = P;
+
+    fn sample(&self, t: f32) -> P {
+        let ([p0, p1, p2, p3], t) = self.span(t);
+
+        let a = p1 * 2.0;
+        let b = p2 - p0;
+        let c = p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3;
+        let d = p1 * 3.0 - p0 - p2 * 3.0 + p3;
+        (a + b * t + c * (t * t) + d * (t * t * t)) * 0.5
+    }
+
+    fn tangent(&self, t: f32) -> P {
+        let ([p0, p1, p2, p3], t) = self.span(t);
+
+        let b = p2 - p0;
+        let c = p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3;
+        let d = p1 * 3.0 - p0 - p2 * 3.0 + p3;
+        // Scaled by the span count, as each span only covers part of `t`.
+        let spans = (self.points.len() - 1) as f32;
+        (b + c * (2.0 * t) + d * (3.0 * t * t)) * (0.5 * spans)
+    }
+}
+
+/// A curve sampled by distance travelled rather than by `t`, so equal steps
+/// move at constant speed.
+///
+/// ```
+/// # use kast_math::{CatmullRom, Curve, Transform, Vec3};
+/// # let waypoints = vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 0.0, 1.0), Vec3::Z];
+/// # let (mut camera, speed, elapsed) = (Transform::IDENTITY, 2.0, 3.5);
+/// let rail = CatmullRom::new(waypoints).arc_length(256);
+/// camera.translation = rail.sample(speed * elapsed % rail.length());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArcLength<C> {
+    curve: C,
+    /// The distance along the curve at each sampled `t`.
+    lengths: Vec<f32>,
+}
+
+impl<C: Curve> ArcLength<C> {
+    /// Panics if `samples` is zero.
+    pub fn new(curve: C, samples: usize) -> Self {
+        assert!(samples > 0, "arc length needs at least one sample");
+
+        let mut lengths = Vec::with_capacity(samples + 1);
+        let mut total = 0.0;
+        let mut previous = curve.sample(0.0);
+        lengths.push(0.0);
+        for i in 1..=samples {
+            let point = curve.sample(i as f32 / samples as f32);
+            total += previous.distance(point);
+            lengths.push(total);
+            previous = point;
+        }
+
+        Self { curve, lengths }
+    }
+
+    pub fn curve(&self) -> &C {
+        &self.curve
+    }
+
+    /// The approximate length of the whole curve.
+    pub fn length(&self) -> f32 {
+        self.lengths[self.lengths.len() - 1]
+    }
+
+    /// The `t` at `distance` along the curve, which is clamped to the curve.
+    pub fn t_at(&self, distance: f32) -> f32 {
+        let samples = (self.lengths.len() - 1) as f32;
+        let distance = distance.clamp(0.0, self.length());
+
+        let i = self
+            .lengths
+            .partition_point(|&length| length < distance)
+            .max(1);
+        let (start, end) = (self.lengths[i - 1], self.lengths[i]);
+        let within = if end > start {
+            (distance - start) / (end - start)
+        } else {
+            0.0
+        };
+
+        ((i - 1) as f32 + within) / samples
+    }
+
+    /// The point at `distance` along the curve.
+    pub fn sample(&self, distance: f32) -> C::Point {
+        self.curve.sample(self.t_at(distance))
+    }
+
+    /// The tangent at `distance` along the curve.
+    pub fn tangent(&self, distance: f32) -> C::Point {
+        self.curve.tangent(self.t_at(distance))
+    }
+}
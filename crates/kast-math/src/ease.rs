@@ -0,0 +1,151 @@
+use core::f32::consts::PI;
+
+/// Easing curves mapping linear progress `t` in `0..=1` to eased progress,
+/// for tweens and UI animation. Every curve maps 0 to 0 and 1 to 1; `Back`
+/// and `Elastic` overshoot in between.
+///
+/// `In` curves start slow, `Out` curves end slow and `InOut` curves do both.
+///
+/// ```
+/// # use kast_math::{Ease, Vec3};
+/// # let (start, end, elapsed, duration) = (Vec3::ZERO, Vec3::X, 0.5, 1.0);
+/// let position = start.lerp(end, Ease::CubicOut.apply(elapsed / duration));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Ease {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+    QuintIn,
+    QuintOut,
+    QuintInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    CircIn,
+    CircOut,
+    CircInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+/// How far `Back` curves overshoot, about 10%.
+const BACK: f32 = 1.70158;
+
+impl Ease {
+    /// The eased progress at `t`, which is clamped to `0..=1`.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::QuadIn => t.powi(2),
+            Ease::QuadOut => out(t, |t| t.powi(2)),
+            Ease::QuadInOut => in_out(t, |t| t.powi(2)),
+            Ease::CubicIn => t.powi(3),
+            Ease::CubicOut => out(t, |t| t.powi(3)),
+            Ease::CubicInOut => in_out(t, |t| t.powi(3)),
+            Ease::QuartIn => t.powi(4),
+            Ease::QuartOut => out(t, |t| t.powi(4)),
+            Ease::QuartInOut => in_out(t, |t| t.powi(4)),
+            Ease::QuintIn => t.powi(5),
+            Ease::QuintOut => out(t, |t| t.powi(5)),
+            Ease::QuintInOut => in_out(t, |t| t.powi(5)),
+            Ease::SineIn => sine_in(t),
+            Ease::SineOut => out(t, sine_in),
+            Ease::SineInOut => in_out(t, sine_in),
+            Ease::ExpoIn => expo_in(t),
+            Ease::ExpoOut => out(t, expo_in),
+            Ease::ExpoInOut => in_out(t, expo_in),
+            Ease::CircIn => circ_in(t),
+            Ease::CircOut => out(t, circ_in),
+            Ease::CircInOut => in_out(t, circ_in),
+            Ease::BackIn => back_in(t),
+            Ease::BackOut => out(t, back_in),
+            Ease::BackInOut => in_out(t, back_in),
+            Ease::ElasticIn => elastic_in(t),
+            Ease::ElasticOut => out(t, elastic_in),
+            Ease::ElasticInOut => in_out(t, elastic_in),
+            Ease::BounceIn => out(t, bounce_out),
+            Ease::BounceOut => bounce_out(t),
+            Ease::BounceInOut => in_out(t, |t| out(t, bounce_out)),
+        }
+    }
+}
+
+/// The `Out` variant of an `In` curve: the same curve played backwards.
+fn out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    1.0 - ease_in(1.0 - t)
+}
+
+/// The `In` curve over the first half and its `Out` variant over the second.
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        ease_in(t * 2.0) * 0.5
+    } else {
+        1.0 - ease_in((1.0 - t) * 2.0) * 0.5
+    }
+}
+
+fn sine_in(t: f32) -> f32 {
+    1.0 - (t * PI * 0.5).cos()
+}
+
+fn expo_in(t: f32) -> f32 {
+    if t == 0.0 {
+        0.0
+    } else {
+        2f32.powf(10.0 * (t - 1.0))
+    }
+}
+
+fn circ_in(t: f32) -> f32 {
+    1.0 - (1.0 - t * t).sqrt()
+}
+
+fn back_in(t: f32) -> f32 {
+    t * t * ((BACK + 1.0) * t - BACK)
+}
+
+fn elastic_in(t: f32) -> f32 {
+    if t == 0.0 || t == 1.0 {
+        return t;
+    }
+
+    -(2f32.powf(10.0 * (t - 1.0))) * ((t - 1.075) * (2.0 * PI) / 0.3).sin()
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
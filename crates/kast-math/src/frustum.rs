@@ -0,0 +1,62 @@
+use crate::{Aabb, Mat4, Plane, Sphere, Vec3};
+
+/// The volume a camera sees, as six planes facing inwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near and far, in clip space order. With the
+    /// Y-down projections of `Mat4`, bottom is the top of the screen.
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes of a projection times a view matrix, with depth
+    /// from 0 to 1 as `Mat4::perspective_rh_zo` produces. Objects are then
+    /// tested in world space.
+    pub fn from_view_projection(view_projection: &Mat4) -> Self {
+        let [x, y, z, w] = [0, 1, 2, 3].map(|i| view_projection.row(i));
+
+        Self {
+            planes: [w + x, w - x, w + y, w - y, z, w - z].map(Plane::from_vec4),
+        }
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    /// Whether any part of `sphere` may be visible. Conservative near the
+    /// corners, where it can report spheres just outside.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(sphere.center) >= -sphere.radius)
+    }
+
+    /// Whether any part of `aabb` may be visible. Conservative near the
+    /// corners, where it can report boxes just outside.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            // The corner furthest along the normal is the last one to leave.
+            let corner = Vec3::new(
+                if plane.normal.x >= 0.0 {
+                    aabb.max.x
+                } else {
+                    aabb.min.x
+                },
+                if plane.normal.y >= 0.0 {
+                    aabb.max.y
+                } else {
+                    aabb.min.y
+                },
+                if plane.normal.z >= 0.0 {
+                    aabb.max.z
+                } else {
+                    aabb.min.z
+                },
+            );
+            plane.signed_distance(corner) >= 0.0
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    Aabb, Color, Frustum, IVec2, Mat3, Mat4, Plane, Quat, Ray, Rect, Sphere, Transform, UVec2,
+    Vec2, Vec3, Vec4,
+};
+
+/// Interpolation between two values: `t = 0` gives `self`, `t = 1` gives
+/// `other`. Values of `t` outside `0..=1` extrapolate where that makes sense.
+///
+/// Generic code such as tweens and curves can animate any type with it.
+pub trait Lerp: Sized {
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+/// Component-wise linear interpolation.
+macro_rules! impl_lerp_linear {
+    ($($name:ty),*) => {
+        $(
+            impl Lerp for $name {
+                fn lerp(self, other: Self, t: f32) -> Self {
+                    self + (other - self) * t
+                }
+            }
+        )*
+    };
+}
+
+impl_lerp_linear!(f32, Vec2, Vec3, Vec4, Mat3, Mat4);
+
+/// Interpolates in linear space, which blends light correctly.
+impl Lerp for Color {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Vec4::from(self).lerp(Vec4::from(other), t).into()
+    }
+}
+
+/// Rounds to the nearest integer vector.
+impl Lerp for IVec2 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self.as_vec2().lerp(other.as_vec2(), t).round().as_ivec2()
+    }
+}
+
+/// Rounds to the nearest integer vector.
+impl Lerp for UVec2 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self.as_vec2().lerp(other.as_vec2(), t).round().as_uvec2()
+    }
+}
+
+/// Spherical interpolation along the shortest path.
+impl Lerp for Quat {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self.slerp(other, t)
+    }
+}
+
+/// Interpolates translation and scale linearly and rotation spherically.
+impl Lerp for Transform {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Transform {
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation.slerp(other.rotation, t),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+}
+
+impl Lerp for Rect {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Rect::from_components(
+            self.position.lerp(other.position, t),
+            self.size.lerp(other.size, t),
+        )
+    }
+}
+
+impl Lerp for Aabb {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Aabb::new(self.min.lerp(other.min, t), self.max.lerp(other.max, t))
+    }
+}
+
+impl Lerp for Sphere {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Sphere::new(
+            self.center.lerp(other.center, t),
+            self.radius.lerp(other.radius, t),
+        )
+    }
+}
+
+/// Interpolates and renormalizes the direction.
+impl Lerp for Ray {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Ray::new(
+            self.origin.lerp(other.origin, t),
+            self.direction.lerp(other.direction, t),
+        )
+    }
+}
+
+/// Interpolates and renormalizes the normal.
+impl Lerp for Plane {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Plane::new(
+            self.normal.lerp(other.normal, t).normalize(),
+            self.distance.lerp(other.distance, t),
+        )
+    }
+}
+
+/// Interpolates each plane. Prefer interpolating the camera and rebuilding
+/// the frustum, which stays exact.
+impl Lerp for Frustum {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let mut planes = self.planes;
+        for (plane, other) in planes.iter_mut().zip(other.planes) {
+            *plane = plane.lerp(other, t);
+        }
+        Frustum { planes }
+    }
+}
@@ -1,6 +1,35 @@
+mod aabb;
+#[doc(hidden)]
+pub mod backend;
+mod color;
+mod curve;
+mod ease;
+mod frustum;
+mod lerp;
 mod matrix;
+mod noise;
+mod plane;
+mod quat;
+mod rand;
+mod ray;
 mod rect;
+mod sphere;
+mod transform;
 mod vector;
 
+pub use aabb::Aabb;
+pub use color::{Color, ColorError};
+pub use curve::{ArcLength, CatmullRom, CubicBezier, Curve, CurvePoint};
+pub use ease::Ease;
+pub use frustum::Frustum;
+pub use lerp::Lerp;
+pub use matrix::{Mat3, Mat4};
+pub use noise::{Fbm, Noise, Perlin, Simplex, ValueNoise};
+pub use plane::Plane;
+pub use quat::Quat;
+pub use rand::Rng;
+pub use ray::Ray;
 pub use rect::Rect;
-pub use vector::{Vec2, Vec3};
+pub use sphere::Sphere;
+pub use transform::Transform;
+pub use vector::{IVec2, UVec2, Vec2, Vec3, Vec4};
@@ -1 +1,586 @@
+use crate::backend::active as backend;
+use crate::{Quat, Vec3, Vec4};
 
+/// A 3x3 column-major matrix, for rotations, scales and normal matrices.
+///
+/// Vectors are treated as columns, so `a * b` applies `b` first. Note that
+/// GLSL pads each column of a `mat3` to 16 bytes in uniform and push
+/// constant blocks; upload a `Mat4` (see `Mat4::from_mat3`) when that matters.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat3 {
+    pub x_axis: Vec3,
+    pub y_axis: Vec3,
+    pub z_axis: Vec3,
+}
+
+impl Mat3 {
+    pub const fn from_cols(x_axis: Vec3, y_axis: Vec3, z_axis: Vec3) -> Self {
+        Self {
+            x_axis,
+            y_axis,
+            z_axis,
+        }
+    }
+
+    pub const ZERO: Self = Self::from_cols(Vec3::ZERO, Vec3::ZERO, Vec3::ZERO);
+
+    pub const IDENTITY: Self = Self::from_cols(
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+    );
+
+    pub const fn from_cols_array(m: &[f32; 9]) -> Self {
+        Self::from_cols(
+            Vec3::new(m[0], m[1], m[2]),
+            Vec3::new(m[3], m[4], m[5]),
+            Vec3::new(m[6], m[7], m[8]),
+        )
+    }
+
+    pub const fn to_cols_array(&self) -> [f32; 9] {
+        [
+            self.x_axis.x,
+            self.x_axis.y,
+            self.x_axis.z,
+            self.y_axis.x,
+            self.y_axis.y,
+            self.y_axis.z,
+            self.z_axis.x,
+            self.z_axis.y,
+            self.z_axis.z,
+        ]
+    }
+
+    pub const fn from_diagonal(diagonal: Vec3) -> Self {
+        Self::from_cols(
+            Vec3::new(diagonal.x, 0.0, 0.0),
+            Vec3::new(0.0, diagonal.y, 0.0),
+            Vec3::new(0.0, 0.0, diagonal.z),
+        )
+    }
+
+    /// The rotation described by the unit quaternion `rotation`.
+    pub fn from_quat(rotation: Quat) -> Self {
+        Self::from_cols(
+            rotation * Vec3::new(1.0, 0.0, 0.0),
+            rotation * Vec3::new(0.0, 1.0, 0.0),
+            rotation * Vec3::new(0.0, 0.0, 1.0),
+        )
+    }
+
+    /// The upper-left 3x3 block of `m`, i.e. its rotation and scale.
+    pub const fn from_mat4(m: Mat4) -> Self {
+        Self::from_cols(
+            m.x_axis.truncate(),
+            m.y_axis.truncate(),
+            m.z_axis.truncate(),
+        )
+    }
+
+    /// Returns the column at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than 2.
+    pub fn col(&self, index: usize) -> Vec3 {
+        match index {
+            0 => self.x_axis,
+            1 => self.y_axis,
+            2 => self.z_axis,
+            _ => panic!("Mat3 column index {index} out of bounds"),
+        }
+    }
+
+    /// Returns the row at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than 2.
+    pub fn row(&self, index: usize) -> Vec3 {
+        let component = |v: Vec3| match index {
+            0 => v.x,
+            1 => v.y,
+            2 => v.z,
+            _ => panic!("Mat3 row index {index} out of bounds"),
+        };
+
+        Vec3::new(
+            component(self.x_axis),
+            component(self.y_axis),
+            component(self.z_axis),
+        )
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_cols(self.row(0), self.row(1), self.row(2))
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.x_axis.dot(self.y_axis.cross(self.z_axis))
+    }
+
+    /// Returns `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        // The rows of the inverse are the cross products of the columns.
+        let cofactors = Self::from_cols(
+            self.y_axis.cross(self.z_axis),
+            self.z_axis.cross(self.x_axis),
+            self.x_axis.cross(self.y_axis),
+        );
+
+        Some(cofactors.transpose() * (1.0 / det))
+    }
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl std::ops::Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        self.x_axis * rhs.x + self.y_axis * rhs.y + self.z_axis * rhs.z
+    }
+}
+
+impl std::ops::Mul for Mat3 {
+    type Output = Self;
+
+    fn mul(self, rhs: Mat3) -> Self {
+        Self::from_cols(self * rhs.x_axis, self * rhs.y_axis, self * rhs.z_axis)
+    }
+}
+
+impl std::ops::Mul<f32> for Mat3 {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self::from_cols(self.x_axis * rhs, self.y_axis * rhs, self.z_axis * rhs)
+    }
+}
+
+impl std::ops::Add for Mat3 {
+    type Output = Self;
+
+    fn add(self, rhs: Mat3) -> Self {
+        Self::from_cols(
+            self.x_axis + rhs.x_axis,
+            self.y_axis + rhs.y_axis,
+            self.z_axis + rhs.z_axis,
+        )
+    }
+}
+
+impl std::ops::Sub for Mat3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Mat3) -> Self {
+        Self::from_cols(
+            self.x_axis - rhs.x_axis,
+            self.y_axis - rhs.y_axis,
+            self.z_axis - rhs.z_axis,
+        )
+    }
+}
+
+impl std::ops::MulAssign for Mat3 {
+    fn mul_assign(&mut self, rhs: Mat3) {
+        *self = *self * rhs;
+    }
+}
+
+/// A 4x4 column-major matrix, laid out the way shaders expect it.
+///
+/// Vectors are treated as columns, so `a * b` applies `b` first. The
+/// projection constructors follow Vulkan conventions: right-handed view
+/// space looking down -Z, depth mapped to `0..1`, and clip-space Y pointing
+/// down.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4 {
+    pub x_axis: Vec4,
+    pub y_axis: Vec4,
+    pub z_axis: Vec4,
+    pub w_axis: Vec4,
+}
+
+impl Mat4 {
+    pub const fn from_cols(x_axis: Vec4, y_axis: Vec4, z_axis: Vec4, w_axis: Vec4) -> Self {
+        Self {
+            x_axis,
+            y_axis,
+            z_axis,
+            w_axis,
+        }
+    }
+
+    pub const ZERO: Self = Self::from_cols(Vec4::ZERO, Vec4::ZERO, Vec4::ZERO, Vec4::ZERO);
+
+    pub const IDENTITY: Self = Self::from_cols(
+        Vec4::new(1.0, 0.0, 0.0, 0.0),
+        Vec4::new(0.0, 1.0, 0.0, 0.0),
+        Vec4::new(0.0, 0.0, 1.0, 0.0),
+        Vec4::new(0.0, 0.0, 0.0, 1.0),
+    );
+
+    pub const fn from_cols_array(m: &[f32; 16]) -> Self {
+        Self::from_cols(
+            Vec4::new(m[0], m[1], m[2], m[3]),
+            Vec4::new(m[4], m[5], m[6], m[7]),
+            Vec4::new(m[8], m[9], m[10], m[11]),
+            Vec4::new(m[12], m[13], m[14], m[15]),
+        )
+    }
+
+    pub const fn to_cols_array(&self) -> [f32; 16] {
+        let [x, y, z, w] = self.to_cols_array_2d();
+        [
+            x[0], x[1], x[2], x[3], y[0], y[1], y[2], y[3], z[0], z[1], z[2], z[3], w[0], w[1],
+            w[2], w[3],
+        ]
+    }
+
+    pub const fn from_cols_array_2d(m: &[[f32; 4]; 4]) -> Self {
+        Self::from_cols(
+            Vec4::new(m[0][0], m[0][1], m[0][2], m[0][3]),
+            Vec4::new(m[1][0], m[1][1], m[1][2], m[1][3]),
+            Vec4::new(m[2][0], m[2][1], m[2][2], m[2][3]),
+            Vec4::new(m[3][0], m[3][1], m[3][2], m[3][3]),
+        )
+    }
+
+    pub const fn to_cols_array_2d(&self) -> [[f32; 4]; 4] {
+        let (x, y, z, w) = (self.x_axis, self.y_axis, self.z_axis, self.w_axis);
+        [
+            [x.x, x.y, x.z, x.w],
+            [y.x, y.y, y.z, y.w],
+            [z.x, z.y, z.z, z.w],
+            [w.x, w.y, w.z, w.w],
+        ]
+    }
+
+    /// The matrix as native-endian bytes in column-major order, ready for a
+    /// push constant or uniform buffer.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0; 64];
+        for (chunk, value) in bytes.chunks_exact_mut(4).zip(self.to_cols_array()) {
+            chunk.copy_from_slice(&value.to_ne_bytes());
+        }
+
+        bytes
+    }
+
+    pub const fn from_translation(translation: Vec3) -> Self {
+        Self::from_cols(
+            Vec4::new(1.0, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, 1.0, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, 1.0, 0.0),
+            Vec4::from_vec3(translation, 1.0),
+        )
+    }
+
+    pub const fn from_scale(scale: Vec3) -> Self {
+        Self::from_cols(
+            Vec4::new(scale.x, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, scale.y, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, scale.z, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+        )
+    }
+
+    /// The rotation described by the unit quaternion `rotation`.
+    pub fn from_quat(rotation: Quat) -> Self {
+        Self::from_scale_rotation_translation(Vec3::ONE, rotation, Vec3::ZERO)
+    }
+
+    /// Embeds `m` as the upper-left block of an affine transform.
+    pub const fn from_mat3(m: Mat3) -> Self {
+        Self::from_cols(
+            Vec4::from_vec3(m.x_axis, 0.0),
+            Vec4::from_vec3(m.y_axis, 0.0),
+            Vec4::from_vec3(m.z_axis, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+        )
+    }
+
+    /// A rotation of `angle` radians around the X axis.
+    pub fn from_rotation_x(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::from_cols(
+            Vec4::new(1.0, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, cos, sin, 0.0),
+            Vec4::new(0.0, -sin, cos, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+        )
+    }
+
+    /// A rotation of `angle` radians around the Y axis.
+    pub fn from_rotation_y(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::from_cols(
+            Vec4::new(cos, 0.0, -sin, 0.0),
+            Vec4::new(0.0, 1.0, 0.0, 0.0),
+            Vec4::new(sin, 0.0, cos, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+        )
+    }
+
+    /// A rotation of `angle` radians around the Z axis.
+    pub fn from_rotation_z(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::from_cols(
+            Vec4::new(cos, sin, 0.0, 0.0),
+            Vec4::new(-sin, cos, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, 1.0, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+        )
+    }
+
+    /// Scales, then rotates, then translates.
+    pub fn from_scale_rotation_translation(scale: Vec3, rotation: Quat, translation: Vec3) -> Self {
+        let x_axis = rotation * Vec3::new(scale.x, 0.0, 0.0);
+        let y_axis = rotation * Vec3::new(0.0, scale.y, 0.0);
+        let z_axis = rotation * Vec3::new(0.0, 0.0, scale.z);
+
+        Self::from_cols(
+            Vec4::from_vec3(x_axis, 0.0),
+            Vec4::from_vec3(y_axis, 0.0),
+            Vec4::from_vec3(z_axis, 0.0),
+            Vec4::from_vec3(translation, 1.0),
+        )
+    }
+
+    /// A perspective projection for Vulkan: right-handed, depth mapped to
+    /// `0..1` and Y flipped so +Y points up on screen.
+    ///
+    /// `fov_y` is the vertical field of view in radians and `aspect` is
+    /// width over height.
+    pub fn perspective_rh_zo(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let focal = 1.0 / (fov_y * 0.5).tan();
+        let depth = far / (near - far);
+
+        Self::from_cols(
+            Vec4::new(focal / aspect, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, -focal, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, depth, -1.0),
+            Vec4::new(0.0, 0.0, depth * near, 0.0),
+        )
+    }
+
+    /// An orthographic projection for Vulkan: right-handed, depth mapped to
+    /// `0..1` and Y flipped so `top` is at the top of the screen.
+    pub fn orthographic_rh_zo(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        let width = 1.0 / (right - left);
+        let height = 1.0 / (top - bottom);
+        let depth = 1.0 / (near - far);
+
+        Self::from_cols(
+            Vec4::new(2.0 * width, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, -2.0 * height, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, depth, 0.0),
+            Vec4::new(
+                -(right + left) * width,
+                (top + bottom) * height,
+                depth * near,
+                1.0,
+            ),
+        )
+    }
+
+    /// A right-handed view matrix for a camera at `eye` looking at `target`.
+    ///
+    /// `up` must not be parallel to the view direction.
+    pub fn look_at_rh(eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        Self::look_to_rh(eye, target - eye, up)
+    }
+
+    /// A right-handed view matrix for a camera at `eye` looking along
+    /// `direction`.
+    pub fn look_to_rh(eye: Vec3, direction: Vec3, up: Vec3) -> Self {
+        let forward = direction.normalize();
+        let right = forward.cross(up).normalize();
+        let up = right.cross(forward);
+
+        Self::from_cols(
+            Vec4::new(right.x, up.x, -forward.x, 0.0),
+            Vec4::new(right.y, up.y, -forward.y, 0.0),
+            Vec4::new(right.z, up.z, -forward.z, 0.0),
+            Vec4::new(-right.dot(eye), -up.dot(eye), forward.dot(eye), 1.0),
+        )
+    }
+
+    /// Returns the column at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than 3.
+    pub fn col(&self, index: usize) -> Vec4 {
+        match index {
+            0 => self.x_axis,
+            1 => self.y_axis,
+            2 => self.z_axis,
+            3 => self.w_axis,
+            _ => panic!("Mat4 column index {index} out of bounds"),
+        }
+    }
+
+    /// Returns the row at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than 3.
+    pub fn row(&self, index: usize) -> Vec4 {
+        let component = |v: Vec4| match index {
+            0 => v.x,
+            1 => v.y,
+            2 => v.z,
+            3 => v.w,
+            _ => panic!("Mat4 row index {index} out of bounds"),
+        };
+
+        Vec4::new(
+            component(self.x_axis),
+            component(self.y_axis),
+            component(self.z_axis),
+            component(self.w_axis),
+        )
+    }
+
+    /// The translation part of an affine transform.
+    pub const fn translation(&self) -> Vec3 {
+        self.w_axis.truncate()
+    }
+
+    /// Splits an affine transform without shear back into the parts
+    /// `from_scale_rotation_translation` takes. A mirroring transform gets a
+    /// negative X scale.
+    pub fn to_scale_rotation_translation(&self) -> (Vec3, Quat, Vec3) {
+        let sign = if self.determinant() < 0.0 { -1.0 } else { 1.0 };
+        let scale = Vec3::new(
+            self.x_axis.truncate().length() * sign,
+            self.y_axis.truncate().length(),
+            self.z_axis.truncate().length(),
+        );
+
+        let rotation = Quat::from_mat3(Mat3::from_cols(
+            self.x_axis.xyz() / scale.x,
+            self.y_axis.xyz() / scale.y,
+            self.z_axis.xyz() / scale.z,
+        ));
+
+        (scale, rotation.normalize(), self.translation())
+    }
+
+    /// Transforms a point, applying translation.
+    pub fn transform_point3(&self, point: Vec3) -> Vec3 {
+        (*self * Vec4::from_vec3(point, 1.0)).truncate()
+    }
+
+    /// Transforms a direction, ignoring translation.
+    pub fn transform_vector3(&self, vector: Vec3) -> Vec3 {
+        (*self * Vec4::from_vec3(vector, 0.0)).truncate()
+    }
+
+    /// Transforms a point and divides by `w`, as a projection matrix needs.
+    pub fn project_point3(&self, point: Vec3) -> Vec3 {
+        let clip = *self * Vec4::from_vec3(point, 1.0);
+        clip.truncate() / clip.w
+    }
+
+    pub fn transpose(&self) -> Self {
+        backend::mat4_transpose(self)
+    }
+
+    pub fn determinant(&self) -> f32 {
+        crate::backend::scalar::mat4_determinant(self)
+    }
+
+    /// Returns `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        backend::mat4_inverse(self)
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl std::ops::Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+
+    fn mul(self, rhs: Vec4) -> Vec4 {
+        backend::mat4_mul_vec4(&self, rhs)
+    }
+}
+
+impl std::ops::Mul for Mat4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Mat4) -> Self {
+        backend::mat4_mul(&self, &rhs)
+    }
+}
+
+impl std::ops::Mul<f32> for Mat4 {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self::from_cols(
+            self.x_axis * rhs,
+            self.y_axis * rhs,
+            self.z_axis * rhs,
+            self.w_axis * rhs,
+        )
+    }
+}
+
+impl std::ops::Add for Mat4 {
+    type Output = Self;
+
+    fn add(self, rhs: Mat4) -> Self {
+        Self::from_cols(
+            self.x_axis + rhs.x_axis,
+            self.y_axis + rhs.y_axis,
+            self.z_axis + rhs.z_axis,
+            self.w_axis + rhs.w_axis,
+        )
+    }
+}
+
+impl std::ops::Sub for Mat4 {
+    type Output = Self;
+
+    fn sub(self, rhs: Mat4) -> Self {
+        Self::from_cols(
+            self.x_axis - rhs.x_axis,
+            self.y_axis - rhs.y_axis,
+            self.z_axis - rhs.z_axis,
+            self.w_axis - rhs.w_axis,
+        )
+    }
+}
+
+impl std::ops::MulAssign for Mat4 {
+    fn mul_assign(&mut self, rhs: Mat4) {
+        *self = *self * rhs;
+    }
+}
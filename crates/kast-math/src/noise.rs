@@ -0,0 +1,392 @@
+use crate::{Rng, Vec2, Vec3};
+
+/// Coherent noise: nearby points get similar values, roughly from -1 to 1.
+///
+/// Every generator is seeded and only uses float arithmetic and `floor`, so
+/// the same seed gives the same values on every platform.
+pub trait Noise {
+    fn get2(&self, point: Vec2) -> f32;
+    fn get3(&self, point: Vec3) -> f32;
+}
+
+/// A shuffled table of `0..256`, repeated so lookups can add two entries
+/// without wrapping.
+#[derive(Debug, Clone)]
+struct Permutation([u8; 512]);
+
+impl Permutation {
+    fn new(seed: u64) -> Self {
+        let mut values: [u8; 256] = core::array::from_fn(|i| i as u8);
+        Rng::new(seed).shuffle(&mut values);
+
+        Self(core::array::from_fn(|i| values[i & 255]))
+    }
+
+    fn get(&self, index: i32) -> usize {
+        self.0[(index & 255) as usize] as usize
+    }
+
+    fn hash2(&self, x: i32, y: i32) -> usize {
+        self.0[self.get(x) + (y & 255) as usize] as usize
+    }
+
+    fn hash3(&self, x: i32, y: i32, z: i32) -> usize {
+        self.0[self.hash2(x, y) + (z & 255) as usize] as usize
+    }
+}
+
+/// The midpoints of a cube's edges, the gradients of 3D Perlin and simplex
+/// noise.
+const GRADIENTS3: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+/// Eight evenly spread directions of equal length.
+const GRADIENTS2: [[f32; 2]; 8] = [
+    [1.0, 1.0],
+    [-1.0, 1.0],
+    [1.0, -1.0],
+    [-1.0, -1.0],
+    [core::f32::consts::SQRT_2, 0.0],
+    [-core::f32::consts::SQRT_2, 0.0],
+    [0.0, core::f32::consts::SQRT_2],
+    [0.0, -core::f32::consts::SQRT_2],
+];
+
+fn gradient2(hash: usize, x: f32, y: f32) -> f32 {
+    let [gx, gy] = GRADIENTS2[hash & 7];
+    gx * x + gy * y
+}
+
+fn gradient3(hash: usize, x: f32, y: f32, z: f32) -> f32 {
+    let [gx, gy, gz] = GRADIENTS3[hash % 12];
+    gx * x + gy * y + gz * z
+}
+
+/// The quintic ease with zero first and second derivatives at 0 and 1, which
+/// hides the lattice.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Gradient noise on a square lattice. Smooth, but with a faint grid
+/// alignment that `Simplex` avoids.
+#[derive(Debug, Clone)]
+pub struct Perlin {
+    permutation: Permutation,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            permutation: Permutation::new(seed),
+        }
+    }
+}
+
+impl Noise for Perlin {
+    fn get2(&self, point: Vec2) -> f32 {
+        let cell = point.floor();
+        let (x, y) = (cell.x as i32, cell.y as i32);
+        let local = point - cell;
+        let (u, v) = (fade(local.x), fade(local.y));
+
+        let p = &self.permutation;
+        let corner = |dx: i32, dy: i32| {
+            gradient2(
+                p.hash2(x + dx, y + dy),
+                local.x - dx as f32,
+                local.y - dy as f32,
+            )
+        };
+
+        // Gradients of length sqrt(2) peak at about 1.
+        lerp(
+            lerp(corner(0, 0), corner(1, 0), u),
+            lerp(corner(0, 1), corner(1, 1), u),
+            v,
+        )
+    }
+
+    fn get3(&self, point: Vec3) -> f32 {
+        let cell = point.floor();
+        let (x, y, z) = (cell.x as i32, cell.y as i32, cell.z as i32);
+        let local = point - cell;
+        let (u, v, w) = (fade(local.x), fade(local.y), fade(local.z));
+
+        let p = &self.permutation;
+        let corner = |dx: i32, dy: i32, dz: i32| {
+            gradient3(
+                p.hash3(x + dx, y + dy, z + dz),
+                local.x - dx as f32,
+                local.y - dy as f32,
+                local.z - dz as f32,
+            )
+        };
+
+        lerp(
+            lerp(
+                lerp(corner(0, 0, 0), corner(1, 0, 0), u),
+                lerp(corner(0, 1, 0), corner(1, 1, 0), u),
+                v,
+            ),
+            lerp(
+                lerp(corner(0, 0, 1), corner(1, 0, 1), u),
+                lerp(corner(0, 1, 1), corner(1, 1, 1), u),
+                v,
+            ),
+            w,
+        )
+    }
+}
+
+/// Gradient noise on a triangle (2D) or tetrahedron (3D) lattice. Cheaper
+/// than `Perlin` in 3D and without its grid alignment.
+#[derive(Debug, Clone)]
+pub struct Simplex {
+    permutation: Permutation,
+}
+
+impl Simplex {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            permutation: Permutation::new(seed),
+        }
+    }
+}
+
+impl Noise for Simplex {
+    fn get2(&self, point: Vec2) -> f32 {
+        // (sqrt(3) - 1) / 2 and (3 - sqrt(3)) / 6: skew into and out of the
+        // square lattice the triangles are found on.
+        const SKEW: f32 = 0.366_025_42;
+        const UNSKEW: f32 = 0.211_324_87;
+
+        let s = (point.x + point.y) * SKEW;
+        let (i, j) = ((point.x + s).floor(), (point.y + s).floor());
+        let t = (i + j) * UNSKEW;
+        let x0 = point.x - (i - t);
+        let y0 = point.y - (j - t);
+
+        // Which triangle of the square the point is in.
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+
+        let offsets = [
+            (0, 0, x0, y0),
+            (i1, j1, x0 - i1 as f32 + UNSKEW, y0 - j1 as f32 + UNSKEW),
+            (1, 1, x0 - 1.0 + 2.0 * UNSKEW, y0 - 1.0 + 2.0 * UNSKEW),
+        ];
+
+        let (i, j) = (i as i32, j as i32);
+        let total: f32 = offsets
+            .into_iter()
+            .map(|(di, dj, x, y)| {
+                let falloff = 0.5 - x * x - y * y;
+                if falloff <= 0.0 {
+                    return 0.0;
+                }
+                let hash = self.permutation.hash2(i + di, j + dj);
+                let [gx, gy, _] = GRADIENTS3[hash % 12];
+                falloff.powi(4) * (gx * x + gy * y)
+            })
+            .sum();
+
+        // Scales the peaks to about 1.
+        70.0 * total
+    }
+
+    fn get3(&self, point: Vec3) -> f32 {
+        const SKEW: f32 = 1.0 / 3.0;
+        const UNSKEW: f32 = 1.0 / 6.0;
+
+        let s = (point.x + point.y + point.z) * SKEW;
+        let cell = (point + Vec3::splat(s)).floor();
+        let t = (cell.x + cell.y + cell.z) * UNSKEW;
+        let p0 = point - (cell - Vec3::splat(t));
+
+        // Which of the six tetrahedra of the cube the point is in, as the
+        // offsets of its second and third corners.
+        let (first, second) = if p0.x >= p0.y {
+            if p0.y >= p0.z {
+                ([1, 0, 0], [1, 1, 0])
+            } else if p0.x >= p0.z {
+                ([1, 0, 0], [1, 0, 1])
+            } else {
+                ([0, 0, 1], [1, 0, 1])
+            }
+        } else if p0.y < p0.z {
+            ([0, 0, 1], [0, 1, 1])
+        } else if p0.x < p0.z {
+            ([0, 1, 0], [0, 1, 1])
+        } else {
+            ([0, 1, 0], [1, 1, 0])
+        };
+
+        let corners = [[0, 0, 0], first, second, [1, 1, 1]];
+        let (i, j, k) = (cell.x as i32, cell.y as i32, cell.z as i32);
+        let total: f32 = corners
+            .into_iter()
+            .enumerate()
+            .map(|(n, [di, dj, dk])| {
+                let offset = Vec3::new(di as f32, dj as f32, dk as f32);
+                let local = p0 - offset + Vec3::splat(n as f32 * UNSKEW);
+                let falloff = 0.6 - local.length_squared();
+                if falloff <= 0.0 {
+                    return 0.0;
+                }
+                let hash = self.permutation.hash3(i + di, j + dj, k + dk);
+                falloff.powi(4) * gradient3(hash, local.x, local.y, local.z)
+            })
+            .sum();
+
+        32.0 * total
+    }
+}
+
+/// Random values on a square lattice, smoothly interpolated. Blockier than
+/// gradient noise, but the cheapest.
+#[derive(Debug, Clone)]
+pub struct ValueNoise {
+    permutation: Permutation,
+}
+
+impl ValueNoise {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            permutation: Permutation::new(seed),
+        }
+    }
+
+    fn value(hash: usize) -> f32 {
+        hash as f32 / 255.0 * 2.0 - 1.0
+    }
+}
+
+impl Noise for ValueNoise {
+    fn get2(&self, point: Vec2) -> f32 {
+        let cell = point.floor();
+        let (x, y) = (cell.x as i32, cell.y as i32);
+        let local = point - cell;
+        let (u, v) = (fade(local.x), fade(local.y));
+
+        let corner = |dx: i32, dy: i32| Self::value(self.permutation.hash2(x + dx, y + dy));
+
+        lerp(
+            lerp(corner(0, 0), corner(1, 0), u),
+            lerp(corner(0, 1), corner(1, 1), u),
+            v,
+        )
+    }
+
+    fn get3(&self, point: Vec3) -> f32 {
+        let cell = point.floor();
+        let (x, y, z) = (cell.x as i32, cell.y as i32, cell.z as i32);
+        let local = point - cell;
+        let (u, v, w) = (fade(local.x), fade(local.y), fade(local.z));
+
+        let corner =
+            |dx: i32, dy: i32, dz: i32| Self::value(self.permutation.hash3(x + dx, y + dy, z + dz));
+
+        lerp(
+            lerp(
+                lerp(corner(0, 0, 0), corner(1, 0, 0), u),
+                lerp(corner(0, 1, 0), corner(1, 1, 0), u),
+                v,
+            ),
+            lerp(
+                lerp(corner(0, 0, 1), corner(1, 0, 1), u),
+                lerp(corner(0, 1, 1), corner(1, 1, 1), u),
+                v,
+            ),
+            w,
+        )
+    }
+}
+
+/// Fractal Brownian motion: octaves of `noise` at rising frequency and
+/// falling amplitude, for natural looking terrain, clouds and the like.
+///
+/// ```
+/// # use kast_math::{Fbm, Noise, Simplex, Vec2};
+/// # let (seed, x, z) = (7, 12.0, -3.0);
+/// let terrain = Fbm::new(Simplex::new(seed)).with_octaves(6);
+/// let height = terrain.get2(Vec2::new(x, z) * 0.01) * 40.0;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fbm<N> {
+    pub noise: N,
+    pub octaves: u32,
+    /// How much the frequency grows with each octave.
+    pub lacunarity: f32,
+    /// How much the amplitude shrinks with each octave.
+    pub gain: f32,
+}
+
+impl<N: Noise> Fbm<N> {
+    /// Five octaves, each twice the frequency and half the amplitude of the
+    /// last.
+    pub fn new(noise: N) -> Self {
+        Self {
+            noise,
+            octaves: 5,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    pub fn with_lacunarity(mut self, lacunarity: f32) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Sums the octaves and divides by the total amplitude, keeping the
+    /// result within the range of `noise`.
+    fn sum(&self, mut sample: impl FnMut(f32, f32) -> f32) -> f32 {
+        let (mut total, mut amplitude, mut frequency, mut max) = (0.0, 1.0, 1.0, 0.0);
+        for octave in 0..self.octaves {
+            // Shifting each octave keeps their lattices from lining up at
+            // the origin.
+            total += sample(frequency, octave as f32 * 19.19) * amplitude;
+            max += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+        }
+
+        if max == 0.0 { 0.0 } else { total / max }
+    }
+}
+
+impl<N: Noise> Noise for Fbm<N> {
+    fn get2(&self, point: Vec2) -> f32 {
+        self.sum(|frequency, shift| self.noise.get2(point * frequency + Vec2::splat(shift)))
+    }
+
+    fn get3(&self, point: Vec3) -> f32 {
+        self.sum(|frequency, shift| self.noise.get3(point * frequency + Vec3::splat(shift)))
+    }
+}
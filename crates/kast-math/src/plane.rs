@@ -0,0 +1,53 @@
+use crate::{Sphere, Vec3, Vec4};
+
+/// The points `p` where `normal.dot(p) == distance`. `normal` is normalized,
+/// and points on its side are in front of the plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+impl Plane {
+    /// `normal` must be normalized.
+    pub const fn new(normal: Vec3, distance: f32) -> Self {
+        Self { normal, distance }
+    }
+
+    pub fn from_point_normal(point: Vec3, normal: Vec3) -> Self {
+        let normal = normal.normalize();
+        Self::new(normal, normal.dot(point))
+    }
+
+    /// The plane through three points, facing the side from which they wind
+    /// counter-clockwise.
+    pub fn from_points(a: Vec3, b: Vec3, c: Vec3) -> Self {
+        Self::from_point_normal(a, (b - a).cross(c - a))
+    }
+
+    /// The plane `x * a + y * b + z * c + w == 0`, as extracted from a
+    /// projection matrix.
+    pub fn from_vec4(coefficients: Vec4) -> Self {
+        let length = coefficients.truncate().length();
+        Self::new(coefficients.truncate() / length, -coefficients.w / length)
+    }
+
+    /// Positive in front of the plane, negative behind it.
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) - self.distance
+    }
+
+    /// The projection of `point` onto the plane.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.signed_distance(sphere.center).abs() <= sphere.radius
+    }
+
+    /// The same plane facing the other way.
+    pub fn flip(&self) -> Self {
+        Self::new(-self.normal, -self.distance)
+    }
+}
@@ -0,0 +1,307 @@
+use crate::backend::active as backend;
+use crate::{Mat3, Mat4, Vec3};
+
+/// A rotation stored as a unit quaternion.
+///
+/// Multiplying composes rotations (`a * b` applies `b` first) and rotates
+/// vectors (`rotation * v`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quat {
+    pub const fn from_xyzw(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    pub const IDENTITY: Self = Self::from_xyzw(0.0, 0.0, 0.0, 1.0);
+
+    /// A rotation of `angle` radians around `axis`, which must be normalized.
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let (sin, cos) = (angle * 0.5).sin_cos();
+        Self::from_xyzw(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    pub fn from_rotation_x(angle: f32) -> Self {
+        Self::from_axis_angle(Vec3::X, angle)
+    }
+
+    pub fn from_rotation_y(angle: f32) -> Self {
+        Self::from_axis_angle(Vec3::Y, angle)
+    }
+
+    pub fn from_rotation_z(angle: f32) -> Self {
+        Self::from_axis_angle(Vec3::Z, angle)
+    }
+
+    /// A rotation from Euler angles in radians, applied as roll around Z,
+    /// then pitch around X, then yaw around Y. This is the usual order for
+    /// cameras and characters, where yaw turns around the world's up axis.
+    pub fn from_euler(yaw: f32, pitch: f32, roll: f32) -> Self {
+        Self::from_rotation_y(yaw) * Self::from_rotation_x(pitch) * Self::from_rotation_z(roll)
+    }
+
+    /// The `(yaw, pitch, roll)` angles `from_euler` would build this
+    /// rotation from. Pitch is within `-PI/2..=PI/2`.
+    pub fn to_euler(self) -> (f32, f32, f32) {
+        let m = Mat3::from_quat(self);
+        let sin_pitch = -m.z_axis.y.clamp(-1.0, 1.0);
+        let pitch = sin_pitch.asin();
+
+        if sin_pitch.abs() < 0.999_999 {
+            let yaw = m.z_axis.x.atan2(m.z_axis.z);
+            let roll = m.x_axis.y.atan2(m.y_axis.y);
+            (yaw, pitch, roll)
+        } else {
+            // Gimbal lock: yaw and roll turn around the same axis, so the
+            // whole turn is put into yaw.
+            let yaw = (-m.x_axis.z).atan2(m.x_axis.x);
+            (yaw, pitch, 0.0)
+        }
+    }
+
+    /// The shortest rotation turning `from` into `to`. Both must be
+    /// normalized.
+    pub fn from_rotation_arc(from: Vec3, to: Vec3) -> Self {
+        let dot = from.dot(to);
+        if dot > 1.0 - 1e-6 {
+            return Self::IDENTITY;
+        }
+
+        if dot < -1.0 + 1e-6 {
+            // Opposite vectors: turn half way around any perpendicular axis.
+            let mut axis = Vec3::X.cross(from);
+            if axis.length_squared() < 1e-6 {
+                axis = Vec3::Y.cross(from);
+            }
+            return Self::from_axis_angle(axis.normalize(), core::f32::consts::PI);
+        }
+
+        let axis = from.cross(to);
+        Self::from_xyzw(axis.x, axis.y, axis.z, 1.0 + dot).normalize()
+    }
+
+    /// The rotation that points -Z along `forward` and keeps +Y as close to
+    /// `up` as possible, matching `Mat4::look_to_rh` for cameras.
+    ///
+    /// `up` must not be parallel to `forward`.
+    pub fn look_rotation(forward: Vec3, up: Vec3) -> Self {
+        let back = -forward.normalize();
+        let right = up.cross(back).normalize();
+        let up = back.cross(right);
+
+        Self::from_mat3(Mat3::from_cols(right, up, back))
+    }
+
+    /// The rotation of a pure rotation matrix.
+    pub fn from_mat3(m: Mat3) -> Self {
+        let (m00, m01, m02) = (m.x_axis.x, m.x_axis.y, m.x_axis.z);
+        let (m10, m11, m12) = (m.y_axis.x, m.y_axis.y, m.y_axis.z);
+        let (m20, m21, m22) = (m.z_axis.x, m.z_axis.y, m.z_axis.z);
+
+        // Solve for the largest component first to keep precision.
+        if m22 <= 0.0 {
+            let diff = m11 - m00;
+            let one_minus_m22 = 1.0 - m22;
+            if diff <= 0.0 {
+                let four_x_sq = one_minus_m22 - diff;
+                let inv = 0.5 / four_x_sq.sqrt();
+                Self::from_xyzw(
+                    four_x_sq * inv,
+                    (m01 + m10) * inv,
+                    (m02 + m20) * inv,
+                    (m12 - m21) * inv,
+                )
+            } else {
+                let four_y_sq = one_minus_m22 + diff;
+                let inv = 0.5 / four_y_sq.sqrt();
+                Self::from_xyzw(
+                    (m01 + m10) * inv,
+                    four_y_sq * inv,
+                    (m12 + m21) * inv,
+                    (m20 - m02) * inv,
+                )
+            }
+        } else {
+            let sum = m11 + m00;
+            let one_plus_m22 = 1.0 + m22;
+            if sum <= 0.0 {
+                let four_z_sq = one_plus_m22 - sum;
+                let inv = 0.5 / four_z_sq.sqrt();
+                Self::from_xyzw(
+                    (m02 + m20) * inv,
+                    (m12 + m21) * inv,
+                    four_z_sq * inv,
+                    (m01 - m10) * inv,
+                )
+            } else {
+                let four_w_sq = one_plus_m22 + sum;
+                let inv = 0.5 / four_w_sq.sqrt();
+                Self::from_xyzw(
+                    (m12 - m21) * inv,
+                    (m20 - m02) * inv,
+                    (m01 - m10) * inv,
+                    four_w_sq * inv,
+                )
+            }
+        }
+    }
+
+    /// The rotation of an affine transform without scale.
+    pub fn from_mat4(m: &Mat4) -> Self {
+        Self::from_mat3(Mat3::from_mat4(*m))
+    }
+
+    /// The axis and angle in radians of the rotation. The identity returns
+    /// an angle of zero around the X axis.
+    pub fn to_axis_angle(self) -> (Vec3, f32) {
+        let q = self.normalize();
+        let angle = 2.0 * q.w.clamp(-1.0, 1.0).acos();
+        let sin = (1.0 - q.w * q.w).max(0.0).sqrt();
+        if sin < 1e-6 {
+            (Vec3::X, 0.0)
+        } else {
+            (q.xyz() / sin, angle)
+        }
+    }
+
+    pub fn dot(self, other: Quat) -> f32 {
+        backend::quat_dot(self, other)
+    }
+
+    pub fn length_squared(self) -> f32 {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> f32 {
+        self.length_squared().sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        let len = self.length();
+        if len == 0.0 { self } else { self * (1.0 / len) }
+    }
+
+    pub fn is_normalized(self) -> bool {
+        (self.length_squared() - 1.0).abs() < 1e-4
+    }
+
+    /// The inverse rotation, for unit quaternions.
+    pub fn conjugate(self) -> Self {
+        Self::from_xyzw(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// The inverse of any non-zero quaternion. Same as `conjugate` for unit
+    /// quaternions.
+    pub fn inverse(self) -> Self {
+        self.conjugate() * (1.0 / self.length_squared())
+    }
+
+    /// The smallest angle in radians between the two rotations.
+    pub fn angle_between(self, other: Quat) -> f32 {
+        2.0 * self.dot(other).abs().min(1.0).acos()
+    }
+
+    /// Rotates `vector`; same as `self * vector`.
+    pub fn rotate(self, vector: Vec3) -> Vec3 {
+        self * vector
+    }
+
+    /// Spherical interpolation along the shortest path, at constant angular
+    /// speed.
+    pub fn slerp(self, end: Quat, t: f32) -> Self {
+        let mut end = end;
+        let mut dot = self.dot(end);
+        if dot < 0.0 {
+            end = -end;
+            dot = -dot;
+        }
+
+        // Nearly identical rotations: the sine below would vanish.
+        if dot > 0.9995 {
+            return (self * (1.0 - t) + end * t).normalize();
+        }
+
+        let theta = dot.acos();
+        let sin = theta.sin();
+        self * (((1.0 - t) * theta).sin() / sin) + end * ((t * theta).sin() / sin)
+    }
+
+    /// Normalized linear interpolation along the shortest path. Cheaper than
+    /// `slerp`, but speeds up towards the middle of large turns.
+    pub fn nlerp(self, end: Quat, t: f32) -> Self {
+        let end = if self.dot(end) < 0.0 { -end } else { end };
+        (self * (1.0 - t) + end * t).normalize()
+    }
+
+    fn xyz(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// Composes rotations: `a * b` applies `b` first.
+impl std::ops::Mul for Quat {
+    type Output = Self;
+
+    fn mul(self, rhs: Quat) -> Self {
+        backend::quat_mul(self, rhs)
+    }
+}
+
+/// Rotates a vector.
+impl std::ops::Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        backend::quat_rotate(self, rhs)
+    }
+}
+
+impl std::ops::MulAssign for Quat {
+    fn mul_assign(&mut self, rhs: Quat) {
+        *self = *self * rhs;
+    }
+}
+
+/// Scales every component; the result is generally not a rotation.
+impl std::ops::Mul<f32> for Quat {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self::from_xyzw(self.x * rhs, self.y * rhs, self.z * rhs, self.w * rhs)
+    }
+}
+
+/// Adds component-wise; the result is generally not a rotation.
+impl std::ops::Add for Quat {
+    type Output = Self;
+
+    fn add(self, rhs: Quat) -> Self {
+        Self::from_xyzw(
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+            self.w + rhs.w,
+        )
+    }
+}
+
+/// Negates every component, which describes the same rotation.
+impl std::ops::Neg for Quat {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_xyzw(-self.x, -self.y, -self.z, -self.w)
+    }
+}
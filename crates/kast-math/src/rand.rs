@@ -0,0 +1,250 @@
+use std::ops::Range;
+
+use crate::{Aabb, Rect, Sphere, Vec2, Vec3};
+
+const MULTIPLIER: u64 = 6364136223846793005;
+const DEFAULT_STREAM: u64 = 0xda3e39cb94b95bdb;
+
+/// A small, fast, seedable random number generator (PCG32).
+///
+/// The same seed always produces the same sequence on every platform: the
+/// helpers only use integer math, float arithmetic and `sqrt`, never
+/// platform-dependent functions like `sin`. This makes it suitable for
+/// procedural generation and replays, but not for anything security related.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+    increment: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, DEFAULT_STREAM)
+    }
+
+    /// Generators with the same seed but different streams produce unrelated
+    /// sequences, such as one per system that needs its own randomness.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Self {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    /// A new generator seeded from this one, so it can be handed out without
+    /// sharing the sequence.
+    pub fn fork(&mut self) -> Self {
+        Self::with_stream(self.u64(), self.u64())
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+
+    pub fn u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        ((self.u32() as u64) << 32) | self.u32() as u64
+    }
+
+    /// Uniform in `0.0..1.0`.
+    pub fn f32(&mut self) -> f32 {
+        // 24 bits fill the mantissa exactly.
+        (self.u32() >> 8) as f32 * (1.0 / (1u32 << 24) as f32)
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.u32() >> 31 == 1
+    }
+
+    /// `true` with the given probability, from 0 to 1.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.f32() < probability
+    }
+
+    /// Uniform in `0..bound`, without the bias of a plain modulo.
+    fn below(&mut self, bound: u32) -> u32 {
+        // Values under `threshold` would make the low results more likely.
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.u32();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+
+    /// Panics if the range is empty.
+    pub fn range_u32(&mut self, range: Range<u32>) -> u32 {
+        assert!(range.start < range.end, "empty range");
+        range.start + self.below(range.end - range.start)
+    }
+
+    /// Panics if the range is empty.
+    pub fn range_i32(&mut self, range: Range<i32>) -> i32 {
+        assert!(range.start < range.end, "empty range");
+        let span = range.end.wrapping_sub(range.start) as u32;
+        range.start.wrapping_add(self.below(span) as i32)
+    }
+
+    /// Panics if the range is empty or wider than `u32::MAX`.
+    pub fn range_usize(&mut self, range: Range<usize>) -> usize {
+        assert!(range.start < range.end, "empty range");
+        let span = u32::try_from(range.end - range.start).expect("range too wide");
+        range.start + self.below(span) as usize
+    }
+
+    pub fn range_f32(&mut self, range: Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.f32()
+    }
+
+    /// A random direction, uniformly spread over the circle.
+    pub fn unit_vec2(&mut self) -> Vec2 {
+        loop {
+            let point = self.point_in_square();
+            let length_squared = point.length_squared();
+            // Rejecting the corners keeps the directions uniform; rejecting
+            // tiny vectors keeps the normalization precise.
+            if length_squared > 1e-4 && length_squared <= 1.0 {
+                return point / length_squared.sqrt();
+            }
+        }
+    }
+
+    /// A random direction, uniformly spread over the sphere.
+    pub fn unit_vec3(&mut self) -> Vec3 {
+        loop {
+            let point = self.point_in_cube();
+            let length_squared = point.length_squared();
+            if length_squared > 1e-4 && length_squared <= 1.0 {
+                return point / length_squared.sqrt();
+            }
+        }
+    }
+
+    /// Uniform inside a circle around the origin.
+    pub fn point_in_circle(&mut self, radius: f32) -> Vec2 {
+        loop {
+            let point = self.point_in_square();
+            if point.length_squared() <= 1.0 {
+                return point * radius;
+            }
+        }
+    }
+
+    /// Uniform inside `sphere`.
+    pub fn point_in_sphere(&mut self, sphere: &Sphere) -> Vec3 {
+        loop {
+            let point = self.point_in_cube();
+            if point.length_squared() <= 1.0 {
+                return sphere.center + point * sphere.radius;
+            }
+        }
+    }
+
+    pub fn point_in_rect(&mut self, rect: &Rect) -> Vec2 {
+        rect.position + rect.size * Vec2::new(self.f32(), self.f32())
+    }
+
+    pub fn point_in_aabb(&mut self, aabb: &Aabb) -> Vec3 {
+        aabb.min + aabb.size() * Vec3::new(self.f32(), self.f32(), self.f32())
+    }
+
+    fn point_in_square(&mut self) -> Vec2 {
+        Vec2::new(self.range_f32(-1.0..1.0), self.range_f32(-1.0..1.0))
+    }
+
+    fn point_in_cube(&mut self) -> Vec3 {
+        Vec3::new(
+            self.range_f32(-1.0..1.0),
+            self.range_f32(-1.0..1.0),
+            self.range_f32(-1.0..1.0),
+        )
+    }
+
+    /// A random item, or `None` if `items` is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        items.get(self.range_usize(0..items.len()))
+    }
+
+    /// A random index into `weights`, each picked in proportion to its
+    /// weight. Returns `None` if no weight is positive.
+    pub fn weighted_index(&mut self, weights: &[f32]) -> Option<usize> {
+        let weight = |w: f32| if w.is_finite() { w.max(0.0) } else { 0.0 };
+        let total: f32 = weights.iter().map(|&w| weight(w)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut remaining = self.f32() * total;
+        let mut last = None;
+        for (i, &w) in weights.iter().enumerate() {
+            if weight(w) <= 0.0 {
+                continue;
+            }
+            if remaining < weight(w) {
+                return Some(i);
+            }
+            remaining -= weight(w);
+            last = Some(i);
+        }
+
+        // Rounding may leave a sliver past the last weight.
+        last
+    }
+
+    /// A random item, each picked in proportion to `weight`. Returns `None`
+    /// if no weight is positive.
+    pub fn choose_weighted<'a, T>(
+        &mut self,
+        items: &'a [T],
+        weight: impl Fn(&T) -> f32,
+    ) -> Option<&'a T> {
+        let weights: Vec<f32> = items.iter().map(weight).collect();
+        self.weighted_index(&weights).map(|i| &items[i])
+    }
+
+    /// Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range_usize(0..i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "empty range")]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn range_usize_rejects_reversed_ranges() {
+        Rng::new(1).range_usize(5..3);
+    }
+
+    #[test]
+    fn range_usize_stays_in_range() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            assert!((10..13).contains(&rng.range_usize(10..13)));
+        }
+    }
+}
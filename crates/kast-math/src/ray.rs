@@ -0,0 +1,96 @@
+use crate::{Aabb, Mat4, Plane, Sphere, Vec2, Vec3, Vec4};
+
+/// A half-line from `origin` along the normalized `direction`.
+///
+/// Casts return the distance along the ray to the first hit, so
+/// `ray.at(distance)` is the hit point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// Normalizes `direction`.
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    /// The ray through a point on screen, for picking. `ndc` is in
+    /// normalized device coordinates, -1 to 1 on both axes, and
+    /// `view_projection` is the camera's projection times its view.
+    ///
+    /// Returns `None` if `view_projection` can't be inverted.
+    pub fn from_ndc(view_projection: &Mat4, ndc: Vec2) -> Option<Self> {
+        let inverse = view_projection.inverse()?;
+        let near = inverse.project_point3(Vec3::new(ndc.x, ndc.y, 0.0));
+        let far = inverse.project_point3(Vec3::new(ndc.x, ndc.y, 1.0));
+
+        Some(Self::new(near, far - near))
+    }
+
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// The point of the ray nearest to `point`.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        self.at((point - self.origin).dot(self.direction).max(0.0))
+    }
+
+    /// Zero if the ray starts inside the box.
+    pub fn cast_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        // Slab test: clip the ray against each pair of faces in turn. Zero
+        // direction components give infinities, which `min` and `max` handle.
+        let inverse = self.direction.recip();
+        let t1 = (aabb.min - self.origin) * inverse;
+        let t2 = (aabb.max - self.origin) * inverse;
+
+        let near = t1.min(t2).max_element();
+        let far = t1.max(t2).min_element();
+        (near <= far && far >= 0.0).then_some(near.max(0.0))
+    }
+
+    /// Zero if the ray starts inside the sphere.
+    pub fn cast_sphere(&self, sphere: &Sphere) -> Option<f32> {
+        let offset = self.origin - sphere.center;
+        let b = offset.dot(self.direction);
+        let c = offset.length_squared() - sphere.radius * sphere.radius;
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+
+        let discriminant = b * b - c;
+        if b > 0.0 || discriminant < 0.0 {
+            return None;
+        }
+
+        Some(-b - discriminant.sqrt())
+    }
+
+    /// Hits from either side; `None` if the ray runs parallel to the plane or
+    /// points away from it.
+    pub fn cast_plane(&self, plane: &Plane) -> Option<f32> {
+        let facing = plane.normal.dot(self.direction);
+        if facing.abs() < 1e-6 {
+            return None;
+        }
+
+        let distance = -plane.signed_distance(self.origin) / facing;
+        (distance >= 0.0).then_some(distance)
+    }
+
+    /// Moves the ray by `transform`, such as from world space into a mesh's
+    /// local space with the inverse of its model matrix.
+    ///
+    /// Distances along the result are in the new space.
+    pub fn transformed(&self, transform: &Mat4) -> Self {
+        Self::new(
+            transform.transform_point3(self.origin),
+            (*transform * Vec4::from_vec3(self.direction, 0.0)).truncate(),
+        )
+    }
+}
@@ -31,4 +31,44 @@ impl Rect {
             && self.position.y < other_max.y
             && self_max.y > other.position.y
     }
+
+    /// Includes the left and top edges but not the right and bottom ones, so
+    /// rects tiling a grid never both contain a point.
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        let max = self.max();
+        point.x >= self.position.x
+            && point.x < max.x
+            && point.y >= self.position.y
+            && point.y < max.y
+    }
+
+    /// The smallest rect containing both.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Self::from_min_max(
+            self.position.min(other.position),
+            self.max().max(other.max()),
+        )
+    }
+
+    /// The overlap of both rects, or `None` if they don't intersect.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        self.intersects(other).then(|| {
+            Self::from_min_max(
+                self.position.max(other.position),
+                self.max().min(other.max()),
+            )
+        })
+    }
+
+    /// Grows every edge outwards by `amount`, or shrinks it if negative.
+    pub fn expand(&self, amount: f32) -> Rect {
+        Self::from_components(
+            self.position - Vec2::splat(amount),
+            self.size + Vec2::splat(amount * 2.0),
+        )
+    }
+
+    fn from_min_max(min: Vec2, max: Vec2) -> Rect {
+        Self::from_components(min, max - min)
+    }
 }
@@ -0,0 +1,50 @@
+use crate::{Aabb, Vec3};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl Sphere {
+    pub const fn new(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// The sphere through the corners of `aabb`.
+    pub fn from_aabb(aabb: &Aabb) -> Self {
+        Self::new(aabb.center(), aabb.half_extents().length())
+    }
+
+    /// Inclusive of the surface.
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.center.distance_squared(point) <= self.radius * self.radius
+    }
+
+    /// Whether the spheres overlap; touching counts.
+    pub fn intersects(&self, other: &Sphere) -> bool {
+        let radii = self.radius + other.radius;
+        self.center.distance_squared(other.center) <= radii * radii
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        aabb.distance_squared_to_point(self.center) <= self.radius * self.radius
+    }
+
+    /// The point of the sphere nearest to `point`, which is `point` itself
+    /// if it is inside.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        let offset = point - self.center;
+        if offset.length_squared() <= self.radius * self.radius {
+            point
+        } else {
+            self.center + offset.normalize() * self.radius
+        }
+    }
+
+    /// The smallest sphere around this one after a uniform `scale` and a
+    /// move by `translation`.
+    pub fn transformed(&self, scale: f32, translation: Vec3) -> Self {
+        Self::new(self.center * scale + translation, self.radius * scale.abs())
+    }
+}
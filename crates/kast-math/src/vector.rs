@@ -1,36 +1,455 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
+use crate::backend::active as backend;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
 }
 
-impl Vec2 {
-    pub const fn new(x: f32, y: f32) -> Self {
-        Self { x, y }
-    }
-
-    pub const ZERO: Self = Self::new(0.0, 0.0);
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Vec3 {
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vec4 {
     pub x: f32,
     pub y: f32,
     pub z: f32,
+    pub w: f32,
 }
 
-impl Vec3 {
-    pub const fn new(x: f32, y: f32, z: f32) -> Self {
-        Self { x, y, z }
+/// A 2D integer vector, for pixel coordinates and grid cells.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct IVec2 {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// A 2D unsigned vector, for sizes and texel coordinates.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct UVec2 {
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Constructors, conversions and component-wise helpers shared by every
+/// vector type.
+macro_rules! impl_vector {
+    ($name:ident, $t:ty, $n:literal, $zero:literal, $one:literal, [$($field:ident),+]) => {
+        impl $name {
+            pub const fn new($($field: $t),+) -> Self {
+                Self { $($field),+ }
+            }
+
+            /// A vector with every component set to `value`.
+            pub const fn splat(value: $t) -> Self {
+                Self { $($field: value),+ }
+            }
+
+            pub const ZERO: Self = Self::splat($zero);
+            pub const ONE: Self = Self::splat($one);
+
+            pub const fn from_array(array: [$t; $n]) -> Self {
+                let [$($field),+] = array;
+                Self { $($field),+ }
+            }
+
+            pub const fn to_array(self) -> [$t; $n] {
+                [$(self.$field),+]
+            }
+
+            /// The smaller of each pair of components.
+            pub fn min(self, other: Self) -> Self {
+                Self { $($field: if other.$field < self.$field { other.$field } else { self.$field }),+ }
+            }
+
+            /// The larger of each pair of components.
+            pub fn max(self, other: Self) -> Self {
+                Self { $($field: if other.$field > self.$field { other.$field } else { self.$field }),+ }
+            }
+
+            /// Clamps each component between the matching components of `min`
+            /// and `max`.
+            pub fn clamp(self, min: Self, max: Self) -> Self {
+                self.max(min).min(max)
+            }
+
+            pub fn min_element(self) -> $t {
+                self.to_array().into_iter().reduce(|a, b| if b < a { b } else { a }).unwrap()
+            }
+
+            pub fn max_element(self) -> $t {
+                self.to_array().into_iter().reduce(|a, b| if b > a { b } else { a }).unwrap()
+            }
+
+            /// The sum of every component.
+            pub fn element_sum(self) -> $t {
+                $zero $(+ self.$field)+
+            }
+        }
+
+        impl Index<usize> for $name {
+            type Output = $t;
+
+            fn index(&self, index: usize) -> &$t {
+                [$(&self.$field),+][index]
+            }
+        }
+
+        impl IndexMut<usize> for $name {
+            fn index_mut(&mut self, index: usize) -> &mut $t {
+                let Self { $($field),+ } = self;
+                [$($field),+].into_iter().nth(index).unwrap_or_else(|| {
+                    panic!("index {index} out of bounds for {}", stringify!($name))
+                })
+            }
+        }
+
+        impl From<[$t; $n]> for $name {
+            fn from(array: [$t; $n]) -> Self {
+                Self::from_array(array)
+            }
+        }
+
+        impl From<$name> for [$t; $n] {
+            fn from(vector: $name) -> Self {
+                vector.to_array()
+            }
+        }
+
+        impl From<($(impl_vector!(@ty $field, $t)),+)> for $name {
+            fn from(($($field),+): ($(impl_vector!(@ty $field, $t)),+)) -> Self {
+                Self { $($field),+ }
+            }
+        }
+
+        impl From<$name> for ($(impl_vector!(@ty $field, $t)),+) {
+            fn from(vector: $name) -> Self {
+                ($(vector.$field),+)
+            }
+        }
+
+        impl std::iter::Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::ZERO, Add::add)
+            }
+        }
+    };
+
+    (@ty $field:ident, $t:ty) => { $t };
+}
+
+/// Component-wise arithmetic and `dot`, computed one component at a time.
+macro_rules! impl_vector_ops {
+    ($name:ident, $t:ty, $zero:literal, [$($field:ident),+]) => {
+        impl $name {
+            pub fn dot(self, other: Self) -> $t {
+                $zero $(+ self.$field * other.$field)+
+            }
+        }
+
+        impl_vector_ops!(@op $name, $t, Add, add, AddAssign, add_assign, [$($field),+]);
+        impl_vector_ops!(@op $name, $t, Sub, sub, SubAssign, sub_assign, [$($field),+]);
+        impl_vector_ops!(@op $name, $t, Mul, mul, MulAssign, mul_assign, [$($field),+]);
+        impl_vector_ops!(@op $name, $t, Div, div, DivAssign, div_assign, [$($field),+]);
+    };
+
+    // `a op b`, `a op scalar`, `scalar op a` and their assigning forms.
+    (@op $name:ident, $t:ty, $op:ident, $method:ident, $assign:ident, $assign_method:ident, [$($field:ident),+]) => {
+        impl $op for $name {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                Self { $($field: $op::$method(self.$field, rhs.$field)),+ }
+            }
+        }
+
+        impl $op<$t> for $name {
+            type Output = Self;
+
+            fn $method(self, rhs: $t) -> Self {
+                Self { $($field: $op::$method(self.$field, rhs)),+ }
+            }
+        }
+
+        impl $op<$name> for $t {
+            type Output = $name;
+
+            fn $method(self, rhs: $name) -> $name {
+                $name { $($field: $op::$method(self, rhs.$field)),+ }
+            }
+        }
+
+        impl $assign for $name {
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = $op::$method(*self, rhs);
+            }
+        }
+
+        impl $assign<$t> for $name {
+            fn $assign_method(&mut self, rhs: $t) {
+                *self = $op::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+/// `Vec4` arithmetic through the backend kernels. Scalar operands are
+/// splatted so both backends round the same way.
+macro_rules! impl_vec4_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $kernel:ident) => {
+        impl $op for Vec4 {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                backend::$kernel(self, rhs)
+            }
+        }
+
+        impl $op<f32> for Vec4 {
+            type Output = Self;
+
+            fn $method(self, rhs: f32) -> Self {
+                backend::$kernel(self, Vec4::splat(rhs))
+            }
+        }
+
+        impl $op<Vec4> for f32 {
+            type Output = Vec4;
+
+            fn $method(self, rhs: Vec4) -> Vec4 {
+                backend::$kernel(Vec4::splat(self), rhs)
+            }
+        }
+
+        impl $assign for Vec4 {
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = $op::$method(*self, rhs);
+            }
+        }
+
+        impl $assign<f32> for Vec4 {
+            fn $assign_method(&mut self, rhs: f32) {
+                *self = $op::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+/// Operations that only make sense for signed components.
+macro_rules! impl_signed_vector {
+    ($name:ident, $t:ty, [$($field:ident),+]) => {
+        impl $name {
+            pub fn abs(self) -> Self {
+                Self { $($field: self.$field.abs()),+ }
+            }
+
+            /// `-1`, `0` or `1` per component for integers; `-1.0` or `1.0`
+            /// for floats.
+            pub fn signum(self) -> Self {
+                Self { $($field: self.$field.signum()),+ }
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+    };
+}
+
+/// Geometry on float vectors.
+macro_rules! impl_float_vector {
+    ($name:ident, [$($field:ident),+]) => {
+        impl_signed_vector!($name, f32, [$($field),+]);
+
+        impl $name {
+            pub fn length_squared(self) -> f32 {
+                self.dot(self)
+            }
+
+            pub fn length(self) -> f32 {
+                self.length_squared().sqrt()
+            }
+
+            /// The vector scaled to length 1, or unchanged if it has length
+            /// zero.
+            pub fn normalize(self) -> Self {
+                let len = self.length();
+                if len == 0.0 { self } else { self / len }
+            }
+
+            /// Like `normalize`, but `None` for vectors too short to have a
+            /// direction.
+            pub fn try_normalize(self) -> Option<Self> {
+                let len = self.length();
+                (len > f32::EPSILON && len.is_finite()).then(|| self / len)
+            }
+
+            pub fn is_normalized(self) -> bool {
+                (self.length_squared() - 1.0).abs() < 1e-4
+            }
+
+            pub fn distance(self, other: Self) -> f32 {
+                (self - other).length()
+            }
+
+            pub fn distance_squared(self, other: Self) -> f32 {
+                (self - other).length_squared()
+            }
+
+            /// Linear interpolation: `self` at `t = 0`, `other` at `t = 1`.
+            pub fn lerp(self, other: Self, t: f32) -> Self {
+                self + (other - self) * t
+            }
+
+            /// Moves towards `target` by at most `max_distance`.
+            pub fn move_towards(self, target: Self, max_distance: f32) -> Self {
+                let offset = target - self;
+                let distance = offset.length();
+                if distance <= max_distance || distance == 0.0 {
+                    target
+                } else {
+                    self + offset * (max_distance / distance)
+                }
+            }
+
+            /// Mirrors the vector across the plane with the given normal,
+            /// which must be normalized. A ball hitting a wall bounces off
+            /// along `velocity.reflect(wall_normal)`.
+            pub fn reflect(self, normal: Self) -> Self {
+                self - normal * (2.0 * self.dot(normal))
+            }
+
+            /// The part of the vector along `other`, which must not be zero.
+            pub fn project_onto(self, other: Self) -> Self {
+                other * (self.dot(other) / other.length_squared())
+            }
+
+            pub fn floor(self) -> Self {
+                Self { $($field: self.$field.floor()),+ }
+            }
+
+            pub fn ceil(self) -> Self {
+                Self { $($field: self.$field.ceil()),+ }
+            }
+
+            pub fn round(self) -> Self {
+                Self { $($field: self.$field.round()),+ }
+            }
+
+            /// `1 / component` for each component.
+            pub fn recip(self) -> Self {
+                Self { $($field: self.$field.recip()),+ }
+            }
+
+            pub fn is_finite(self) -> bool {
+                $(self.$field.is_finite())&&+
+            }
+
+            pub fn is_nan(self) -> bool {
+                $(self.$field.is_nan())||+
+            }
+
+            /// Whether every component is within `epsilon` of the other's.
+            pub fn abs_diff_eq(self, other: Self, epsilon: f32) -> bool {
+                $((self.$field - other.$field).abs() <= epsilon)&&+
+            }
+        }
+    };
+}
+
+impl_vector!(Vec2, f32, 2, 0.0, 1.0, [x, y]);
+impl_vector!(Vec3, f32, 3, 0.0, 1.0, [x, y, z]);
+impl_vector!(Vec4, f32, 4, 0.0, 1.0, [x, y, z, w]);
+impl_vector!(IVec2, i32, 2, 0, 1, [x, y]);
+impl_vector!(UVec2, u32, 2, 0, 1, [x, y]);
+
+impl_vector_ops!(Vec2, f32, 0.0, [x, y]);
+impl_vector_ops!(Vec3, f32, 0.0, [x, y, z]);
+impl_vector_ops!(IVec2, i32, 0, [x, y]);
+impl_vector_ops!(UVec2, u32, 0, [x, y]);
+
+impl_vec4_op!(Add, add, AddAssign, add_assign, vec4_add);
+impl_vec4_op!(Sub, sub, SubAssign, sub_assign, vec4_sub);
+impl_vec4_op!(Mul, mul, MulAssign, mul_assign, vec4_mul);
+impl_vec4_op!(Div, div, DivAssign, div_assign, vec4_div);
+
+impl_float_vector!(Vec2, [x, y]);
+impl_float_vector!(Vec3, [x, y, z]);
+impl_float_vector!(Vec4, [x, y, z, w]);
+impl_signed_vector!(IVec2, i32, [x, y]);
+
+impl Vec2 {
+    pub const X: Self = Self::new(1.0, 0.0);
+    pub const Y: Self = Self::new(0.0, 1.0);
+    pub const NEG_X: Self = Self::new(-1.0, 0.0);
+    pub const NEG_Y: Self = Self::new(0.0, -1.0);
+
+    /// Adds a `z` component.
+    pub const fn extend(self, z: f32) -> Vec3 {
+        Vec3::new(self.x, self.y, z)
     }
 
-    pub const ZERO: Self = Self::new(0.0, 0.0, 0.0);
-    pub const Y: Self = Self::new(0.0, 1.0, 0.0);
+    /// The vector rotated a quarter turn counter-clockwise.
+    pub const fn perp(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// The z component of the 3D cross product; positive when `other` is
+    /// counter-clockwise from `self`.
+    pub fn perp_dot(self, other: Vec2) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// The vector rotated by `angle` radians counter-clockwise.
+    pub fn rotate(self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// The angle in radians between the vector and +X, within `-PI..=PI`.
+    pub fn to_angle(self) -> f32 {
+        self.y.atan2(self.x)
+    }
 
-    pub fn dot(self, other: Vec3) -> f32 {
-        self.x * other.x + self.y * other.y + self.z * other.z
+    pub const fn yx(self) -> Self {
+        Self::new(self.y, self.x)
     }
 
+    /// Truncates towards zero.
+    pub const fn as_ivec2(self) -> IVec2 {
+        IVec2::new(self.x as i32, self.y as i32)
+    }
+
+    /// Truncates towards zero; negative components become 0.
+    pub const fn as_uvec2(self) -> UVec2 {
+        UVec2::new(self.x as u32, self.y as u32)
+    }
+}
+
+impl Vec3 {
+    pub const X: Self = Self::new(1.0, 0.0, 0.0);
+    pub const Y: Self = Self::new(0.0, 1.0, 0.0);
+    pub const Z: Self = Self::new(0.0, 0.0, 1.0);
+    pub const NEG_X: Self = Self::new(-1.0, 0.0, 0.0);
+    pub const NEG_Y: Self = Self::new(0.0, -1.0, 0.0);
+    pub const NEG_Z: Self = Self::new(0.0, 0.0, -1.0);
+
     pub fn cross(self, other: Vec3) -> Self {
         Self {
             x: self.y * other.z - self.z * other.y,
@@ -39,76 +458,91 @@ impl Vec3 {
         }
     }
 
-    pub fn length_squared(self) -> f32 {
-        self.dot(self)
+    /// The angle in radians between the two vectors, neither of which may be
+    /// zero.
+    pub fn angle_between(self, other: Vec3) -> f32 {
+        (self.dot(other) / (self.length_squared() * other.length_squared()).sqrt())
+            .clamp(-1.0, 1.0)
+            .acos()
     }
 
-    pub fn length(self) -> f32 {
-        self.length_squared().sqrt()
+    /// Adds a `w` component.
+    pub const fn extend(self, w: f32) -> Vec4 {
+        Vec4::new(self.x, self.y, self.z, w)
     }
 
-    pub fn normalize(self) -> Self {
-        let len = self.length();
-        if len == 0.0 { self } else { self / len }
+    /// Drops the `z` component.
+    pub const fn truncate(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
     }
-}
 
-impl std::ops::Add for Vec3 {
-    type Output = Self;
+    pub const fn xy(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
 
-    fn add(self, rhs: Vec3) -> Self {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
+    pub const fn xz(self) -> Vec2 {
+        Vec2::new(self.x, self.z)
+    }
+
+    pub const fn yz(self) -> Vec2 {
+        Vec2::new(self.y, self.z)
     }
 }
 
-impl std::ops::Sub for Vec3 {
-    type Output = Self;
+impl Vec4 {
+    pub const X: Self = Self::new(1.0, 0.0, 0.0, 0.0);
+    pub const Y: Self = Self::new(0.0, 1.0, 0.0, 0.0);
+    pub const Z: Self = Self::new(0.0, 0.0, 1.0, 0.0);
+    pub const W: Self = Self::new(0.0, 0.0, 0.0, 1.0);
 
-    fn sub(self, rhs: Vec3) -> Self {
-        Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-            z: self.z - rhs.z,
-        }
+    pub const fn from_vec3(v: Vec3, w: f32) -> Self {
+        Self::new(v.x, v.y, v.z, w)
     }
-}
 
-impl std::ops::Mul<f32> for Vec3 {
-    type Output = Self;
+    pub fn dot(self, other: Vec4) -> f32 {
+        backend::vec4_dot(self, other)
+    }
 
-    fn mul(self, rhs: f32) -> Self {
-        Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
-            z: self.z * rhs,
-        }
+    /// Drops the `w` component.
+    pub const fn truncate(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    pub const fn xyz(self) -> Vec3 {
+        self.truncate()
+    }
+
+    pub const fn xy(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
     }
 }
 
-impl std::ops::Div<f32> for Vec3 {
-    type Output = Self;
+impl IVec2 {
+    pub const X: Self = Self::new(1, 0);
+    pub const Y: Self = Self::new(0, 1);
+    pub const NEG_X: Self = Self::new(-1, 0);
+    pub const NEG_Y: Self = Self::new(0, -1);
 
-    fn div(self, rhs: f32) -> Self {
-        Self {
-            x: self.x / rhs,
-            y: self.y / rhs,
-            z: self.z / rhs,
-        }
+    pub const fn as_vec2(self) -> Vec2 {
+        Vec2::new(self.x as f32, self.y as f32)
+    }
+
+    /// Negative components wrap around.
+    pub const fn as_uvec2(self) -> UVec2 {
+        UVec2::new(self.x as u32, self.y as u32)
     }
 }
 
-impl std::ops::Neg for Vec3 {
-    type Output = Self;
+impl UVec2 {
+    pub const X: Self = Self::new(1, 0);
+    pub const Y: Self = Self::new(0, 1);
 
-    fn neg(self) -> Self {
-        Self {
-            x: -self.x,
-            y: -self.y,
-            z: -self.z,
-        }
+    pub const fn as_vec2(self) -> Vec2 {
+        Vec2::new(self.x as f32, self.y as f32)
+    }
+
+    /// Components above `i32::MAX` wrap around.
+    pub const fn as_ivec2(self) -> IVec2 {
+        IVec2::new(self.x as i32, self.y as i32)
     }
 }
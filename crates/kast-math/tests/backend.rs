@@ -0,0 +1,145 @@
+//! Checks the SIMD kernels against the scalar ones on random inputs.
+
+#![cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2"
+))]
+
+use kast_math::backend::{scalar, sse2};
+use kast_math::{Mat4, Quat, Vec3, Vec4};
+use proptest::prelude::*;
+
+fn component() -> impl Strategy<Value = f32> {
+    -100.0f32..100.0
+}
+
+fn vec3() -> impl Strategy<Value = Vec3> {
+    [component(), component(), component()].prop_map(Vec3::from_array)
+}
+
+fn vec4() -> impl Strategy<Value = Vec4> {
+    [component(), component(), component(), component()].prop_map(Vec4::from_array)
+}
+
+fn mat4() -> impl Strategy<Value = Mat4> {
+    [vec4(), vec4(), vec4(), vec4()].prop_map(|[x, y, z, w]| Mat4::from_cols(x, y, z, w))
+}
+
+fn quat() -> impl Strategy<Value = Quat> {
+    [-1.0f32..1.0, -1.0f32..1.0, -1.0f32..1.0, -1.0f32..1.0]
+        .prop_map(|[x, y, z, w]| Quat::from_xyzw(x, y, z, w))
+}
+
+fn unit_quat() -> impl Strategy<Value = Quat> {
+    quat()
+        .prop_filter("non-zero", |q| q.length_squared() > 1e-3)
+        .prop_map(Quat::normalize)
+}
+
+/// `a` and `b` agree to within `tolerance` relative to `scale`, which should
+/// be the magnitude of the terms summed to get them.
+fn close(a: f32, b: f32, scale: f32, tolerance: f32) -> bool {
+    (a - b).abs() <= tolerance * scale.max(1.0)
+}
+
+fn vec4_close(a: Vec4, b: Vec4, scale: f32, tolerance: f32) -> bool {
+    (0..4).all(|i| close(a[i], b[i], scale, tolerance))
+}
+
+fn mat4_close(a: &Mat4, b: &Mat4, scale: f32, tolerance: f32) -> bool {
+    (0..4).all(|i| vec4_close(a.col(i), b.col(i), scale, tolerance))
+}
+
+/// The largest sum of absolute products any entry of `a * b` is built from.
+fn mat4_mul_scale(a: &Mat4, b: &Mat4) -> f32 {
+    let max = |m: &Mat4| {
+        m.to_cols_array()
+            .into_iter()
+            .fold(0.0f32, |max, v| max.max(v.abs()))
+    };
+    4.0 * max(a) * max(b)
+}
+
+proptest! {
+    #[test]
+    fn vec4_ops_match(a in vec4(), b in vec4()) {
+        prop_assert_eq!(sse2::vec4_add(a, b), scalar::vec4_add(a, b));
+        prop_assert_eq!(sse2::vec4_sub(a, b), scalar::vec4_sub(a, b));
+        prop_assert_eq!(sse2::vec4_mul(a, b), scalar::vec4_mul(a, b));
+    }
+
+    #[test]
+    fn vec4_div_matches(a in vec4(), b in vec4()) {
+        prop_assume!((0..4).all(|i| b[i].abs() > 1e-3));
+        prop_assert_eq!(sse2::vec4_div(a, b), scalar::vec4_div(a, b));
+    }
+
+    #[test]
+    fn vec4_dot_matches(a in vec4(), b in vec4()) {
+        let scale = (a.abs() * b.abs()).element_sum();
+        let (simd, portable) = (sse2::vec4_dot(a, b), scalar::vec4_dot(a, b));
+        prop_assert!(close(simd, portable, scale, 1e-6), "{simd} != {portable}");
+    }
+
+    #[test]
+    fn mat4_mul_vec4_matches(m in mat4(), v in vec4()) {
+        prop_assert_eq!(sse2::mat4_mul_vec4(&m, v), scalar::mat4_mul_vec4(&m, v));
+    }
+
+    #[test]
+    fn mat4_mul_matches(a in mat4(), b in mat4()) {
+        prop_assert_eq!(sse2::mat4_mul(&a, &b), scalar::mat4_mul(&a, &b));
+    }
+
+    #[test]
+    fn mat4_transpose_matches(m in mat4()) {
+        prop_assert_eq!(sse2::mat4_transpose(&m), scalar::mat4_transpose(&m));
+    }
+
+    #[test]
+    fn mat4_inverse_matches(m in mat4()) {
+        // Near-singular matrices may round to invertible on one path only.
+        let det = scalar::mat4_determinant(&m);
+        prop_assume!(det.abs() > 1.0);
+
+        let simd = sse2::mat4_inverse(&m).unwrap();
+        let portable = scalar::mat4_inverse(&m).unwrap();
+
+        // Compare what matters: both must undo `m` equally well.
+        let scale = mat4_mul_scale(&m, &portable);
+        let identity_simd = scalar::mat4_mul(&m, &simd);
+        let identity_portable = scalar::mat4_mul(&m, &portable);
+        prop_assert!(
+            mat4_close(&identity_simd, &identity_portable, scale, 1e-4),
+            "{identity_simd:?} != {identity_portable:?}"
+        );
+    }
+
+    #[test]
+    fn mat4_inverse_of_singular_is_none(m in mat4()) {
+        // A zero column keeps the determinant exactly zero despite rounding.
+        let singular = Mat4::from_cols(m.x_axis, m.y_axis, Vec4::ZERO, m.w_axis);
+        prop_assert_eq!(sse2::mat4_inverse(&singular), None);
+        prop_assert_eq!(scalar::mat4_inverse(&singular), None);
+    }
+
+    #[test]
+    fn quat_mul_matches(a in quat(), b in quat()) {
+        prop_assert_eq!(sse2::quat_mul(a, b), scalar::quat_mul(a, b));
+    }
+
+    #[test]
+    fn quat_rotate_matches(q in unit_quat(), v in vec3()) {
+        let (simd, portable) = (sse2::quat_rotate(q, v), scalar::quat_rotate(q, v));
+        let scale = v.abs().element_sum();
+        for i in 0..3 {
+            prop_assert!(close(simd[i], portable[i], scale, 1e-6), "{simd:?} != {portable:?}");
+        }
+    }
+
+    #[test]
+    fn quat_dot_matches(a in quat(), b in quat()) {
+        let (simd, portable) = (sse2::quat_dot(a, b), scalar::quat_dot(a, b));
+        prop_assert!(close(simd, portable, 4.0, 1e-6), "{simd} != {portable}");
+    }
+}
@@ -0,0 +1,197 @@
+//! Bakes a directory of source assets into what the engine loads at runtime.
+//!
+//! GLSL shaders are compiled to SPIR-V, PNG images are decoded and stored
+//! with their mipmaps as KTEX textures, and everything else is copied as is.
+//! Images hold sRGB colors unless named like `bricks_normal.linear.png`, which
+//! marks them as linear data.
+//! The result is either a packed archive for `ArchiveSource`, which release
+//! builds ship instead of the loose source files, or a directory of loose
+//! baked files for development builds to hot reload.
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use kast_resources::{ArchiveSource, ArchiveWriter, Compression, VfsSource};
+
+mod process;
+
+use process::Processor;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PackError {
+    Io(String),
+    /// `glslangValidator` couldn't run or rejected a shader.
+    Shader(String),
+    Image(String),
+    Archive(String),
+}
+
+impl core::fmt::Display for PackError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl core::error::Error for PackError {}
+
+/// What a pack did with each asset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PackReport {
+    /// Assets that were processed and written.
+    pub baked: usize,
+    /// Assets left as they were because nothing changed.
+    pub unchanged: usize,
+}
+
+/// Bakes the assets under a source directory.
+///
+/// ```no_run
+/// # use kast_pack::Packer;
+/// # fn main() -> Result<(), kast_pack::PackError> {
+/// let report = Packer::new("assets").pack("game.kpak")?;
+/// println!("{} baked, {} unchanged", report.baked, report.unchanged);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Packer {
+    source: PathBuf,
+}
+
+impl Packer {
+    pub fn new(source: impl Into<PathBuf>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// Bakes every asset into an archive at `output`, replacing it.
+    ///
+    /// Each entry records a hash of its source file and of how it's baked.
+    /// Entries of the previous archive at `output` whose hash still matches
+    /// are carried over instead of being processed again, except for
+    /// shaders, whose includes aren't tracked.
+    pub fn pack(&self, output: impl AsRef<Path>) -> Result<PackReport, PackError> {
+        let output = output.as_ref();
+        // Without a readable previous archive everything is baked again.
+        let previous = ArchiveSource::open(output).ok();
+        let mut writer = ArchiveWriter::new();
+        let mut report = PackReport::default();
+
+        for relative in self.sources()? {
+            let path = self.source.join(&relative);
+            let bytes = read(&path)?;
+            let processor = Processor::for_path(&relative);
+            let baked_path = processor.output_path(&relative);
+            let hash = processor.hash(&bytes);
+
+            let reusable = previous.as_ref().filter(|_| processor.is_reusable());
+            let unchanged = reusable.and_then(|archive| {
+                let entry = archive.entry(&baked_path)?;
+                (entry.hash == hash)
+                    .then(|| archive.read(&baked_path).ok())
+                    .flatten()
+            });
+            let baked = match unchanged {
+                Some(baked) => {
+                    report.unchanged += 1;
+                    baked
+                }
+                None => {
+                    report.baked += 1;
+                    processor.process(&path, &bytes)?
+                }
+            };
+
+            writer
+                .add(&baked_path, &baked, Compression::Deflate, hash)
+                .map_err(|error| PackError::Archive(error.to_string()))?;
+        }
+
+        // Written beside the output and moved over it, so a failed pack
+        // doesn't leave a truncated archive behind.
+        let mut partial = OsString::from(output);
+        partial.push(".partial");
+        writer
+            .write(&partial)
+            .map_err(|error| PackError::Archive(error.to_string()))?;
+        drop(previous);
+        fs::rename(&partial, output).map_err(|error| io_error(output, error))?;
+
+        Ok(report)
+    }
+
+    /// Bakes every asset into loose files under `output`, for development
+    /// builds that reload assets as they change.
+    ///
+    /// Every asset is processed, but files whose baked bytes didn't change
+    /// are left untouched so watchers don't reload them.
+    pub fn pack_directory(&self, output: impl AsRef<Path>) -> Result<PackReport, PackError> {
+        let output = output.as_ref();
+        let mut report = PackReport::default();
+
+        for relative in self.sources()? {
+            let path = self.source.join(&relative);
+            let processor = Processor::for_path(&relative);
+            let baked = processor.process(&path, &read(&path)?)?;
+
+            let baked_path = output.join(processor.output_path(&relative));
+            if fs::read(&baked_path).is_ok_and(|existing| existing == baked) {
+                report.unchanged += 1;
+                continue;
+            }
+
+            if let Some(parent) = baked_path.parent() {
+                fs::create_dir_all(parent).map_err(|error| io_error(parent, error))?;
+            }
+            fs::write(&baked_path, &baked).map_err(|error| io_error(&baked_path, error))?;
+            report.baked += 1;
+        }
+
+        Ok(report)
+    }
+
+    /// Every file under the source directory, relative to it and sorted.
+    /// Hidden files, such as editor swap files, are skipped.
+    fn sources(&self) -> Result<Vec<PathBuf>, PackError> {
+        let mut sources = Vec::new();
+        let mut pending = vec![PathBuf::new()];
+
+        while let Some(relative) = pending.pop() {
+            let directory = self.source.join(&relative);
+            let entries = fs::read_dir(&directory).map_err(|error| io_error(&directory, error))?;
+
+            for entry in entries {
+                let entry = entry.map_err(|error| io_error(&directory, error))?;
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+
+                let path = relative.join(entry.file_name());
+                let file_type = entry
+                    .file_type()
+                    .map_err(|error| io_error(&entry.path(), error))?;
+                if file_type.is_dir() {
+                    pending.push(path);
+                } else {
+                    sources.push(path);
+                }
+            }
+        }
+
+        sources.sort();
+        Ok(sources)
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, PackError> {
+    fs::read(path).map_err(|error| io_error(path, error))
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> PackError {
+    PackError::Io(format!("{}: {error}", path.display()))
+}
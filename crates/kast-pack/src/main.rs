@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use kast_pack::Packer;
+
+const USAGE: &str = "\
+usage: kast-pack [--directory] <assets> <output>
+
+Bakes the assets directory into a packed archive at <output>, or into loose
+files under the <output> directory with --directory. Assets whose sources
+haven't changed since the last pack are reused.";
+
+fn main() -> ExitCode {
+    let mut directory = false;
+    let mut paths = Vec::new();
+    for arg in std::env::args_os().skip(1) {
+        match arg.to_str() {
+            Some("--directory") => directory = true,
+            Some("-h" | "--help") => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    let Ok([source, output]) = <[PathBuf; 2]>::try_from(paths) else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    let packer = Packer::new(source);
+    let result = if directory {
+        packer.pack_directory(&output)
+    } else {
+        packer.pack(&output)
+    };
+
+    match result {
+        Ok(report) => {
+            println!(
+                "Packed {} assets into {} ({} unchanged)",
+                report.baked + report.unchanged,
+                output.display(),
+                report.unchanged
+            );
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("Failed to pack assets: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
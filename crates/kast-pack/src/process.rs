@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use kast_resources::content_hash;
+use kast_texture::{ColorSpace, Image};
+
+use crate::{PackError, io_error};
+
+/// How a source file is turned into the asset the engine loads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Processor {
+    /// GLSL compiled to SPIR-V, with the stage taken from the extension.
+    Shader,
+    /// A PNG stored as a KTEX texture with its mipmaps, generated in the
+    /// color space its name declares, see `ColorSpace::from_path`.
+    Image(ColorSpace),
+    Copy,
+}
+
+impl Processor {
+    pub fn for_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("vert" | "frag" | "comp" | "geom" | "tesc" | "tese") => Self::Shader,
+            Some("png") => Self::Image(ColorSpace::from_path(path)),
+            _ => Self::Copy,
+        }
+    }
+
+    /// Where the baked asset goes: `triangle.vert` becomes
+    /// `triangle.vert.spv`, and `grass.png` becomes `grass.ktex`.
+    pub fn output_path(self, path: &Path) -> PathBuf {
+        match self {
+            Self::Shader => {
+                let mut output = path.as_os_str().to_owned();
+                output.push(".spv");
+                output.into()
+            }
+            Self::Image(_) => path.with_extension("ktex"),
+            Self::Copy => path.to_path_buf(),
+        }
+    }
+
+    /// Whether the asset baked last time can be reused when `hash` matches.
+    /// Shaders can `#include` other files the hash doesn't cover, so they're
+    /// always compiled again.
+    pub fn is_reusable(self) -> bool {
+        self != Self::Shader
+    }
+
+    /// Covers both the source and the way it's baked, so changing either
+    /// bakes the asset again.
+    pub fn hash(self, source: &[u8]) -> u64 {
+        // Bump a version whenever its processor's output changes.
+        let version: &[u8] = match self {
+            Self::Shader => b"shader 1",
+            Self::Image(ColorSpace::Srgb) => b"image 1",
+            Self::Image(ColorSpace::Linear) => b"linear image 1",
+            Self::Copy => b"copy 1",
+        };
+
+        let mut key = content_hash(source).to_le_bytes().to_vec();
+        key.extend_from_slice(version);
+        content_hash(&key)
+    }
+
+    pub fn process(self, path: &Path, bytes: &[u8]) -> Result<Vec<u8>, PackError> {
+        match self {
+            Self::Shader => compile_shader(path),
+            Self::Image(color_space) => bake_texture(bytes, color_space),
+            Self::Copy => Ok(bytes.to_vec()),
+        }
+    }
+}
+
+/// Runs `glslangValidator` on the file, so includes resolve next to it.
+fn compile_shader(path: &Path) -> Result<Vec<u8>, PackError> {
+    static NEXT_OUTPUT: AtomicUsize = AtomicUsize::new(0);
+
+    let output = std::env::temp_dir().join(format!(
+        "kast-pack-{}-{}.spv",
+        std::process::id(),
+        NEXT_OUTPUT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = Command::new("glslangValidator")
+        .args(["-V", "-o"])
+        .arg(&output)
+        .arg(path)
+        .output()
+        .map_err(|error| PackError::Shader(format!("failed to run glslangValidator: {error}")))?;
+
+    if !result.status.success() {
+        let _ = fs::remove_file(&output);
+        // The compiler reports errors on stdout.
+        let log = String::from_utf8_lossy(&result.stdout);
+        return Err(PackError::Shader(format!(
+            "{}: {}",
+            path.display(),
+            log.trim()
+        )));
+    }
+
+    let spirv = fs::read(&output).map_err(|error| io_error(&output, error));
+    let _ = fs::remove_file(&output);
+    spirv
+}
+
+fn bake_texture(bytes: &[u8], color_space: ColorSpace) -> Result<Vec<u8>, PackError> {
+    let mut image = Image::from_png(bytes).map_err(|error| PackError::Image(error.to_string()))?;
+    image.color_space = color_space;
+    image.generate_mipmaps();
+
+    Ok(image.to_ktex())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use kast_texture::ColorSpace;
+
+    use super::Processor;
+
+    #[test]
+    fn images_are_baked_in_the_color_space_their_name_declares() {
+        let grass = Processor::for_path(Path::new("textures/grass.png"));
+        let normal = Processor::for_path(Path::new("textures/bricks_normal.linear.png"));
+
+        assert_eq!(grass, Processor::Image(ColorSpace::Srgb));
+        assert_eq!(normal, Processor::Image(ColorSpace::Linear));
+        assert_eq!(
+            normal.output_path(Path::new("textures/bricks_normal.linear.png")),
+            Path::new("textures/bricks_normal.linear.ktex")
+        );
+        assert_ne!(grass.hash(b"pixels"), normal.hash(b"pixels"));
+    }
+}
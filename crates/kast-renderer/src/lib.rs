@@ -1,12 +1,26 @@
+use std::collections::HashSet;
+
 use kast_graphics::GraphicsContext;
+use kast_graphics::resources::GpuResources;
+use kast_graphics::shader::{Shader, ShaderPipeline};
+use kast_graphics::texture::Texture;
+use kast_resources::{Asset, AssetEvent, AssetServer, Handle, StrongHandle};
 
 pub struct Renderer {
     context: Option<Box<dyn GraphicsContext>>,
+    resources: GpuResources,
+    pending_textures: HashSet<Handle<Texture>>,
+    pending_pipelines: HashSet<Handle<ShaderPipeline>>,
 }
 
 impl Renderer {
     pub fn new() -> Self {
-        Self { context: None }
+        Self {
+            context: None,
+            resources: GpuResources::new(),
+            pending_textures: HashSet::new(),
+            pending_pipelines: HashSet::new(),
+        }
     }
 
     /// Whether a backend graphics context has been attached yet.
@@ -29,6 +43,67 @@ impl Renderer {
         self.context.as_deref_mut()
     }
 
+    /// Reference counting for GPU objects; tracked objects are destroyed at
+    /// the end of the frame their last strong handle dropped in.
+    pub fn resources(&self) -> &GpuResources {
+        &self.resources
+    }
+
+    /// Brings the GPU side of assets up to date with the asset server's last
+    /// update: uploads textures that were loaded or reloaded, and builds
+    /// shader pipelines that were added or whose shaders changed.
+    ///
+    /// Call after every `AssetServer::update`. Work is kept until a graphics
+    /// context is attached. Failures are logged and leave the previous GPU
+    /// version in place.
+    pub fn prepare_assets(&mut self, assets: &mut AssetServer) {
+        self.pending_textures.extend(changed::<Texture>(assets));
+
+        let shaders = changed::<Shader>(assets);
+        self.pending_pipelines
+            .extend(changed::<ShaderPipeline>(assets));
+        self.pending_pipelines.extend(
+            assets
+                .iter::<ShaderPipeline>()
+                .filter(|(_, pipeline)| shaders.iter().any(|shader| pipeline.uses(*shader)))
+                .map(|(id, _)| id),
+        );
+
+        let Some(context) = self.context.as_deref_mut() else {
+            return;
+        };
+
+        for id in self.pending_textures.drain() {
+            let Some(texture) = assets.get_mut(id) else {
+                continue;
+            };
+            if let Err(error) = texture.upload(context, &self.resources) {
+                eprintln!("Renderer: failed to upload texture {id:?}: {error}");
+            }
+        }
+
+        for id in self.pending_pipelines.drain() {
+            let Some(pipeline) = assets.get::<ShaderPipeline>(id) else {
+                continue;
+            };
+            let shader = |handle: &StrongHandle<Shader>| assets.get(handle.id()).cloned();
+            // Built once the second shader arrives.
+            let (Some(vertex), Some(fragment)) = (
+                shader(&pipeline.descriptor.vertex_shader),
+                shader(&pipeline.descriptor.fragment_shader),
+            ) else {
+                continue;
+            };
+
+            let Some(pipeline) = assets.get_mut::<ShaderPipeline>(id) else {
+                continue;
+            };
+            if let Err(error) = pipeline.build(context, &self.resources, &vertex, &fragment) {
+                eprintln!("Renderer: failed to build pipeline {id:?}: {error}");
+            }
+        }
+    }
+
     pub fn begin_frame(&mut self) -> bool {
         match &mut self.context {
             Some(context) => match context.begin_frame() {
@@ -48,6 +123,15 @@ impl Renderer {
                 eprintln!("Renderer: end_frame failed: {error}");
             }
         }
+        self.release_unused();
+    }
+
+    /// Destroys the tracked GPU objects that are no longer referenced. Runs at
+    /// the end of every frame; call it directly to release outside of one.
+    pub fn release_unused(&mut self) {
+        if let Some(context) = &mut self.context {
+            self.resources.release_unused(context.as_mut());
+        }
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
@@ -62,3 +146,15 @@ impl Default for Renderer {
         Self::new()
     }
 }
+
+/// The assets of type `A` loaded or reloaded by the last update.
+fn changed<A: Asset>(assets: &AssetServer) -> Vec<Handle<A>> {
+    assets
+        .events::<A>()
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Loaded(id) | AssetEvent::Modified(id) => Some(*id),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,218 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::{Handle, Pool, RefCounts, StrongHandle};
+
+struct Ids<A> {
+    live: Mutex<Pool<(), A>>,
+    counts: RefCounts<A>,
+}
+
+/// Hands out handles for an `Assets<A>` from any thread, such as an asset
+/// loader referencing assets that aren't stored yet.
+///
+/// Reserved handles resolve to nothing until their asset is `insert`ed into
+/// the `Assets` the allocator belongs to.
+pub struct HandleAllocator<A> {
+    ids: Arc<Ids<A>>,
+}
+
+impl<A> HandleAllocator<A> {
+    pub fn new() -> Self {
+        Self {
+            ids: Arc::new(Ids {
+                live: Mutex::new(Pool::new()),
+                counts: RefCounts::new(),
+            }),
+        }
+    }
+
+    pub fn reserve(&self) -> StrongHandle<A> {
+        let id = self.live().insert(());
+        self.ids.counts.track(id)
+    }
+
+    fn live(&self) -> MutexGuard<'_, Pool<(), A>> {
+        self.ids.live.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<A> Clone for HandleAllocator<A> {
+    fn clone(&self) -> Self {
+        Self {
+            ids: self.ids.clone(),
+        }
+    }
+}
+
+impl<A> Default for HandleAllocator<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Storage for assets of type `A`, each kept alive by its `StrongHandle`s.
+///
+/// Dropping the last strong handle doesn't free the asset right away: it is
+/// queued until `remove_unused`, which hands it back so resources it owns
+/// elsewhere, such as on the GPU, can be destroyed too.
+///
+/// A handle can also be `reserve`d before its asset exists, which is how the
+/// asset server returns handles to assets that are still loading. Only the
+/// ids are shared with the `HandleAllocator`; reading assets never locks.
+///
+/// ```
+/// # use kast_resources::Assets;
+/// # struct Mesh;
+/// # impl Mesh {
+/// #     fn cube() -> Self {
+/// #         Mesh
+/// #     }
+/// #     fn destroy(self, destroyed: &mut usize) {
+/// #         *destroyed += 1;
+/// #     }
+/// # }
+/// # let (mut meshes, mut context) = (Assets::new(), 0);
+/// let mesh = meshes.add(Mesh::cube());
+/// let weak = mesh.downgrade();
+/// drop(mesh);
+///
+/// for (_, mesh) in meshes.remove_unused() {
+///     if let Some(mesh) = mesh {
+///         mesh.destroy(&mut context);
+///     }
+/// }
+/// assert!(weak.upgrade().is_none());
+/// # assert_eq!(context, 1);
+/// ```
+pub struct Assets<A> {
+    allocator: HandleAllocator<A>,
+    /// Indexed like the ids, tagged with the generation that owns the value.
+    values: Vec<Option<(u32, A)>>,
+}
+
+impl<A> Assets<A> {
+    pub fn new() -> Self {
+        Self::with_allocator(HandleAllocator::new())
+    }
+
+    /// Storage for the handles `allocator` hands out. The allocator must not
+    /// belong to another `Assets`.
+    pub fn with_allocator(allocator: HandleAllocator<A>) -> Self {
+        Self {
+            allocator,
+            values: Vec::new(),
+        }
+    }
+
+    pub fn allocator(&self) -> &HandleAllocator<A> {
+        &self.allocator
+    }
+
+    pub fn add(&mut self, asset: A) -> StrongHandle<A> {
+        let handle = self.reserve();
+        let _ = self.insert(handle.id(), asset);
+        handle
+    }
+
+    /// A handle with no asset behind it yet; `get` returns `None` until one
+    /// is `insert`ed.
+    pub fn reserve(&self) -> StrongHandle<A> {
+        self.allocator.reserve()
+    }
+
+    /// Puts `asset` behind `id`, filling a reserved handle or swapping in a
+    /// new version behind existing ones. Returns the previous asset, or gives
+    /// `asset` back if `id` is stale.
+    pub fn insert(&mut self, id: Handle<A>, asset: A) -> Result<Option<A>, A> {
+        if !self.contains(id) {
+            return Err(asset);
+        }
+
+        let index = id.index() as usize;
+        if index >= self.values.len() {
+            self.values.resize_with(index + 1, || None);
+        }
+
+        let previous = self.values[index].replace((id.generation(), asset));
+        Ok(previous
+            .filter(|(generation, _)| *generation == id.generation())
+            .map(|(_, asset)| asset))
+    }
+
+    /// `None` for stale ids and for reserved ones not filled yet.
+    pub fn get(&self, id: Handle<A>) -> Option<&A> {
+        match self.values.get(id.index() as usize)? {
+            Some((generation, asset)) if *generation == id.generation() => Some(asset),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, id: Handle<A>) -> Option<&mut A> {
+        match self.values.get_mut(id.index() as usize)? {
+            Some((generation, asset)) if *generation == id.generation() => Some(asset),
+            _ => None,
+        }
+    }
+
+    /// Whether `id` is live, even if its asset isn't there yet.
+    pub fn contains(&self, id: Handle<A>) -> bool {
+        self.allocator.live().contains(id)
+    }
+
+    /// The number of live handles, including reserved ones and unused ones
+    /// not yet removed.
+    pub fn len(&self) -> usize {
+        self.allocator.live().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<A>, &A)> {
+        self.values.iter().enumerate().filter_map(|(index, value)| {
+            let (generation, asset) = value.as_ref()?;
+            Some((Handle::from_raw_parts(index as u32, *generation), asset))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle<A>, &mut A)> {
+        self.values
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, value)| {
+                let (generation, asset) = value.as_mut()?;
+                Some((Handle::from_raw_parts(index as u32, *generation), asset))
+            })
+    }
+
+    /// Removes the assets whose last strong handle has dropped, returning the
+    /// ids of every freed handle along with its asset, if it had one.
+    ///
+    /// The assets are removed right away, whether or not the iterator is
+    /// consumed.
+    pub fn remove_unused(&mut self) -> std::vec::IntoIter<(Handle<A>, Option<A>)> {
+        let released = self.allocator.ids.counts.drain_released();
+        let removed: Vec<_> = released
+            .filter_map(|id| {
+                self.allocator.live().remove(id)?;
+
+                let asset = self
+                    .values
+                    .get_mut(id.index() as usize)
+                    .and_then(|value| {
+                        value.take_if(|(generation, _)| *generation == id.generation())
+                    })
+                    .map(|(_, asset)| asset);
+                Some((id, asset))
+            })
+            .collect();
+        removed.into_iter()
+    }
+}
+
+impl<A> Default for Assets<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
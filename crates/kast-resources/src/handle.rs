@@ -0,0 +1,79 @@
+use core::marker::PhantomData;
+
+/// A typed reference to a value in a `Pool`.
+///
+/// `T` names what the handle points to, so a handle from a pool of textures
+/// can't be passed to a pool of buffers. Handles are plain ids: they don't
+/// keep the value alive, and once it is removed they stop resolving, even if
+/// its slot is reused.
+pub struct Handle<T> {
+    index: u32,
+    generation: u32,
+    // `fn() -> T` keeps the handle `Send`, `Sync` and `Copy` whatever `T` is.
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    /// Rebuilds a handle from `index` and `generation`, such as an id stored
+    /// elsewhere. Nothing checks that it came from a pool of `T`.
+    pub const fn from_raw_parts(index: u32, generation: u32) -> Self {
+        Self {
+            index,
+            generation,
+            marker: PhantomData,
+        }
+    }
+
+    pub const fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub const fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+// Implemented by hand: derives would require `T` itself to implement them.
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> core::hash::Hash for Handle<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> PartialOrd for Handle<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Handle<T> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (self.index, self.generation).cmp(&(other.index, other.generation))
+    }
+}
+
+impl<T> core::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let name = core::any::type_name::<T>();
+        let name = name.rsplit("::").next().unwrap_or(name);
+        write!(f, "Handle<{}>({}v{})", name, self.index, self.generation)
+    }
+}
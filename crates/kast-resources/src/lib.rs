@@ -1,3 +1,20 @@
+mod assets;
+mod handle;
+mod loader;
 mod pool;
+mod ref_counts;
+mod server;
+mod task_pool;
+mod vfs;
 
+pub use assets::{Assets, HandleAllocator};
+pub use handle::Handle;
+pub use loader::{AssetLoader, LoadContext};
 pub use pool::Pool;
+pub use ref_counts::{RefCounts, StrongHandle, WeakHandle};
+pub use server::{Asset, AssetError, AssetEvent, AssetServer, LoadState};
+pub use task_pool::{Scope, TaskPool};
+pub use vfs::{
+    ArchiveEntry, ArchiveSource, ArchiveWriter, Compression, DirectorySource, MemorySource, Vfs,
+    VfsError, VfsSource, content_hash,
+};
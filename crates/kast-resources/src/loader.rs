@@ -0,0 +1,181 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::server::{Dependency, SharedHandles};
+use crate::vfs::normalize;
+use crate::{Asset, AssetError, StrongHandle};
+
+/// Turns the bytes of a file into an asset.
+///
+/// Loaders are registered with `AssetServer::register_loader` and picked by
+/// file extension, so new formats can be added without touching the engine.
+/// `load` runs on a worker thread: it should do the expensive work (parsing,
+/// decompressing, converting) and leave what needs the main thread, such as
+/// GPU uploads, for later.
+///
+/// ```
+/// # use kast_resources::{Asset, AssetError, AssetLoader, LoadContext, StrongHandle};
+/// # struct Texture;
+/// # impl Asset for Texture {}
+/// # struct Material {
+/// #     albedo: StrongHandle<Texture>,
+/// #     roughness: f32,
+/// # }
+/// # impl Asset for Material {}
+/// # struct Source {
+/// #     albedo_path: String,
+/// #     roughness: f32,
+/// # }
+/// # fn parse(_: &[u8]) -> Result<Source, AssetError> {
+/// #     Ok(Source { albedo_path: "grass.png".into(), roughness: 0.5 })
+/// # }
+/// struct MaterialLoader;
+///
+/// impl AssetLoader for MaterialLoader {
+///     type Asset = Material;
+///     type Settings = ();
+///
+///     fn extensions(&self) -> &[&str] {
+///         &["material"]
+///     }
+///
+///     fn load(&self, bytes: &[u8], _: &(), context: &mut LoadContext) -> Result<Material, AssetError> {
+///         let source = parse(bytes)?;
+///         Ok(Material {
+///             albedo: context.load(source.albedo_path),
+///             roughness: source.roughness,
+///         })
+///     }
+/// }
+/// ```
+pub trait AssetLoader: Send + Sync + 'static {
+    type Asset: Asset;
+    /// Options for a single load, passed to `AssetServer::load_with_settings`.
+    type Settings: Default + Send + Sync + 'static;
+
+    /// The extensions this loader handles, without the dot. Matched ignoring
+    /// case.
+    fn extensions(&self) -> &[&str];
+
+    fn load(
+        &self,
+        bytes: &[u8],
+        settings: &Self::Settings,
+        context: &mut LoadContext,
+    ) -> Result<Self::Asset, AssetError>;
+}
+
+/// What a loader can see and do besides decoding its own bytes.
+pub struct LoadContext<'a> {
+    path: &'a Path,
+    handles: &'a SharedHandles,
+    pub(crate) dependencies: Vec<Dependency>,
+}
+
+impl<'a> LoadContext<'a> {
+    pub(crate) fn new(path: &'a Path, handles: &'a SharedHandles) -> Self {
+        Self {
+            path,
+            handles,
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// The path being loaded, within the `Vfs`.
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// Declares a dependency on another asset, loading it if needed. `path`
+    /// is a `Vfs` path, like any other load.
+    ///
+    /// The dependency is kept alive by the returned handle, so store it in
+    /// the asset being loaded. `AssetServer::is_loaded_with_dependencies`
+    /// waits for it.
+    pub fn load<B: Asset>(&mut self, path: impl AsRef<Path>) -> StrongHandle<B> {
+        let path = path.as_ref();
+        let path = &normalize(path).unwrap_or_else(|| path.to_path_buf());
+        let (handle, is_new) = self.handles.get::<B>().handle_for(path);
+        self.dependencies
+            .push(Dependency::new(&handle, is_new.then(|| path.to_path_buf())));
+        handle
+    }
+}
+
+/// The type-erased side of `AssetLoader`, so loaders of any type can share
+/// one registry.
+pub(crate) trait ErasedLoader: Send + Sync {
+    fn asset_type(&self) -> TypeId;
+    fn settings_type(&self) -> TypeId;
+    fn default_settings(&self) -> Arc<dyn Any + Send + Sync>;
+    fn extensions(&self) -> &[&str];
+    fn load(
+        &self,
+        bytes: &[u8],
+        settings: &(dyn Any + Send + Sync),
+        context: &mut LoadContext,
+    ) -> Result<Box<dyn Any + Send>, AssetError>;
+}
+
+impl<L: AssetLoader> ErasedLoader for L {
+    fn asset_type(&self) -> TypeId {
+        TypeId::of::<L::Asset>()
+    }
+
+    fn settings_type(&self) -> TypeId {
+        TypeId::of::<L::Settings>()
+    }
+
+    fn default_settings(&self) -> Arc<dyn Any + Send + Sync> {
+        Arc::new(L::Settings::default())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        AssetLoader::extensions(self)
+    }
+
+    fn load(
+        &self,
+        bytes: &[u8],
+        settings: &(dyn Any + Send + Sync),
+        context: &mut LoadContext,
+    ) -> Result<Box<dyn Any + Send>, AssetError> {
+        let settings = settings
+            .downcast_ref()
+            .expect("settings are checked against the loader before loading");
+        let asset = AssetLoader::load(self, bytes, settings, context)?;
+        Ok(Box::new(asset))
+    }
+}
+
+/// The registered loaders, by extension.
+#[derive(Default)]
+pub(crate) struct Loaders {
+    by_extension: HashMap<String, Vec<Arc<dyn ErasedLoader>>>,
+}
+
+impl Loaders {
+    pub fn register(&mut self, loader: impl AssetLoader) {
+        let loader: Arc<dyn ErasedLoader> = Arc::new(loader);
+        for extension in loader.extensions() {
+            self.by_extension
+                .entry(extension.to_ascii_lowercase())
+                .or_default()
+                .push(loader.clone());
+        }
+    }
+
+    /// The loader producing `asset_type` from files like `path`. Loaders
+    /// registered later take precedence, so built-in ones can be replaced.
+    pub fn find(&self, asset_type: TypeId, path: &Path) -> Option<Arc<dyn ErasedLoader>> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        self.by_extension
+            .get(&extension)?
+            .iter()
+            .rev()
+            .find(|loader| loader.asset_type() == asset_type)
+            .cloned()
+    }
+}
@@ -1,27 +1,45 @@
+use core::marker::PhantomData;
+
+use crate::Handle;
+
 struct PoolEntry<T> {
     value: Option<T>,
     generation: u32,
 }
 
-pub struct Pool<T> {
+/// Generational storage handing out a `Handle` for each value.
+///
+/// Removed slots are reused, but with a bumped generation, so stale handles
+/// never resolve to the new occupant.
+///
+/// Handles are typed by `H`, which defaults to the stored type. Set it to a
+/// marker when the stored type is an implementation detail, as the graphics
+/// backend does with `Pool<VulkanBuffer, GpuBuffer>`.
+pub struct Pool<T, H = T> {
     entries: Vec<PoolEntry<T>>,
     free_indices: Vec<u32>,
+    len: usize,
+    marker: PhantomData<fn() -> H>,
 }
 
-impl<T> Pool<T> {
+impl<T, H> Pool<T, H> {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
             free_indices: Vec::new(),
+            len: 0,
+            marker: PhantomData,
         }
     }
 
-    pub fn insert(&mut self, value: T) -> (u32, u32) {
+    pub fn insert(&mut self, value: T) -> Handle<H> {
+        self.len += 1;
+
         if let Some(index) = self.free_indices.pop() {
             let entry = &mut self.entries[index as usize];
             entry.value = Some(value);
             entry.generation += 1;
-            (index, entry.generation)
+            Handle::from_raw_parts(index, entry.generation)
         } else {
             let index = self.entries.len() as u32;
             self.entries.push(PoolEntry {
@@ -29,45 +47,170 @@ impl<T> Pool<T> {
                 generation: 1,
             });
 
-            (index, 1)
+            Handle::from_raw_parts(index, 1)
         }
     }
 
-    pub fn get(&self, index: u32, generation: u32) -> Option<&T> {
-        self.entries.get(index as usize).and_then(|entry| {
-            if entry.generation == generation {
-                entry.value.as_ref()
-            } else {
-                None
-            }
-        })
+    pub fn get(&self, handle: Handle<H>) -> Option<&T> {
+        self.entries
+            .get(handle.index() as usize)
+            .filter(|entry| entry.generation == handle.generation())
+            .and_then(|entry| entry.value.as_ref())
     }
 
-    pub fn get_mut(&mut self, index: u32, generation: u32) -> Option<&mut T> {
-        self.entries.get_mut(index as usize).and_then(|entry| {
-            if entry.generation == generation {
-                entry.value.as_mut()
-            } else {
-                None
-            }
-        })
+    pub fn get_mut(&mut self, handle: Handle<H>) -> Option<&mut T> {
+        self.entries
+            .get_mut(handle.index() as usize)
+            .filter(|entry| entry.generation == handle.generation())
+            .and_then(|entry| entry.value.as_mut())
     }
 
-    pub fn remove(&mut self, index: u32, generation: u32) -> Option<T> {
-        if let Some(entry) = self.entries.get_mut(index as usize) {
-            if entry.generation == generation && entry.value.is_some() {
-                self.free_indices.push(index);
-                return entry.value.take();
-            }
+    pub fn contains(&self, handle: Handle<H>) -> bool {
+        self.get(handle).is_some()
+    }
+
+    pub fn remove(&mut self, handle: Handle<H>) -> Option<T> {
+        if let Some(entry) = self.entries.get_mut(handle.index() as usize)
+            && entry.generation == handle.generation()
+            && entry.value.is_some()
+        {
+            self.free_indices.push(handle.index());
+            self.len -= 1;
+            return entry.value.take();
         }
 
         None
     }
 
-    pub fn drain(&mut self) -> impl Iterator<Item = (u32, T)> {
+    /// The number of live values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The live values and their handles, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<H>, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let handle = Handle::from_raw_parts(index as u32, entry.generation);
+                entry.value.as_ref().map(|value| (handle, value))
+            })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle<H>, &mut T)> {
         self.entries
-            .drain(..)
+            .iter_mut()
             .enumerate()
-            .filter_map(|(index, entry)| entry.value.map(|value| (index as u32, value)))
+            .filter_map(|(index, entry)| {
+                let handle = Handle::from_raw_parts(index as u32, entry.generation);
+                entry.value.as_mut().map(|value| (handle, value))
+            })
+    }
+
+    /// Removes every value. Slots and their generations are kept, so handles
+    /// from before stay stale.
+    pub fn clear(&mut self) {
+        self.drain().for_each(drop);
+    }
+
+    /// Removes and returns every value, like `clear`. The pool is emptied
+    /// up front, so dropping the iterator early doesn't leave values behind.
+    pub fn drain(&mut self) -> std::vec::IntoIter<(Handle<H>, T)> {
+        let mut values = Vec::with_capacity(self.len);
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            if let Some(value) = entry.value.take() {
+                self.free_indices.push(index as u32);
+                values.push((
+                    Handle::from_raw_parts(index as u32, entry.generation),
+                    value,
+                ));
+            }
+        }
+
+        self.len = 0;
+        values.into_iter()
+    }
+}
+
+impl<T, H> Default for Pool<T, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pool;
+
+    #[test]
+    fn removed_slots_are_reused_with_a_new_generation() {
+        let mut pool = Pool::<&str>::new();
+        let a = pool.insert("a");
+        assert_eq!(pool.remove(a), Some("a"));
+        assert_eq!(pool.remove(a), None);
+
+        let b = pool.insert("b");
+        assert_eq!(b.index(), a.index());
+        assert_ne!(b.generation(), a.generation());
+        assert_eq!(pool.get(a), None);
+        assert_eq!(pool.get(b), Some(&"b"));
+    }
+
+    #[test]
+    fn iter_len_and_contains_skip_removed_values() {
+        let mut pool = Pool::<u32>::new();
+        let handles: Vec<_> = (0..4).map(|value| pool.insert(value)).collect();
+        pool.remove(handles[1]);
+
+        assert_eq!(pool.len(), 3);
+        assert!(!pool.contains(handles[1]));
+        assert!(pool.contains(handles[2]));
+        let values: Vec<_> = pool.iter().map(|(_, &value)| value).collect();
+        assert_eq!(values, [0, 2, 3]);
+
+        for (_, value) in pool.iter_mut() {
+            *value *= 10;
+        }
+        assert_eq!(pool.get(handles[3]), Some(&30));
+    }
+
+    #[test]
+    fn handles_are_stale_after_clear() {
+        let mut pool = Pool::<u32>::new();
+        let a = pool.insert(1);
+        let b = pool.insert(2);
+        pool.clear();
+
+        assert!(pool.is_empty());
+        assert_eq!(pool.iter().count(), 0);
+        assert!(!pool.contains(a));
+
+        let c = pool.insert(3);
+        let d = pool.insert(4);
+        assert!(!pool.contains(a) && !pool.contains(b));
+        assert_eq!((pool.get(c), pool.get(d)), (Some(&3), Some(&4)));
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn drain_empties_the_pool_even_if_dropped_early() {
+        let mut pool = Pool::<u32>::new();
+        let a = pool.insert(1);
+        pool.insert(2);
+
+        let first = pool.drain().next();
+        assert_eq!(first, Some((a, 1)));
+        assert!(pool.is_empty());
+        assert_eq!(pool.iter().count(), 0);
+
+        pool.insert(3);
+        pool.insert(4);
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.drain().count(), 2);
     }
 }
@@ -0,0 +1,165 @@
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+use crate::Handle;
+
+/// Hands out reference-counted handles to ids, and collects the ids whose
+/// last `StrongHandle` dropped so their owner can destroy them.
+///
+/// Handles can be cloned and dropped on any thread; releases are only
+/// observed when the owner calls `drain_released`.
+pub struct RefCounts<T> {
+    released: Arc<Mutex<Vec<Handle<T>>>>,
+}
+
+impl<T> RefCounts<T> {
+    pub fn new() -> Self {
+        Self {
+            released: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Starts counting references to `id`. Track each id only once: every
+    /// call starts its own count, and would release the id again.
+    pub fn track(&self, id: Handle<T>) -> StrongHandle<T> {
+        StrongHandle {
+            inner: Arc::new(Tracked {
+                id,
+                released: Arc::downgrade(&self.released),
+            }),
+        }
+    }
+
+    /// The ids whose last strong handle dropped since the previous call.
+    pub fn drain_released(&self) -> impl Iterator<Item = Handle<T>> + use<T> {
+        let mut released = self.released.lock().unwrap_or_else(PoisonError::into_inner);
+        core::mem::take(&mut *released).into_iter()
+    }
+}
+
+impl<T> Default for RefCounts<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Tracked<T> {
+    id: Handle<T>,
+    released: Weak<Mutex<Vec<Handle<T>>>>,
+}
+
+impl<T> Drop for Tracked<T> {
+    fn drop(&mut self) {
+        // The owner may already be gone, in which case so is what `id` named.
+        if let Some(released) = self.released.upgrade() {
+            released
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(self.id);
+        }
+    }
+}
+
+/// A handle that keeps what it points to alive. Cloning it adds a
+/// reference; when the last one drops, the id is released to its
+/// `RefCounts`.
+pub struct StrongHandle<T> {
+    inner: Arc<Tracked<T>>,
+}
+
+impl<T> StrongHandle<T> {
+    pub fn id(&self) -> Handle<T> {
+        self.inner.id
+    }
+
+    /// A handle that doesn't keep the value alive.
+    pub fn downgrade(&self) -> WeakHandle<T> {
+        WeakHandle {
+            id: self.inner.id,
+            inner: Arc::downgrade(&self.inner),
+        }
+    }
+
+    /// The number of strong handles to this id, including this one.
+    pub fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+}
+
+impl<T> Clone for StrongHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> PartialEq for StrongHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl<T> Eq for StrongHandle<T> {}
+
+impl<T> core::hash::Hash for StrongHandle<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
+
+impl<T> core::fmt::Debug for StrongHandle<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Strong({:?})", self.id())
+    }
+}
+
+/// A handle that doesn't keep what it points to alive, for caches and
+/// back-references. `upgrade` to use it.
+pub struct WeakHandle<T> {
+    id: Handle<T>,
+    inner: Weak<Tracked<T>>,
+}
+
+impl<T> WeakHandle<T> {
+    pub fn id(&self) -> Handle<T> {
+        self.id
+    }
+
+    /// A strong handle, or `None` once every strong handle has dropped.
+    pub fn upgrade(&self) -> Option<StrongHandle<T>> {
+        self.inner.upgrade().map(|inner| StrongHandle { inner })
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.inner.strong_count() > 0
+    }
+}
+
+impl<T> Clone for WeakHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> PartialEq for WeakHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for WeakHandle<T> {}
+
+impl<T> core::hash::Hash for WeakHandle<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> core::fmt::Debug for WeakHandle<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Weak({:?})", self.id)
+    }
+}
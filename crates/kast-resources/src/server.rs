@@ -0,0 +1,761 @@
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::loader::{AssetLoader, LoadContext, Loaders};
+use crate::vfs::normalize;
+use crate::{Assets, Handle, HandleAllocator, StrongHandle, TaskPool, Vfs, WeakHandle};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetError {
+    Io(String),
+    Decode(String),
+    /// No registered loader produces the requested type from this file.
+    NoLoader(String),
+    /// The settings passed to `load_with_settings` aren't the loader's.
+    InvalidSettings(String),
+}
+
+impl core::fmt::Display for AssetError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl core::error::Error for AssetError {}
+
+/// A type the `AssetServer` can store. Files are turned into assets by an
+/// `AssetLoader`.
+pub trait Asset: Send + Sync + 'static {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadState {
+    Loading,
+    Loaded,
+    Failed(AssetError),
+}
+
+/// What happened to an asset during the last `AssetServer::update`.
+pub enum AssetEvent<A> {
+    /// The asset finished loading, or was added directly.
+    Loaded(Handle<A>),
+    /// Loading failed; `AssetServer::load_state` has the error. A failed
+    /// reload keeps the previous version of the asset, which stays `Loaded`;
+    /// its error is in `AssetServer::reload_error` instead.
+    Failed(Handle<A>),
+    /// The file changed and was loaded again; the handles now lead to the
+    /// new version.
+    Modified(Handle<A>),
+    /// The last strong handle dropped and the asset was freed.
+    Removed(Handle<A>),
+}
+
+impl<A> Clone for AssetEvent<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A> Copy for AssetEvent<A> {}
+
+impl<A> core::fmt::Debug for AssetEvent<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Loaded(id) => write!(f, "Loaded({id:?})"),
+            Self::Failed(id) => write!(f, "Failed({id:?})"),
+            Self::Modified(id) => write!(f, "Modified({id:?})"),
+            Self::Removed(id) => write!(f, "Removed({id:?})"),
+        }
+    }
+}
+
+/// The id of an asset of any type, for tracking dependencies.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct UntypedId {
+    type_id: TypeId,
+    index: u32,
+    generation: u32,
+}
+
+impl UntypedId {
+    fn of<A: Asset>(id: Handle<A>) -> Self {
+        Self {
+            type_id: TypeId::of::<A>(),
+            index: id.index(),
+            generation: id.generation(),
+        }
+    }
+}
+
+type Finished = Box<dyn FnOnce(&mut AssetServer) + Send>;
+
+/// An asset a loader asked for, to be started on the main thread if it
+/// wasn't loaded or loading already.
+pub(crate) struct Dependency {
+    id: UntypedId,
+    start: Option<Finished>,
+}
+
+impl Dependency {
+    pub fn new<A: Asset>(handle: &StrongHandle<A>, start_path: Option<PathBuf>) -> Self {
+        let id = handle.id();
+        Self {
+            id: UntypedId::of(id),
+            start: start_path.map(|path| -> Finished {
+                Box::new(move |server| server.start_load(id, &path, None, false))
+            }),
+        }
+    }
+}
+
+/// The part of an asset type's storage worker threads can reach: handing out
+/// handles, and finding the one already loaded from a path.
+pub(crate) struct Shared<A> {
+    allocator: HandleAllocator<A>,
+    paths: Mutex<HashMap<PathBuf, WeakHandle<A>>>,
+}
+
+impl<A> Shared<A> {
+    /// The live handle loaded from `path`, or a new one and `true` if there
+    /// is none and the caller must start the load.
+    pub fn handle_for(&self, path: &Path) -> (StrongHandle<A>, bool) {
+        let mut paths = self.paths.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(handle) = paths.get(path).and_then(WeakHandle::upgrade) {
+            return (handle, false);
+        }
+
+        let handle = self.allocator.reserve();
+        paths.insert(path.to_path_buf(), handle.downgrade());
+        (handle, true)
+    }
+}
+
+/// Every asset type's `Shared`, created on first use from either side.
+#[derive(Default)]
+pub(crate) struct SharedHandles {
+    types: Mutex<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl SharedHandles {
+    pub fn get<A: Asset>(&self) -> Arc<Shared<A>> {
+        let shared = self
+            .types
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(TypeId::of::<A>())
+            .or_insert_with(|| {
+                Arc::new(Shared::<A> {
+                    allocator: HandleAllocator::new(),
+                    paths: Mutex::new(HashMap::new()),
+                })
+            })
+            .clone();
+
+        shared
+            .downcast()
+            .unwrap_or_else(|_| unreachable!("shared handles are keyed by their type"))
+    }
+}
+
+struct Storage<A> {
+    shared: Arc<Shared<A>>,
+    assets: Assets<A>,
+    states: HashMap<Handle<A>, LoadState>,
+    /// Why the last reload failed, for assets still serving an old version.
+    reload_errors: HashMap<Handle<A>, AssetError>,
+    dependencies: HashMap<Handle<A>, Vec<UntypedId>>,
+    /// What each file was loaded with, to load it the same way on changes.
+    settings: HashMap<Handle<A>, Arc<dyn Any + Send + Sync>>,
+    queued: Vec<AssetEvent<A>>,
+    events: Vec<AssetEvent<A>>,
+}
+
+impl<A> Storage<A> {
+    fn new(shared: Arc<Shared<A>>) -> Self {
+        Self {
+            assets: Assets::with_allocator(shared.allocator.clone()),
+            shared,
+            states: HashMap::new(),
+            reload_errors: HashMap::new(),
+            dependencies: HashMap::new(),
+            settings: HashMap::new(),
+            queued: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+/// The type-erased half of `Storage`, so the server can reach every type.
+trait AnyStorage: Any + Send + Sync {
+    fn remove_unused(&mut self);
+    fn publish(&mut self);
+    fn state(&self, index: u32, generation: u32) -> Option<&LoadState>;
+    fn dependencies(&self, index: u32, generation: u32) -> &[UntypedId];
+    /// Starts loading the asset from `path` again, if there is a live one.
+    fn reload(&self, path: &Path) -> Option<Finished>;
+}
+
+impl<A: Asset> AnyStorage for Storage<A> {
+    fn remove_unused(&mut self) {
+        for (id, _) in self.assets.remove_unused() {
+            self.states.remove(&id);
+            self.reload_errors.remove(&id);
+            self.dependencies.remove(&id);
+            self.settings.remove(&id);
+            self.queued.push(AssetEvent::Removed(id));
+        }
+
+        self.shared
+            .paths
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|_, handle| handle.is_alive());
+    }
+
+    fn publish(&mut self) {
+        self.events = core::mem::take(&mut self.queued);
+    }
+
+    fn state(&self, index: u32, generation: u32) -> Option<&LoadState> {
+        self.states.get(&Handle::from_raw_parts(index, generation))
+    }
+
+    fn dependencies(&self, index: u32, generation: u32) -> &[UntypedId] {
+        self.dependencies
+            .get(&Handle::from_raw_parts(index, generation))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    fn reload(&self, path: &Path) -> Option<Finished> {
+        let paths = self
+            .shared
+            .paths
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let handle = paths.get(path).filter(|handle| handle.is_alive())?;
+
+        let id = handle.id();
+        let settings = self.settings.get(&id).cloned();
+        let path = path.to_path_buf();
+        Some(Box::new(move |server| {
+            server.start_load(id, &path, settings, true)
+        }))
+    }
+}
+
+/// Loads assets from files in a `Vfs` on background threads.
+///
+/// `load` returns a handle right away while worker threads read the file and
+/// run the `AssetLoader` registered for its extension. Finished loads are
+/// picked up by `update`, which the app calls once per frame on the main
+/// thread; until then `get` returns `None` and `load_state` reports
+/// `Loading`.
+///
+/// Each `update` also frees assets whose last strong handle dropped, and
+/// publishes what happened as `AssetEvent`s, e.g. for the renderer to upload
+/// freshly loaded textures.
+///
+/// After `watch_for_changes`, files that change in directories mounted into
+/// the `Vfs` are loaded again with the same loader and settings, and swapped
+/// in behind the existing handles.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use kast_resources::*;
+/// # struct Texture;
+/// # impl Asset for Texture {}
+/// # struct TextureLoader;
+/// # impl AssetLoader for TextureLoader {
+/// #     type Asset = Texture;
+/// #     type Settings = ();
+/// #     fn extensions(&self) -> &[&str] {
+/// #         &["png"]
+/// #     }
+/// #     fn load(&self, _: &[u8], _: &(), _: &mut LoadContext) -> Result<Texture, AssetError> {
+/// #         Ok(Texture)
+/// #     }
+/// # }
+/// # fn upload(_: &Texture) {}
+/// # let vfs = Vfs::new();
+/// # vfs.mount("", MemorySource::new().with_file("textures/grass.png", &b"png"[..]));
+/// # let mut assets = AssetServer::with_vfs(Arc::new(vfs));
+/// # assets.register_loader(TextureLoader);
+/// let grass: StrongHandle<Texture> = assets.load("textures/grass.png");
+///
+/// // Frames later, once `update` has run:
+/// # while assets.load_state(grass.id()) == Some(&LoadState::Loading) {
+/// #     assets.update();
+/// # }
+/// if let Some(texture) = assets.get(grass.id()) {
+///     upload(texture);
+/// }
+/// # assert!(assets.is_loaded(grass.id()));
+/// ```
+pub struct AssetServer {
+    vfs: Arc<Vfs>,
+    storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+    loaders: Loaders,
+    handles: Arc<SharedHandles>,
+    task_pool: TaskPool,
+    finished: Sender<Finished>,
+    finished_receiver: Mutex<Receiver<Finished>>,
+    watcher: Option<Mutex<RecommendedWatcher>>,
+    /// Files the watcher saw change since the last update, as `Vfs` paths.
+    changed: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl AssetServer {
+    /// Loads from the directory `root`, using up to four worker threads.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self::with_vfs(Arc::new(Vfs::with_directory(root)))
+    }
+
+    /// Loads from whatever is mounted into `vfs`, using up to four worker
+    /// threads.
+    pub fn with_vfs(vfs: Arc<Vfs>) -> Self {
+        let workers = std::thread::available_parallelism().map_or(2, |count| count.get().min(4));
+        Self::with_workers(vfs, workers)
+    }
+
+    pub fn with_workers(vfs: Arc<Vfs>, workers: usize) -> Self {
+        let (finished, finished_receiver) = channel();
+        Self {
+            vfs,
+            storages: HashMap::new(),
+            loaders: Loaders::default(),
+            handles: Arc::default(),
+            task_pool: TaskPool::named("kast-assets", workers),
+            finished,
+            finished_receiver: Mutex::new(finished_receiver),
+            watcher: None,
+            changed: Arc::default(),
+        }
+    }
+
+    /// The namespace assets are read from. More sources can be mounted at
+    /// any time.
+    pub fn vfs(&self) -> &Arc<Vfs> {
+        &self.vfs
+    }
+
+    /// Reloads assets whose files change in the directories mounted so far
+    /// from now on. Archives and in-memory sources aren't watched.
+    pub fn watch_for_changes(&mut self) -> Result<(), AssetError> {
+        if self.watcher.is_some() {
+            return Ok(());
+        }
+
+        let io_error = |error: notify::Error| AssetError::Io(error.to_string());
+        // Events carry absolute paths, so compare against absolute
+        // directories. Missing ones have nothing to watch.
+        let directories: Vec<(PathBuf, PathBuf)> = self
+            .vfs
+            .directories()
+            .into_iter()
+            .filter_map(|(point, directory)| Some((point, std::fs::canonicalize(directory).ok()?)))
+            .collect();
+
+        let changed = self.changed.clone();
+        let watched = directories.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                // Editors often save by writing a new file and renaming it over
+                // the old one, which shows up as a create.
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    return;
+                }
+
+                let mut changed = changed.lock().unwrap_or_else(PoisonError::into_inner);
+                for path in event.paths {
+                    for (point, directory) in &watched {
+                        if let Ok(path) = path.strip_prefix(directory) {
+                            changed.insert(point.join(path));
+                        }
+                    }
+                }
+            })
+            .map_err(io_error)?;
+
+        for (_, directory) in &directories {
+            watcher
+                .watch(directory, RecursiveMode::Recursive)
+                .map_err(io_error)?;
+        }
+        self.watcher = Some(Mutex::new(watcher));
+        Ok(())
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.watcher.is_some()
+    }
+
+    /// Adds a loader for the extensions it declares. For extensions several
+    /// loaders of the same asset type claim, the last one registered wins.
+    pub fn register_loader(&mut self, loader: impl AssetLoader) {
+        self.loaders.register(loader);
+    }
+
+    /// Starts loading `path` from the `Vfs`, unless it's already
+    /// loaded or loading, in which case the existing handle is returned.
+    pub fn load<A: Asset>(&mut self, path: impl AsRef<Path>) -> StrongHandle<A> {
+        self.load_from(path.as_ref(), None)
+    }
+
+    /// Like `load`, with options for the loader, which must be its
+    /// `AssetLoader::Settings`. Settings are ignored if `path` is already
+    /// loaded or loading.
+    pub fn load_with_settings<A: Asset, S: Send + Sync + 'static>(
+        &mut self,
+        path: impl AsRef<Path>,
+        settings: S,
+    ) -> StrongHandle<A> {
+        self.load_from(path.as_ref(), Some(Arc::new(settings)))
+    }
+
+    /// Stores an asset that didn't come from a file, such as a generated one.
+    pub fn add<A: Asset>(&mut self, asset: A) -> StrongHandle<A> {
+        let storage = self.storage_mut::<A>();
+        let handle = storage.assets.add(asset);
+        storage.states.insert(handle.id(), LoadState::Loaded);
+        storage.queued.push(AssetEvent::Loaded(handle.id()));
+        handle
+    }
+
+    /// `None` while the asset is loading, if it failed, or once it's freed.
+    pub fn get<A: Asset>(&self, id: Handle<A>) -> Option<&A> {
+        self.storage::<A>()?.assets.get(id)
+    }
+
+    pub fn get_mut<A: Asset>(&mut self, id: Handle<A>) -> Option<&mut A> {
+        self.storage_mut::<A>().assets.get_mut(id)
+    }
+
+    /// `None` for ids that were never loaded or have been freed.
+    pub fn load_state<A: Asset>(&self, id: Handle<A>) -> Option<&LoadState> {
+        self.storage::<A>()?.states.get(&id)
+    }
+
+    /// Why the last reload of the asset failed, while it keeps serving the
+    /// previous version. Cleared once a reload succeeds.
+    pub fn reload_error<A: Asset>(&self, id: Handle<A>) -> Option<&AssetError> {
+        self.storage::<A>()?.reload_errors.get(&id)
+    }
+
+    pub fn is_loaded<A: Asset>(&self, id: Handle<A>) -> bool {
+        self.load_state(id) == Some(&LoadState::Loaded)
+    }
+
+    /// Whether the asset and everything its loader depended on, directly or
+    /// not, are loaded.
+    pub fn is_loaded_with_dependencies<A: Asset>(&self, id: Handle<A>) -> bool {
+        let mut pending = vec![UntypedId::of(id)];
+        let mut visited = HashSet::new();
+
+        while let Some(id) = pending.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+
+            let Some(storage) = self.storages.get(&id.type_id) else {
+                return false;
+            };
+            if storage.state(id.index, id.generation) != Some(&LoadState::Loaded) {
+                return false;
+            }
+            pending.extend_from_slice(storage.dependencies(id.index, id.generation));
+        }
+
+        true
+    }
+
+    /// The stored assets of type `A`, skipping those still loading.
+    pub fn iter<A: Asset>(&self) -> impl Iterator<Item = (Handle<A>, &A)> {
+        self.storage::<A>()
+            .into_iter()
+            .flat_map(|storage| storage.assets.iter())
+    }
+
+    /// What happened to assets of type `A` during the last `update`.
+    pub fn events<A: Asset>(&self) -> &[AssetEvent<A>] {
+        self.storage::<A>().map_or(&[], |storage| &storage.events)
+    }
+
+    /// Frees unused assets, reloads changed files, stores finished loads,
+    /// and publishes the events for all of them. Call once per frame from
+    /// the main thread.
+    pub fn update(&mut self) {
+        for storage in self.storages.values_mut() {
+            storage.remove_unused();
+        }
+
+        // Saving a file usually fires several events; the set merges those
+        // that arrived within a frame.
+        let changed =
+            core::mem::take(&mut *self.changed.lock().unwrap_or_else(PoisonError::into_inner));
+        let reloads: Vec<Finished> = changed
+            .iter()
+            .flat_map(|path| {
+                self.storages
+                    .values()
+                    .filter_map(|storage| storage.reload(path))
+            })
+            .collect();
+        for reload in reloads {
+            reload(self);
+        }
+
+        let finished: Vec<Finished> = self
+            .finished_receiver
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_iter()
+            .collect();
+        for finish in finished {
+            finish(self);
+        }
+
+        for storage in self.storages.values_mut() {
+            storage.publish();
+        }
+    }
+
+    fn load_from<A: Asset>(
+        &mut self,
+        path: &Path,
+        settings: Option<Arc<dyn Any + Send + Sync>>,
+    ) -> StrongHandle<A> {
+        // Spellings of the same file share a handle. Paths leaving the root
+        // are kept as is and fail to read.
+        let path = normalize(path).unwrap_or_else(|| path.to_path_buf());
+        let (handle, is_new) = self.storage_mut::<A>().shared.handle_for(&path);
+        if is_new {
+            self.start_load(handle.id(), &path, settings, false);
+        }
+        handle
+    }
+
+    fn start_load<A: Asset>(
+        &mut self,
+        id: Handle<A>,
+        path: &Path,
+        settings: Option<Arc<dyn Any + Send + Sync>>,
+        reload: bool,
+    ) {
+        // A dependency whose handle dropped before it got here.
+        if !self.storage_mut::<A>().assets.contains(id) {
+            return;
+        }
+
+        let Some(loader) = self.loaders.find(TypeId::of::<A>(), path) else {
+            let error = AssetError::NoLoader(path.display().to_string());
+            return self.fail(id, error);
+        };
+
+        let settings = match settings {
+            Some(settings) if Any::type_id(&*settings) == loader.settings_type() => settings,
+            Some(_) => {
+                let error = AssetError::InvalidSettings(path.display().to_string());
+                return self.fail(id, error);
+            }
+            None => loader.default_settings(),
+        };
+
+        let storage = self.storage_mut::<A>();
+        storage.settings.insert(id, settings.clone());
+        // A reloading asset stays usable until the new version replaces it.
+        if !reload {
+            storage.states.insert(id, LoadState::Loading);
+        }
+
+        let path = path.to_path_buf();
+        let vfs = self.vfs.clone();
+        let handles = self.handles.clone();
+        let finished = self.finished.clone();
+        self.task_pool.spawn(move || {
+            let mut context = LoadContext::new(&path, &handles);
+            let result = vfs
+                .read(&path)
+                .map_err(|error| AssetError::Io(error.to_string()))
+                .and_then(|bytes| {
+                    // A panicking loader fails its asset instead of killing
+                    // the worker and leaving the asset loading forever.
+                    panic::catch_unwind(AssertUnwindSafe(|| {
+                        loader.load(&bytes, &*settings, &mut context)
+                    }))
+                    .unwrap_or_else(|_| Err(AssetError::Decode("the loader panicked".to_string())))
+                });
+            let dependencies = context.dependencies;
+
+            // The server may have been dropped mid-load; nothing is waiting.
+            let _ = finished.send(Box::new(move |server: &mut AssetServer| {
+                server.finish(id, result, dependencies);
+            }));
+        });
+    }
+
+    fn finish<A: Asset>(
+        &mut self,
+        id: Handle<A>,
+        result: Result<Box<dyn Any + Send>, AssetError>,
+        dependencies: Vec<Dependency>,
+    ) {
+        let mut dependency_ids = Vec::with_capacity(dependencies.len());
+        for dependency in dependencies {
+            dependency_ids.push(dependency.id);
+            if let Some(start) = dependency.start {
+                start(self);
+            }
+        }
+
+        let asset = match result {
+            Ok(asset) => *asset
+                .downcast::<A>()
+                .expect("loaders are picked by the type they produce"),
+            Err(error) => return self.fail(id, error),
+        };
+
+        let storage = self.storage_mut::<A>();
+        let event = match storage.assets.insert(id, asset) {
+            Ok(Some(_previous)) => AssetEvent::Modified(id),
+            Ok(None) => AssetEvent::Loaded(id),
+            // Every handle dropped while loading: nobody wants it anymore.
+            Err(_) => return,
+        };
+
+        storage.dependencies.insert(id, dependency_ids);
+        storage.states.insert(id, LoadState::Loaded);
+        storage.reload_errors.remove(&id);
+        storage.queued.push(event);
+    }
+
+    fn fail<A: Asset>(&mut self, id: Handle<A>, error: AssetError) {
+        let storage = self.storage_mut::<A>();
+        if !storage.assets.contains(id) {
+            return;
+        }
+
+        // The previous version is still served, so the asset stays loaded.
+        if storage.assets.get(id).is_some() {
+            storage.reload_errors.insert(id, error);
+        } else {
+            storage.states.insert(id, LoadState::Failed(error));
+        }
+        storage.queued.push(AssetEvent::Failed(id));
+    }
+
+    fn storage<A: Asset>(&self) -> Option<&Storage<A>> {
+        let storage: &dyn Any = self.storages.get(&TypeId::of::<A>())?.as_ref();
+        storage.downcast_ref()
+    }
+
+    fn storage_mut<A: Asset>(&mut self) -> &mut Storage<A> {
+        let handles = &self.handles;
+        let storage: &mut dyn Any = self
+            .storages
+            .entry(TypeId::of::<A>())
+            .or_insert_with(|| Box::new(Storage::new(handles.get::<A>())))
+            .as_mut();
+        storage
+            .downcast_mut()
+            .expect("asset storages are keyed by their type")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::MemorySource;
+
+    struct Text(String);
+
+    impl Asset for Text {}
+
+    struct TextLoader;
+
+    impl AssetLoader for TextLoader {
+        type Asset = Text;
+        type Settings = ();
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn load(&self, bytes: &[u8], _: &(), _: &mut LoadContext) -> Result<Text, AssetError> {
+            String::from_utf8(bytes.to_vec())
+                .map(Text)
+                .map_err(|error| AssetError::Decode(error.to_string()))
+        }
+    }
+
+    fn update_until(server: &mut AssetServer, mut done: impl FnMut(&AssetServer) -> bool) {
+        let start = Instant::now();
+        while !done(server) {
+            assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+            std::thread::sleep(Duration::from_millis(5));
+            server.update();
+        }
+    }
+
+    #[test]
+    fn failed_reload_keeps_the_old_version_loaded() {
+        let vfs = Arc::new(Vfs::new());
+        vfs.mount("", MemorySource::new().with_file("a.txt", b"one".to_vec()));
+        let mut server = AssetServer::with_workers(vfs.clone(), 1);
+        server.register_loader(TextLoader);
+
+        let handle: StrongHandle<Text> = server.load("a.txt");
+        let id = handle.id();
+        update_until(&mut server, |server| server.is_loaded(id));
+        // Drop the `Loaded` event.
+        server.update();
+
+        vfs.mount("", MemorySource::new().with_file("a.txt", vec![0xff]));
+        server.start_load(id, Path::new("a.txt"), None, true);
+        update_until(&mut server, |server| !server.events::<Text>().is_empty());
+
+        assert!(matches!(server.events::<Text>(), [AssetEvent::Failed(_)]));
+        assert_eq!(server.load_state(id), Some(&LoadState::Loaded));
+        assert!(matches!(
+            server.reload_error(id),
+            Some(AssetError::Decode(_))
+        ));
+        assert_eq!(server.get(id).unwrap().0, "one");
+        server.update();
+
+        vfs.mount("", MemorySource::new().with_file("a.txt", b"two".to_vec()));
+        server.start_load(id, Path::new("a.txt"), None, true);
+        update_until(&mut server, |server| !server.events::<Text>().is_empty());
+
+        assert!(matches!(server.events::<Text>(), [AssetEvent::Modified(_)]));
+        assert_eq!(server.reload_error(id), None);
+        assert_eq!(server.get(id).unwrap().0, "two");
+    }
+
+    #[test]
+    fn failed_first_load_is_reported_in_the_state() {
+        let mut server = AssetServer::with_vfs(Arc::new(Vfs::new()));
+        server.register_loader(TextLoader);
+
+        let handle: StrongHandle<Text> = server.load("missing.txt");
+        let id = handle.id();
+        update_until(&mut server, |server| {
+            server.load_state(id) != Some(&LoadState::Loading)
+        });
+
+        assert!(matches!(
+            server.load_state(id),
+            Some(LoadState::Failed(AssetError::Io(_)))
+        ));
+        assert_eq!(server.reload_error(id), None);
+    }
+}
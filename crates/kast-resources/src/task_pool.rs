@@ -0,0 +1,269 @@
+use std::marker::PhantomData;
+use std::num::NonZero;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::sync::{Arc, Condvar, Mutex, mpsc};
+use std::thread::JoinHandle;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// A fixed set of worker threads running jobs in the order they're queued.
+///
+/// Threads are created once and reused every frame; `scope` lets jobs borrow
+/// from the caller's stack (the `World`, the systems) by blocking until every
+/// job it spawned has finished, while `spawn` queues jobs nobody waits for,
+/// such as asset loads.
+pub struct TaskPool {
+    sender: Option<mpsc::Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl TaskPool {
+    pub fn new(threads: usize) -> Self {
+        Self::named("kast-worker", threads)
+    }
+
+    /// A pool whose threads are called `{name}-0`, `{name}-1` and so on.
+    pub fn named(name: &str, threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..threads.max(1))
+            .map(|index| {
+                let receiver = Arc::clone(&receiver);
+                std::thread::Builder::new()
+                    .name(format!("{name}-{index}"))
+                    .spawn(move || {
+                        loop {
+                            let job = match receiver.lock() {
+                                Ok(receiver) => receiver.recv(),
+                                Err(_) => return,
+                            };
+                            match job {
+                                // A panicking job was reported by the panic
+                                // hook; keep the worker for the next one.
+                                Ok(job) => {
+                                    let _ = catch_unwind(AssertUnwindSafe(job));
+                                }
+                                Err(_) => return,
+                            }
+                        }
+                    })
+                    .expect("failed to spawn task pool worker")
+            })
+            .collect();
+
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// A pool with one worker per available hardware thread.
+    pub fn with_available_parallelism() -> Self {
+        Self::new(std::thread::available_parallelism().map_or(1, NonZero::get))
+    }
+
+    pub fn thread_count(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Queues a job that runs on its own, without anything waiting for it.
+    /// Jobs still queued when the pool is dropped run before it returns.
+    pub fn spawn(&self, job: impl FnOnce() + Send + 'static) {
+        self.send(Box::new(job));
+    }
+
+    fn send(&self, job: Job) {
+        self.sender
+            .as_ref()
+            .expect("task pool is shutting down")
+            .send(job)
+            .expect("task pool workers have exited");
+    }
+
+    /// Runs `f`, letting it spawn jobs that borrow anything outliving the
+    /// call. Returns once `f` and every spawned job have completed.
+    ///
+    /// If `f` or any job panics, the panic is resumed on the calling thread
+    /// after all jobs are done.
+    pub fn scope<'env, F, R>(&self, f: F) -> R
+    where
+        F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> R,
+    {
+        let scope = Scope {
+            pool: self,
+            state: Arc::new(ScopeState {
+                pending: Mutex::new(0),
+                done: Condvar::new(),
+                panic: Mutex::new(None),
+            }),
+            _env: PhantomData,
+        };
+
+        let result = catch_unwind(AssertUnwindSafe(|| f(&scope)));
+
+        let mut pending = scope.state.pending.lock().unwrap();
+        while *pending > 0 {
+            pending = scope.state.done.wait(pending).unwrap();
+        }
+        drop(pending);
+
+        if let Some(payload) = scope.state.panic.lock().unwrap().take() {
+            resume_unwind(payload);
+        }
+
+        match result {
+            Ok(result) => result,
+            Err(payload) => resume_unwind(payload),
+        }
+    }
+}
+
+impl Drop for TaskPool {
+    fn drop(&mut self) {
+        // Closing the channel lets the workers finish the queue and return.
+        self.sender.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+struct ScopeState {
+    pending: Mutex<usize>,
+    done: Condvar,
+    panic: Mutex<Option<Box<dyn std::any::Any + Send>>>,
+}
+
+/// Handle for spawning borrowed jobs onto a `TaskPool`; see `TaskPool::scope`.
+pub struct Scope<'scope, 'env: 'scope> {
+    pool: &'scope TaskPool,
+    state: Arc<ScopeState>,
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    pub fn spawn<F: FnOnce() + Send + 'env>(&self, f: F) {
+        *self.state.pending.lock().unwrap() += 1;
+
+        let state = Arc::clone(&self.state);
+        let job: Box<dyn FnOnce() + Send + 'env> = Box::new(move || {
+            if let Err(payload) = catch_unwind(AssertUnwindSafe(f)) {
+                state.panic.lock().unwrap().get_or_insert(payload);
+            }
+
+            let mut pending = state.pending.lock().unwrap();
+            *pending -= 1;
+            if *pending == 0 {
+                state.done.notify_all();
+            }
+        });
+
+        // SAFETY: `TaskPool::scope` doesn't return until `pending` drops back
+        // to zero, so the job never outlives the `'env` borrows it captures.
+        let job: Job = unsafe { core::mem::transmute(job) };
+        self.pool.send(job);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use super::TaskPool;
+
+    #[test]
+    fn scoped_jobs_borrow_the_callers_stack() {
+        let pool = TaskPool::new(4);
+        let mut totals = [0; 8];
+        let values: Vec<usize> = (0..800).collect();
+
+        let chunks = pool.scope(|scope| {
+            for (total, chunk) in totals.iter_mut().zip(values.chunks(100)) {
+                scope.spawn(move || *total = chunk.iter().sum());
+            }
+            values.len() / 100
+        });
+
+        assert_eq!(chunks, 8);
+        assert_eq!(totals.iter().sum::<usize>(), values.iter().sum());
+    }
+
+    #[test]
+    fn job_panics_are_raised_after_every_job_finishes() {
+        let pool = TaskPool::new(2);
+        let finished = AtomicUsize::new(0);
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            pool.scope(|scope| {
+                scope.spawn(|| panic!("boom"));
+                for _ in 0..4 {
+                    scope.spawn(|| {
+                        std::thread::sleep(Duration::from_millis(10));
+                        finished.fetch_add(1, Ordering::Relaxed);
+                    });
+                }
+            })
+        }));
+
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+        assert_eq!(finished.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn a_panicking_scope_still_waits_for_its_jobs() {
+        let pool = TaskPool::new(2);
+        let finished = AtomicUsize::new(0);
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            pool.scope(|scope| {
+                scope.spawn(|| {
+                    std::thread::sleep(Duration::from_millis(20));
+                    finished.fetch_add(1, Ordering::Relaxed);
+                });
+                panic!("scope");
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(finished.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn workers_survive_panicking_jobs() {
+        let pool = TaskPool::new(1);
+        let (sender, receiver) = mpsc::channel();
+
+        pool.spawn(|| panic!("detached"));
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            pool.scope(|scope| scope.spawn(|| panic!("scoped")))
+        }));
+        pool.spawn(move || {
+            sender
+                .send(std::thread::current().name().map(str::to_owned))
+                .unwrap()
+        });
+
+        let name = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(name.as_deref(), Some("kast-worker-0"));
+    }
+
+    #[test]
+    fn dropping_the_pool_runs_queued_jobs() {
+        let finished = std::sync::Arc::new(AtomicUsize::new(0));
+        let pool = TaskPool::named("test", 1);
+        for _ in 0..10 {
+            let finished = finished.clone();
+            pool.spawn(move || {
+                finished.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+        drop(pool);
+
+        assert_eq!(finished.load(Ordering::Relaxed), 10);
+    }
+}
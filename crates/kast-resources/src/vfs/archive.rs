@@ -0,0 +1,447 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::vfs::normalize;
+use crate::{VfsError, VfsSource};
+
+// Layout, all integers little-endian:
+//
+//   magic        b"KPAK"
+//   version      u32
+//   entry count  u32
+//   entries      path length u32, UTF-8 path with `/` separators,
+//                offset u64, stored size u64, size u64, compression u8,
+//                hash u64
+//   data         each entry's stored bytes, at its offset from the start
+
+const MAGIC: &[u8; 4] = b"KPAK";
+const VERSION: u32 = 1;
+/// Magic, version and entry count.
+const HEADER_LEN: u64 = 4 + 4 + 4;
+/// An entry in the table of contents, without its path.
+const ENTRY_FIXED_LEN: u64 = 4 + 8 + 8 + 8 + 1 + 8;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None = 0,
+    Deflate = 1,
+}
+
+impl Compression {
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Deflate),
+            _ => None,
+        }
+    }
+}
+
+/// Where a file lives in an archive, as listed in its table of contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub offset: u64,
+    pub stored_size: u64,
+    /// The size once decompressed.
+    pub size: u64,
+    pub compression: Compression,
+    /// Whatever hash the writer recorded, such as `content_hash` of the
+    /// source the entry was built from. Reading doesn't check it.
+    pub hash: u64,
+}
+
+enum Backing {
+    File(Mutex<File>),
+    Memory(Arc<[u8]>),
+}
+
+/// Files packed into a single archive, as written by `ArchiveWriter`.
+///
+/// Only the table of contents is read up front; each file is read, and
+/// decompressed if needed, when asked for.
+pub struct ArchiveSource {
+    entries: HashMap<PathBuf, ArchiveEntry>,
+    backing: Backing,
+}
+
+impl ArchiveSource {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, VfsError> {
+        let path = path.as_ref();
+        let io_error = |error: std::io::Error| VfsError::Io(format!("{}: {error}", path.display()));
+
+        let file = File::open(path).map_err(io_error)?;
+        let len = file.metadata().map_err(io_error)?.len();
+        let entries = read_toc(&mut BufReader::new(&file), len)?;
+
+        Ok(Self {
+            entries,
+            backing: Backing::File(Mutex::new(file)),
+        })
+    }
+
+    /// An archive already in memory, e.g. one embedded with `include_bytes!`.
+    pub fn from_bytes(bytes: impl Into<Arc<[u8]>>) -> Result<Self, VfsError> {
+        let bytes = bytes.into();
+        let entries = read_toc(&mut &bytes[..], bytes.len() as u64)?;
+
+        Ok(Self {
+            entries,
+            backing: Backing::Memory(bytes),
+        })
+    }
+
+    pub fn entry(&self, path: impl AsRef<Path>) -> Option<&ArchiveEntry> {
+        self.entries.get(&normalize(path.as_ref())?)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&Path, &ArchiveEntry)> {
+        self.entries
+            .iter()
+            .map(|(path, entry)| (path.as_path(), entry))
+    }
+
+    fn read_stored(&self, entry: &ArchiveEntry) -> Result<Vec<u8>, VfsError> {
+        match &self.backing {
+            Backing::Memory(bytes) => {
+                let start = entry.offset as usize;
+                Ok(bytes[start..start + entry.stored_size as usize].to_vec())
+            }
+            Backing::File(file) => {
+                let mut file = file.lock().unwrap_or_else(PoisonError::into_inner);
+                let mut stored = vec![0; entry.stored_size as usize];
+                file.seek(SeekFrom::Start(entry.offset))
+                    .and_then(|_| file.read_exact(&mut stored))
+                    .map_err(|error| VfsError::Io(error.to_string()))?;
+                Ok(stored)
+            }
+        }
+    }
+}
+
+impl VfsSource for ArchiveSource {
+    fn read(&self, path: &Path) -> Result<Vec<u8>, VfsError> {
+        let corrupt = || VfsError::Corrupt(path.display().to_string());
+        let entry = self
+            .entries
+            .get(path)
+            .ok_or_else(|| VfsError::NotFound(path.display().to_string()))?;
+
+        let stored = self.read_stored(entry)?;
+        let bytes = match entry.compression {
+            Compression::None => stored,
+            Compression::Deflate => {
+                miniz_oxide::inflate::decompress_to_vec_with_limit(&stored, entry.size as usize)
+                    .map_err(|_| corrupt())?
+            }
+        };
+
+        if bytes.len() as u64 != entry.size {
+            return Err(corrupt());
+        }
+        Ok(bytes)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.entries.keys().cloned().collect()
+    }
+}
+
+fn read_toc(
+    reader: &mut impl Read,
+    archive_len: u64,
+) -> Result<HashMap<PathBuf, ArchiveEntry>, VfsError> {
+    let corrupt = |what: &str| VfsError::Corrupt(what.to_string());
+
+    let mut magic = [0; 4];
+    reader
+        .read_exact(&mut magic)
+        .map_err(|_| corrupt("missing header"))?;
+    if &magic != MAGIC {
+        return Err(corrupt("not an archive"));
+    }
+
+    let mut reader = TocReader { reader };
+    let version = reader.u32()?;
+    if version != VERSION {
+        return Err(VfsError::Corrupt(format!("unsupported version {version}")));
+    }
+
+    let count = reader.u32()?;
+    // Checked before allocating anything for the entries, so a bogus count
+    // can't ask for more memory than the archive could describe.
+    if u64::from(count) * ENTRY_FIXED_LEN > archive_len.saturating_sub(HEADER_LEN) {
+        return Err(corrupt("more entries than the archive holds"));
+    }
+    let mut entries = HashMap::with_capacity(count as usize);
+    for _ in 0..count {
+        let path_len = reader.u32()?;
+        let path = String::from_utf8(reader.bytes(path_len as usize)?)
+            .map_err(|_| corrupt("path is not UTF-8"))?;
+        let path = normalize(Path::new(&path)).ok_or_else(|| corrupt("path leaves the archive"))?;
+
+        let entry = ArchiveEntry {
+            offset: reader.u64()?,
+            stored_size: reader.u64()?,
+            size: reader.u64()?,
+            compression: Compression::from_u8(reader.u8()?)
+                .ok_or_else(|| corrupt("unknown compression"))?,
+            hash: reader.u64()?,
+        };
+        let end = entry.offset.checked_add(entry.stored_size);
+        if end.is_none_or(|end| end > archive_len) {
+            return Err(VfsError::Corrupt(format!(
+                "{} is out of bounds",
+                path.display()
+            )));
+        }
+
+        entries.insert(path, entry);
+    }
+
+    Ok(entries)
+}
+
+struct TocReader<'a, R> {
+    reader: &'a mut R,
+}
+
+impl<R: Read> TocReader<'_, R> {
+    fn bytes(&mut self, len: usize) -> Result<Vec<u8>, VfsError> {
+        let mut bytes = Vec::new();
+        self.reader
+            .take(len as u64)
+            .read_to_end(&mut bytes)
+            .map_err(|error| VfsError::Io(error.to_string()))?;
+        if bytes.len() != len {
+            return Err(VfsError::Corrupt("truncated table of contents".to_string()));
+        }
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], VfsError> {
+        let bytes = self.bytes(N)?;
+        Ok(bytes.try_into().expect("`bytes` reads exactly N bytes"))
+    }
+
+    fn u8(&mut self) -> Result<u8, VfsError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, VfsError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, VfsError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+}
+
+struct PendingEntry {
+    stored: Vec<u8>,
+    size: u64,
+    compression: Compression,
+    hash: u64,
+}
+
+/// Builds an archive for `ArchiveSource`.
+///
+/// Entries are written sorted by path, so the same files always produce the
+/// same archive.
+#[derive(Default)]
+pub struct ArchiveWriter {
+    entries: BTreeMap<String, PendingEntry>,
+}
+
+impl ArchiveWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file, replacing any previous one at `path`. Files that don't
+    /// shrink when compressed are stored as is.
+    pub fn add(
+        &mut self,
+        path: impl AsRef<Path>,
+        bytes: &[u8],
+        compression: Compression,
+        hash: u64,
+    ) -> Result<(), VfsError> {
+        let path = path.as_ref();
+        let invalid = || VfsError::InvalidPath(path.display().to_string());
+        let normalized = normalize(path).ok_or_else(invalid)?;
+        let parts: Option<Vec<&str>> = normalized.iter().map(|part| part.to_str()).collect();
+        let name = parts.ok_or_else(invalid)?.join("/");
+
+        let (stored, compression) = match compression {
+            Compression::Deflate => {
+                let compressed = miniz_oxide::deflate::compress_to_vec(bytes, 6);
+                if compressed.len() < bytes.len() {
+                    (compressed, Compression::Deflate)
+                } else {
+                    (bytes.to_vec(), Compression::None)
+                }
+            }
+            Compression::None => (bytes.to_vec(), Compression::None),
+        };
+
+        self.entries.insert(
+            name,
+            PendingEntry {
+                stored,
+                size: bytes.len() as u64,
+                compression,
+                hash,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<(), VfsError> {
+        let toc_len: u64 = self
+            .entries
+            .keys()
+            .map(|name| ENTRY_FIXED_LEN + name.len() as u64)
+            .sum();
+
+        let mut toc = Vec::with_capacity((HEADER_LEN + toc_len) as usize);
+        toc.extend_from_slice(MAGIC);
+        toc.extend_from_slice(&VERSION.to_le_bytes());
+        toc.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+
+        let mut offset = HEADER_LEN + toc_len;
+        for (name, entry) in &self.entries {
+            toc.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            toc.extend_from_slice(name.as_bytes());
+            toc.extend_from_slice(&offset.to_le_bytes());
+            toc.extend_from_slice(&(entry.stored.len() as u64).to_le_bytes());
+            toc.extend_from_slice(&entry.size.to_le_bytes());
+            toc.push(entry.compression as u8);
+            toc.extend_from_slice(&entry.hash.to_le_bytes());
+            offset += entry.stored.len() as u64;
+        }
+
+        let io_error = |error: std::io::Error| VfsError::Io(error.to_string());
+        writer.write_all(&toc).map_err(io_error)?;
+        for entry in self.entries.values() {
+            writer.write_all(&entry.stored).map_err(io_error)?;
+        }
+        writer.flush().map_err(io_error)
+    }
+
+    /// Writes the archive to a file, replacing it.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), VfsError> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .map_err(|error| VfsError::Io(format!("{}: {error}", path.display())))?;
+        self.write_to(&mut std::io::BufWriter::new(file))
+    }
+}
+
+/// A 64-bit FNV-1a hash, to tell whether content changed between builds.
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive_bytes() -> Vec<u8> {
+        let mut writer = ArchiveWriter::new();
+        writer
+            .add("textures/big.bin", &[7; 4096], Compression::Deflate, 1)
+            .unwrap();
+        writer
+            .add("./small.txt", b"hi", Compression::Deflate, 2)
+            .unwrap();
+
+        let mut bytes = Vec::new();
+        writer.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn written_archives_read_back() {
+        let archive = ArchiveSource::from_bytes(archive_bytes()).unwrap();
+
+        let big = archive.entry("textures/big.bin").unwrap();
+        assert_eq!(big.compression, Compression::Deflate);
+        assert_eq!((big.size, big.hash), (4096, 1));
+        // Compressing doesn't shrink it, so it's stored as is.
+        let small = archive.entry("small.txt").unwrap();
+        assert_eq!(small.compression, Compression::None);
+
+        assert_eq!(
+            archive.read(Path::new("textures/big.bin")).unwrap(),
+            [7; 4096]
+        );
+        assert_eq!(archive.read(Path::new("small.txt")).unwrap(), b"hi");
+        assert!(matches!(
+            archive.read(Path::new("missing.txt")),
+            Err(VfsError::NotFound(_))
+        ));
+
+        let mut files = archive.files();
+        files.sort();
+        assert_eq!(
+            files,
+            [Path::new("small.txt"), Path::new("textures/big.bin")]
+        );
+    }
+
+    #[test]
+    fn writer_rejects_paths_leaving_the_archive() {
+        let mut writer = ArchiveWriter::new();
+        let result = writer.add("../secret", b"", Compression::None, 0);
+
+        assert!(matches!(result, Err(VfsError::InvalidPath(_))));
+        assert!(writer.is_empty());
+    }
+
+    #[test]
+    fn truncated_archives_are_corrupt() {
+        let bytes = archive_bytes();
+        // Cut into the data, the table of contents and the header.
+        for len in [bytes.len() - 1, 20, 6, 0] {
+            let result = ArchiveSource::from_bytes(&bytes[..len]);
+            assert!(matches!(result, Err(VfsError::Corrupt(_))), "{len}");
+        }
+    }
+
+    #[test]
+    fn bogus_headers_are_corrupt() {
+        let bytes = archive_bytes();
+
+        let mut magic = bytes.clone();
+        magic[..4].copy_from_slice(b"NOPE");
+        let mut version = bytes.clone();
+        version[4..8].copy_from_slice(&99u32.to_le_bytes());
+        let mut count = bytes;
+        count[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        for bytes in [magic, version, count] {
+            assert!(matches!(
+                ArchiveSource::from_bytes(bytes),
+                Err(VfsError::Corrupt(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::{VfsError, VfsSource};
+
+/// Loose files under a directory on disk.
+pub struct DirectorySource {
+    root: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl VfsSource for DirectorySource {
+    fn read(&self, path: &Path) -> Result<Vec<u8>, VfsError> {
+        let full_path = self.root.join(path);
+        std::fs::read(&full_path).map_err(|error| match error.kind() {
+            ErrorKind::NotFound => VfsError::NotFound(path.display().to_string()),
+            _ => VfsError::Io(format!("{}: {error}", full_path.display())),
+        })
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.root.join(path).is_file()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut directories = vec![PathBuf::new()];
+        while let Some(directory) = directories.pop() {
+            let Ok(entries) = std::fs::read_dir(self.root.join(&directory)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = directory.join(entry.file_name());
+                match entry.file_type() {
+                    Ok(kind) if kind.is_dir() => directories.push(path),
+                    Ok(kind) if kind.is_file() => files.push(path),
+                    _ => {}
+                }
+            }
+        }
+        files
+    }
+
+    fn directory(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+}
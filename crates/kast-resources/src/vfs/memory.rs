@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::vfs::normalize;
+use crate::{VfsError, VfsSource};
+
+/// Files held in memory, for tests and generated content.
+#[derive(Default)]
+pub struct MemorySource {
+    files: HashMap<PathBuf, Arc<[u8]>>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a file. Paths climbing out with `..` are ignored.
+    pub fn insert(&mut self, path: impl AsRef<Path>, bytes: impl Into<Arc<[u8]>>) {
+        if let Some(path) = normalize(path.as_ref()) {
+            self.files.insert(path, bytes.into());
+        }
+    }
+
+    pub fn with_file(mut self, path: impl AsRef<Path>, bytes: impl Into<Arc<[u8]>>) -> Self {
+        self.insert(path, bytes);
+        self
+    }
+}
+
+impl VfsSource for MemorySource {
+    fn read(&self, path: &Path) -> Result<Vec<u8>, VfsError> {
+        self.files
+            .get(path)
+            .map(|bytes| bytes.to_vec())
+            .ok_or_else(|| VfsError::NotFound(path.display().to_string()))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.files.keys().cloned().collect()
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+
+mod archive;
+mod directory;
+mod memory;
+
+pub use archive::{ArchiveEntry, ArchiveSource, ArchiveWriter, Compression, content_hash};
+pub use directory::DirectorySource;
+pub use memory::MemorySource;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VfsError {
+    NotFound(String),
+    /// Paths can't leave the namespace with `..`.
+    InvalidPath(String),
+    Io(String),
+    /// An archive whose table of contents or data doesn't add up.
+    Corrupt(String),
+}
+
+impl core::fmt::Display for VfsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl core::error::Error for VfsError {}
+
+/// Somewhere files can be read from, mounted into a `Vfs`.
+///
+/// Paths passed in are relative to the mount point, normalized, and use the
+/// same separators on every platform.
+pub trait VfsSource: Send + Sync {
+    /// The file's bytes, or `VfsError::NotFound` to let earlier mounts try.
+    fn read(&self, path: &Path) -> Result<Vec<u8>, VfsError>;
+
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Every file in the source.
+    fn files(&self) -> Vec<PathBuf>;
+
+    /// The directory on disk backing the source, for watching changes.
+    fn directory(&self) -> Option<&Path> {
+        None
+    }
+}
+
+struct Mount {
+    point: PathBuf,
+    source: Arc<dyn VfsSource>,
+}
+
+/// One namespace over several sources, such as loose directories during
+/// development and packed archives in release builds.
+///
+/// Sources are mounted under a path prefix, the empty path being the root.
+/// When several contain a file, the one mounted last wins, so mods and
+/// patches go on top of the base game:
+///
+/// ```no_run
+/// # use kast_resources::{ArchiveSource, DirectorySource, Vfs};
+/// # fn main() -> Result<(), kast_resources::VfsError> {
+/// let vfs = Vfs::new();
+/// vfs.mount("", ArchiveSource::open("game.kpak")?);
+/// vfs.mount("", DirectorySource::new("mods/hd-textures"));
+///
+/// let grass = vfs.read("textures/grass.png")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Mounting only needs `&self`, so sources can be added while worker
+/// threads are reading.
+#[derive(Default)]
+pub struct Vfs {
+    mounts: RwLock<Vec<Mount>>,
+}
+
+impl Vfs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A namespace reading everything from one directory.
+    pub fn with_directory(root: impl Into<PathBuf>) -> Self {
+        let vfs = Self::new();
+        vfs.mount("", DirectorySource::new(root));
+        vfs
+    }
+
+    /// Mounts `source` under `point`, over everything mounted before.
+    pub fn mount(&self, point: impl AsRef<Path>, source: impl VfsSource + 'static) {
+        let point = normalize(point.as_ref()).unwrap_or_default();
+        self.mounts
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Mount {
+                point,
+                source: Arc::new(source),
+            });
+    }
+
+    pub fn read(&self, path: impl AsRef<Path>) -> Result<Vec<u8>, VfsError> {
+        let path = path.as_ref();
+        let normalized =
+            normalize(path).ok_or_else(|| VfsError::InvalidPath(path.display().to_string()))?;
+
+        for (source, relative) in self.candidates(&normalized) {
+            match source.read(&relative) {
+                Err(VfsError::NotFound(_)) => continue,
+                result => return result,
+            }
+        }
+
+        Err(VfsError::NotFound(path.display().to_string()))
+    }
+
+    pub fn exists(&self, path: impl AsRef<Path>) -> bool {
+        normalize(path.as_ref()).is_some_and(|path| {
+            self.candidates(&path)
+                .into_iter()
+                .any(|(source, relative)| source.exists(&relative))
+        })
+    }
+
+    /// Every file visible through any mount, sorted.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .mounts()
+            .iter()
+            .flat_map(|mount| {
+                mount
+                    .source
+                    .files()
+                    .into_iter()
+                    .map(|file| mount.point.join(file))
+            })
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// The directories on disk behind the mounts, with their mount points.
+    pub fn directories(&self) -> Vec<(PathBuf, PathBuf)> {
+        self.mounts()
+            .iter()
+            .filter_map(|mount| {
+                let directory = mount.source.directory()?;
+                Some((mount.point.clone(), directory.to_path_buf()))
+            })
+            .collect()
+    }
+
+    fn mounts(&self) -> std::sync::RwLockReadGuard<'_, Vec<Mount>> {
+        self.mounts.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The sources that could hold `path`, latest mount first, each with
+    /// the path relative to it.
+    fn candidates(&self, path: &Path) -> Vec<(Arc<dyn VfsSource>, PathBuf)> {
+        self.mounts()
+            .iter()
+            .rev()
+            .filter_map(|mount| {
+                let relative = path.strip_prefix(&mount.point).ok()?;
+                Some((mount.source.clone(), relative.to_path_buf()))
+            })
+            .collect()
+    }
+}
+
+/// `path` relative to the namespace root, without `.` components, or `None`
+/// if it climbs out with `..`.
+pub(crate) fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            Component::ParentDir => return None,
+        }
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_drops_dots_and_roots() {
+        assert_eq!(normalize(Path::new("a/./b")), Some(PathBuf::from("a/b")));
+        assert_eq!(normalize(Path::new("./a")), Some(PathBuf::from("a")));
+        assert_eq!(normalize(Path::new("/a/b")), Some(PathBuf::from("a/b")));
+        assert_eq!(normalize(Path::new(".")), Some(PathBuf::new()));
+    }
+
+    #[test]
+    fn normalize_rejects_parent_dirs() {
+        assert_eq!(normalize(Path::new("../a")), None);
+        assert_eq!(normalize(Path::new("a/../b")), None);
+        assert_eq!(normalize(Path::new("/..")), None);
+    }
+
+    #[test]
+    fn later_mounts_override_earlier_ones() {
+        let vfs = Vfs::new();
+        vfs.mount(
+            "",
+            MemorySource::new()
+                .with_file("a.txt", b"base".to_vec())
+                .with_file("data/b.txt", b"base".to_vec()),
+        );
+        vfs.mount(
+            "data",
+            MemorySource::new().with_file("b.txt", b"mod".to_vec()),
+        );
+
+        assert_eq!(vfs.read("a.txt").unwrap(), b"base");
+        assert_eq!(vfs.read("data/b.txt").unwrap(), b"mod");
+        assert_eq!(vfs.read("./data/b.txt").unwrap(), b"mod");
+        assert_eq!(vfs.files(), [Path::new("a.txt"), Path::new("data/b.txt")]);
+
+        vfs.mount(
+            "",
+            MemorySource::new().with_file("data/b.txt", b"patch".to_vec()),
+        );
+        assert_eq!(vfs.read("data/b.txt").unwrap(), b"patch");
+    }
+
+    #[test]
+    fn reads_report_missing_and_invalid_paths() {
+        let vfs = Vfs::new();
+        vfs.mount("", MemorySource::new().with_file("a.txt", b"a".to_vec()));
+
+        assert!(vfs.exists("a.txt"));
+        assert!(!vfs.exists("../a.txt"));
+        assert!(matches!(vfs.read("b.txt"), Err(VfsError::NotFound(_))));
+        assert!(matches!(
+            vfs.read("../a.txt"),
+            Err(VfsError::InvalidPath(_))
+        ));
+    }
+}
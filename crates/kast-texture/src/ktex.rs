@@ -0,0 +1,73 @@
+use crate::{ColorSpace, Image, ImageError, max_mip_levels, mip_chain_len};
+
+// Layout, all integers little-endian:
+//
+//   magic        b"KTEX"
+//   version      u32
+//   width        u32
+//   height       u32
+//   mip levels   u32
+//   color space  u8, 0 for sRGB and 1 for linear
+//   data         every mip level as in `Image::data`
+
+const MAGIC: &[u8; 4] = b"KTEX";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 4 + 4 * 4 + 1;
+
+impl Image {
+    /// Decodes an image baked by `to_ktex`, with its mipmaps.
+    pub fn from_ktex(bytes: &[u8]) -> Result<Self, ImageError> {
+        let header = bytes.get(..HEADER_LEN).ok_or(ImageError::InvalidKtex)?;
+        if &header[..4] != MAGIC {
+            return Err(ImageError::InvalidKtex);
+        }
+        let field = |index: usize| {
+            let start = 4 + index * 4;
+            u32::from_le_bytes(header[start..start + 4].try_into().unwrap())
+        };
+        if field(0) != VERSION {
+            return Err(ImageError::InvalidKtex);
+        }
+
+        let (width, height, mip_levels) = (field(1), field(2), field(3));
+        let color_space = match header[20] {
+            0 => ColorSpace::Srgb,
+            1 => ColorSpace::Linear,
+            _ => return Err(ImageError::InvalidKtex),
+        };
+        if width == 0
+            || height == 0
+            || mip_levels == 0
+            || mip_levels > max_mip_levels(width, height)
+        {
+            return Err(ImageError::InvalidKtex);
+        }
+
+        let data = &bytes[HEADER_LEN..];
+        if data.len() as u64 != mip_chain_len(width, height, mip_levels) {
+            return Err(ImageError::InvalidKtex);
+        }
+
+        let mut image = Self::new(width, height, color_space, data.to_vec());
+        image.mip_levels = mip_levels;
+        Ok(image)
+    }
+
+    /// Encodes the image with its mipmaps, in a format that loads without
+    /// decompressing or filtering anything.
+    pub fn to_ktex(&self) -> Vec<u8> {
+        let color_space = match self.color_space {
+            ColorSpace::Srgb => 0,
+            ColorSpace::Linear => 1,
+        };
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.data.len());
+        bytes.extend_from_slice(MAGIC);
+        for field in [VERSION, self.width, self.height, self.mip_levels] {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+        bytes.push(color_space);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+}
@@ -0,0 +1,141 @@
+//! CPU-side images: decoding, mipmap generation and the baked KTEX format.
+//!
+//! Kept apart from `kast-graphics` so tools such as `kast-pack` can process
+//! textures without building a graphics backend.
+
+use std::path::Path;
+
+mod ktex;
+mod mipmaps;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageError {
+    Png(String),
+    /// Not a KTEX file, or one whose header and data don't agree.
+    InvalidKtex,
+}
+
+impl core::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl core::error::Error for ImageError {}
+
+/// How the color channels of RGBA8 pixels are encoded. Alpha is always
+/// linear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    Srgb,
+    /// Linear data, such as normal maps.
+    Linear,
+}
+
+impl ColorSpace {
+    /// The color space a source image declares through its name: files
+    /// named like `bricks_normal.linear.png` hold linear data, any other
+    /// sRGB colors.
+    pub fn from_path(path: &Path) -> Self {
+        let linear = path
+            .file_stem()
+            .and_then(|stem| Path::new(stem).extension())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("linear"));
+
+        if linear { Self::Linear } else { Self::Srgb }
+    }
+}
+
+/// An RGBA8 image with any number of mip levels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub color_space: ColorSpace,
+    /// 1 unless mipmaps were generated.
+    pub mip_levels: u32,
+    /// Tightly packed rows of pixels, top to bottom, for each mip level in
+    /// turn, largest first.
+    pub data: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, color_space: ColorSpace, data: Vec<u8>) -> Self {
+        Self {
+            width,
+            height,
+            color_space,
+            mip_levels: 1,
+            data,
+        }
+    }
+
+    /// Decodes a PNG of any color type into 8-bit RGBA, taken to be sRGB.
+    /// Set `color_space` afterwards for linear data.
+    pub fn from_png(bytes: &[u8]) -> Result<Self, ImageError> {
+        let decode_error = |error: png::DecodingError| ImageError::Png(error.to_string());
+
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info().map_err(decode_error)?;
+
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(decode_error)?;
+        buffer.truncate(info.buffer_size());
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, u8::MAX]).collect(),
+            png::ColorType::Indexed => {
+                return Err(ImageError::Png("indexed PNG was not expanded".to_string()));
+            }
+        };
+
+        Ok(Self::new(info.width, info.height, ColorSpace::Srgb, data))
+    }
+}
+
+/// Levels in a full mip chain down to 1x1.
+fn max_mip_levels(width: u32, height: u32) -> u32 {
+    32 - width.max(height).max(1).leading_zeros()
+}
+
+/// Bytes taken by the first `levels` levels of an RGBA8 mip chain.
+fn mip_chain_len(width: u32, height: u32, levels: u32) -> u64 {
+    (0..levels)
+        .map(|level| {
+            let width = u64::from((width >> level).max(1));
+            let height = u64::from((height >> level).max(1));
+            width * height * 4
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::ColorSpace;
+
+    #[test]
+    fn linear_images_are_named_as_such() {
+        let color_space = |path| ColorSpace::from_path(Path::new(path));
+
+        assert_eq!(color_space("bricks_normal.linear.png"), ColorSpace::Linear);
+        assert_eq!(
+            color_space("textures/roughness.LINEAR.png"),
+            ColorSpace::Linear
+        );
+        assert_eq!(color_space("textures/grass.png"), ColorSpace::Srgb);
+        assert_eq!(color_space("linear.png"), ColorSpace::Srgb);
+        assert_eq!(color_space("linear/grass.png"), ColorSpace::Srgb);
+    }
+}
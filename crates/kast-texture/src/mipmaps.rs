@@ -0,0 +1,98 @@
+use kast_math::Color;
+
+use crate::{ColorSpace, Image, max_mip_levels, mip_chain_len};
+
+impl Image {
+    /// Replaces any mip levels below the full-size image with a complete
+    /// chain down to 1x1, each level averaging 2x2 pixels of the one above.
+    ///
+    /// sRGB colors are averaged in linear space so the smaller levels don't
+    /// darken.
+    pub fn generate_mipmaps(&mut self) {
+        let srgb = self.color_space == ColorSpace::Srgb;
+        let decode = |[r, g, b, a]: [u8; 4]| {
+            if srgb {
+                Color::srgb_u8(r, g, b, a)
+            } else {
+                Color::linear_u8(r, g, b, a)
+            }
+        };
+        let encode = |color: Color| {
+            if srgb {
+                color.to_srgb_u8()
+            } else {
+                color.to_linear_u8()
+            }
+        };
+
+        let levels = max_mip_levels(self.width, self.height);
+        self.data
+            .truncate(mip_chain_len(self.width, self.height, 1) as usize);
+        self.data
+            .reserve(mip_chain_len(self.width, self.height, levels) as usize - self.data.len());
+
+        // Sizes are in `usize` so byte offsets of large images don't overflow.
+        let (mut width, mut height) = (self.width as usize, self.height as usize);
+        let mut start = 0;
+        for _ in 1..levels {
+            let (next_width, next_height) = ((width / 2).max(1), (height / 2).max(1));
+            let end = start + width * height * 4;
+
+            for y in 0..next_height {
+                for x in 0..next_width {
+                    // Odd sizes clamp to the last row or column.
+                    let xs = [(x * 2).min(width - 1), (x * 2 + 1).min(width - 1)];
+                    let ys = [(y * 2).min(height - 1), (y * 2 + 1).min(height - 1)];
+
+                    let level = &self.data[start..end];
+                    let mut sum = [0.0f32; 4];
+                    for sy in ys {
+                        for sx in xs {
+                            let pixel = (sy * width + sx) * 4;
+                            let pixel = level[pixel..pixel + 4].try_into().unwrap();
+                            for (total, value) in sum.iter_mut().zip(decode(pixel).to_array()) {
+                                *total += value;
+                            }
+                        }
+                    }
+
+                    let [r, g, b, a] = sum.map(|total| total / 4.0);
+                    self.data
+                        .extend_from_slice(&encode(Color::rgba(r, g, b, a)));
+                }
+            }
+
+            start = end;
+            (width, height) = (next_width, next_height);
+        }
+
+        self.mip_levels = levels;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ColorSpace, Image};
+
+    #[test]
+    fn odd_sizes_get_a_full_chain() {
+        let mut image = Image::new(5, 3, ColorSpace::Linear, vec![100; 5 * 3 * 4]);
+        image.generate_mipmaps();
+
+        assert_eq!(image.mip_levels, 3);
+        assert_eq!(image.data.len(), (15 + 2 + 1) * 4);
+        assert!(image.data.iter().all(|&value| value == 100));
+    }
+
+    #[test]
+    fn srgb_is_averaged_in_linear_space() {
+        let data = [[255; 4], [0, 0, 0, 255]].concat();
+        let mut image = Image::new(2, 1, ColorSpace::Srgb, data.clone());
+        image.generate_mipmaps();
+        assert_eq!(&image.data[8..], &[188, 188, 188, 255]);
+
+        let mut image = Image::new(2, 1, ColorSpace::Linear, data);
+        image.generate_mipmaps();
+        assert_eq!(&image.data[8..], &[128, 128, 128, 255]);
+    }
+}
@@ -0,0 +1,274 @@
+use proc_macro::TokenStream;
+use proc_macro_crate::{FoundCrate, crate_name};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Index, Member, parse_macro_input, spanned::Spanned};
+
+/// Derives `Reflect` and `SceneValue` for a struct.
+///
+/// Every field must implement `SceneValue`. Fields marked
+/// `#[reflect(ignore)]` are hidden from reflection and rebuilt with
+/// `Default` when loading.
+#[proc_macro_derive(Reflect, attributes(reflect))]
+pub fn derive_reflect(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct Field {
+    member: Member,
+    name: String,
+    ty: syn::Type,
+    ignored: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let world = world_path();
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "`Reflect` can only be derived for structs",
+        ));
+    };
+
+    let fields = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(index)),
+            };
+            let name = match &field.ident {
+                Some(ident) => ident.to_string(),
+                None => index.to_string(),
+            };
+
+            Ok(Field {
+                member,
+                name,
+                ty: field.ty.clone(),
+                ignored: is_ignored(field)?,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let reflected: Vec<_> = fields.iter().filter(|field| !field.ignored).collect();
+
+    let names: Vec<_> = reflected.iter().map(|field| &field.name).collect();
+    let members: Vec<_> = reflected.iter().map(|field| &field.member).collect();
+    let type_names: Vec<_> = reflected
+        .iter()
+        .map(|field| {
+            let ty = &field.ty;
+            quote!(#ty).to_string().replace(' ', "")
+        })
+        .collect();
+
+    let type_info = quote! {
+        static INFO: #world::TypeInfo = #world::TypeInfo {
+            name: stringify!(#ident),
+            fields: &[#(#world::FieldInfo { name: #names, type_name: #type_names }),*],
+        };
+        &INFO
+    };
+
+    let scene_value = scene_value(&world, &input, &data.fields, &fields);
+
+    Ok(quote! {
+        impl #impl_generics #world::Reflect for #ident #type_generics #where_clause {
+            fn type_info() -> &'static #world::TypeInfo {
+                #type_info
+            }
+
+            fn info(&self) -> &'static #world::TypeInfo {
+                <Self as #world::Reflect>::type_info()
+            }
+
+            fn field(&self, name: &str) -> ::core::option::Option<&dyn ::core::any::Any> {
+                match name {
+                    #(#names => ::core::option::Option::Some(&self.#members),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn field_mut(&mut self, name: &str) -> ::core::option::Option<&mut dyn ::core::any::Any> {
+                match name {
+                    #(#names => ::core::option::Option::Some(&mut self.#members),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn field_value(&self, name: &str) -> ::core::option::Option<#world::Value> {
+                match name {
+                    #(#names => ::core::option::Option::Some(#world::SceneValue::to_value(&self.#members)),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn set_field_value(
+                &mut self,
+                name: &str,
+                value: &#world::Value,
+            ) -> ::core::result::Result<(), #world::ReflectError> {
+                match name {
+                    #(#names => {
+                        self.#members = #world::SceneValue::from_value(value)?;
+                        ::core::result::Result::Ok(())
+                    })*
+                    _ => ::core::result::Result::Err(#world::ReflectError::UnknownField(
+                        ::std::string::String::from(name),
+                    )),
+                }
+            }
+
+            fn as_any(&self) -> &dyn ::core::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::core::any::Any {
+                self
+            }
+        }
+
+        #scene_value
+    })
+}
+
+/// Named structs become objects, newtypes their inner value and other tuple
+/// structs arrays.
+fn scene_value(
+    world: &TokenStream2,
+    input: &DeriveInput,
+    shape: &Fields,
+    fields: &[Field],
+) -> TokenStream2 {
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let reflected: Vec<_> = fields.iter().filter(|field| !field.ignored).collect();
+    let members: Vec<_> = reflected.iter().map(|field| &field.member).collect();
+    let names: Vec<_> = reflected.iter().map(|field| &field.name).collect();
+
+    let (to_value, from_value) = match shape {
+        Fields::Named(_) => {
+            let inits = fields.iter().map(|field| {
+                let member = &field.member;
+                let name = &field.name;
+                if field.ignored {
+                    quote!(#member: ::core::default::Default::default())
+                } else {
+                    quote!(#member: #world::SceneValue::from_value(value.field(#name)?)?)
+                }
+            });
+
+            (
+                quote! {
+                    #world::Value::Object(::std::vec![
+                        #((::std::string::String::from(#names), #world::SceneValue::to_value(&self.#members))),*
+                    ])
+                },
+                quote!(::core::result::Result::Ok(Self { #(#inits),* })),
+            )
+        }
+        Fields::Unnamed(_) if reflected.len() == 1 && fields.len() == 1 => (
+            quote!(#world::SceneValue::to_value(&self.0)),
+            quote!(::core::result::Result::Ok(Self(#world::SceneValue::from_value(value)?))),
+        ),
+        Fields::Unnamed(_) => {
+            let count = reflected.len();
+            let mut position = 0usize;
+            let inits = fields.iter().map(|field| {
+                if field.ignored {
+                    quote!(::core::default::Default::default())
+                } else {
+                    let item = format_ident!("item{}", position);
+                    position += 1;
+                    quote!(#world::SceneValue::from_value(#item)?)
+                }
+            });
+            let items: Vec<_> = (0..count).map(|i| format_ident!("item{}", i)).collect();
+
+            (
+                quote! {
+                    #world::Value::Array(::std::vec![#(#world::SceneValue::to_value(&self.#members)),*])
+                },
+                quote! {
+                    let [#(#items),*] = value.as_array()? else {
+                        return ::core::result::Result::Err(#world::SceneError::InvalidValue(
+                            ::std::format!("expected {} items", #count),
+                        ));
+                    };
+                    ::core::result::Result::Ok(Self(#(#inits),*))
+                },
+            )
+        }
+        Fields::Unit => (
+            quote!(#world::Value::Null),
+            quote! {
+                let _ = value;
+                ::core::result::Result::Ok(Self)
+            },
+        ),
+    };
+
+    quote! {
+        impl #impl_generics #world::SceneValue for #ident #type_generics #where_clause {
+            fn to_value(&self) -> #world::Value {
+                #to_value
+            }
+
+            fn from_value(value: &#world::Value) -> ::core::result::Result<Self, #world::SceneError> {
+                #from_value
+            }
+        }
+    }
+}
+
+fn is_ignored(field: &syn::Field) -> syn::Result<bool> {
+    let mut ignored = false;
+    for attribute in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("reflect"))
+    {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ignore") {
+                ignored = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported `reflect` attribute"))
+            }
+        })?;
+    }
+
+    Ok(ignored)
+}
+
+/// Where `kast-world` is reachable from the crate using the derive: directly,
+/// or through the `kast-core`/`kast` re-exports, under whatever name the
+/// crate's manifest gives them.
+fn world_path() -> TokenStream2 {
+    for (package, module) in [
+        ("kast-world", None),
+        ("kast-core", Some(quote!(::world))),
+        ("kast", Some(quote!(::world))),
+    ] {
+        let name = match crate_name(package) {
+            // Integration tests and examples of the package itself see it
+            // under its own name.
+            Ok(FoundCrate::Itself) => package.replace('-', "_"),
+            Ok(FoundCrate::Name(name)) => name,
+            Err(_) => continue,
+        };
+        let ident = format_ident!("{name}");
+        return quote!(::#ident #module);
+    }
+
+    quote!(::kast_world)
+}
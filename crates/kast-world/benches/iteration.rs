@@ -0,0 +1,128 @@
+//! Compares query iteration over archetype tables against a sparse-set
+//! baseline (one densely packed array per component type, joined through the
+//! sparse index).
+//!
+//! Run with `cargo bench -p kast-world`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use kast_world::{Entity, World};
+
+const ENTITY_COUNT: u32 = 50_000;
+const ITERATIONS: u32 = 200;
+
+#[derive(Clone, Copy)]
+struct Position([f32; 3]);
+
+#[derive(Clone, Copy)]
+struct Velocity([f32; 3]);
+
+struct TagA;
+struct TagB;
+struct TagC;
+
+/// Minimal sparse-set storage, as used by the first version of `kast-world`.
+struct SparseSet<T> {
+    sparse: Vec<Option<usize>>,
+    entities: Vec<Entity>,
+    components: Vec<T>,
+}
+
+impl<T> SparseSet<T> {
+    fn new() -> Self {
+        Self {
+            sparse: Vec::new(),
+            entities: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, entity: Entity, component: T) {
+        let index = entity.index as usize;
+        if index >= self.sparse.len() {
+            self.sparse.resize(index + 1, None);
+        }
+
+        self.sparse[index] = Some(self.entities.len());
+        self.entities.push(entity);
+        self.components.push(component);
+    }
+
+    fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        let dense = (*self.sparse.get(entity.index as usize)?)?;
+        (self.entities[dense] == entity).then(|| &mut self.components[dense])
+    }
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let per_iteration = elapsed / ITERATIONS;
+    let throughput = (ENTITY_COUNT as f64 * ITERATIONS as f64) / elapsed.as_secs_f64();
+    println!(
+        "{name:<32} {per_iteration:>12.2?}/iter {:>10.1} M entities/s",
+        throughput / 1_000_000.0
+    );
+}
+
+fn integrate(position: &mut Position, velocity: &Velocity) {
+    for axis in 0..3 {
+        position.0[axis] += velocity.0[axis] * 0.016;
+    }
+}
+
+fn bench_archetype(name: &str, fragmented: bool) {
+    let mut world = World::new();
+    for i in 0..ENTITY_COUNT {
+        let entity = world.spawn((Position([0.0; 3]), Velocity([1.0, 2.0, 3.0])));
+        if fragmented {
+            match i % 4 {
+                1 => world.insert(entity, TagA),
+                2 => world.insert(entity, TagB),
+                3 => world.insert(entity, TagC),
+                _ => true,
+            };
+        }
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for (mut position, velocity) in world.query::<(&mut Position, &Velocity)>() {
+            integrate(&mut position, velocity);
+        }
+    }
+    report(name, start.elapsed());
+
+    black_box(&mut world);
+}
+
+fn bench_sparse_set() {
+    let mut positions = SparseSet::new();
+    let mut velocities = SparseSet::new();
+    for index in 0..ENTITY_COUNT {
+        let entity = Entity::new(index, 1);
+        positions.insert(entity, Position([0.0; 3]));
+        velocities.insert(entity, Velocity([1.0, 2.0, 3.0]));
+    }
+
+    // Joining drives iteration off one set and probes the other, which is
+    // what a sparse-set query over two components boils down to.
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for (entity, velocity) in velocities.entities.iter().zip(&velocities.components) {
+            if let Some(position) = positions.get_mut(*entity) {
+                integrate(position, velocity);
+            }
+        }
+    }
+    report("sparse set", start.elapsed());
+
+    black_box(&mut positions);
+}
+
+fn main() {
+    println!("{ENTITY_COUNT} entities, {ITERATIONS} iterations of (&mut Position, &Velocity)");
+
+    bench_archetype("archetype (single table)", false);
+    bench_archetype("archetype (4 tables)", true);
+    bench_sparse_set();
+}
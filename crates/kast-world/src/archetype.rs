@@ -0,0 +1,528 @@
+use std::any::{Any, TypeId};
+use std::cell::UnsafeCell;
+use std::collections::HashMap;
+
+use crate::{ChangeTicks, Component, Entity, Mut, change::ComponentTicks, component::Components};
+
+/// Where an entity's components live: which archetype, and which row of its
+/// columns.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct EntityLocation {
+    pub archetype: usize,
+    pub row: usize,
+}
+
+impl EntityLocation {
+    /// Placeholder for ids reserved by `Commands` but not yet flushed.
+    pub const RESERVED: Self = Self {
+        archetype: usize::MAX,
+        row: usize::MAX,
+    };
+}
+
+/// Type-erased operations on a column, so archetypes can move and drop rows
+/// without knowing the concrete component types.
+pub(crate) trait ColumnData: Send + Sync {
+    fn swap_remove_drop(&mut self, row: usize);
+
+    /// Swap-removes `row` and pushes it onto `target`, which must hold the
+    /// same component type.
+    fn swap_remove_into(&mut self, row: usize, target: &mut dyn ColumnData);
+
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+pub(crate) struct TypedColumn<T> {
+    pub data: Vec<T>,
+}
+
+impl<T: Component> TypedColumn<T> {
+    pub fn new_erased() -> Box<dyn ColumnData> {
+        Box::new(Self { data: Vec::new() })
+    }
+}
+
+impl<T: Component> ColumnData for TypedColumn<T> {
+    fn swap_remove_drop(&mut self, row: usize) {
+        self.data.swap_remove(row);
+    }
+
+    fn swap_remove_into(&mut self, row: usize, target: &mut dyn ColumnData) {
+        let target = target
+            .as_any_mut()
+            .downcast_mut::<Self>()
+            .expect("moved a component into a column of a different type");
+        target.data.push(self.data.swap_remove(row));
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A single component type's values for every entity of an archetype, stored
+/// contiguously in row order, along with each row's change ticks.
+///
+/// The data sits behind an `UnsafeCell` so queries can borrow several columns
+/// mutably through a shared `&World`; `Access` checks are what make that sound.
+pub(crate) struct Column {
+    data: UnsafeCell<Box<dyn ColumnData>>,
+    ticks: UnsafeCell<Vec<ComponentTicks>>,
+    /// Newest ticks of any row, so change filters can skip whole tables.
+    last_ticks: UnsafeCell<ComponentTicks>,
+}
+
+impl Column {
+    fn new(data: Box<dyn ColumnData>) -> Self {
+        Self {
+            data: UnsafeCell::new(data),
+            ticks: UnsafeCell::new(Vec::new()),
+            last_ticks: UnsafeCell::new(ComponentTicks::default()),
+        }
+    }
+
+    fn push_ticks(&mut self, ticks: ComponentTicks) {
+        self.ticks.get_mut().push(ticks);
+        self.last_ticks.get_mut().max(ticks);
+    }
+
+    fn swap_remove_drop(&mut self, row: usize) {
+        self.data.get_mut().swap_remove_drop(row);
+        self.ticks.get_mut().swap_remove(row);
+    }
+
+    fn swap_remove_into(&mut self, row: usize, target: &mut Column) {
+        self.data
+            .get_mut()
+            .swap_remove_into(row, &mut **target.data.get_mut());
+        target.push_ticks(self.ticks.get_mut().swap_remove(row));
+    }
+
+    /// Swap-removes and returns the value of `row`.
+    pub fn swap_remove_typed<T: Component>(&mut self, row: usize) -> T {
+        self.ticks.get_mut().swap_remove(row);
+        self.typed_mut::<T>().data.swap_remove(row)
+    }
+
+    /// Overwrites the value of `row`, marking it as changed.
+    pub fn replace<T: Component>(&mut self, row: usize, value: T, tick: u64) {
+        self.typed_mut::<T>().data[row] = value;
+        self.ticks.get_mut()[row].changed = tick;
+        self.last_ticks.get_mut().changed = self.last_ticks.get_mut().changed.max(tick);
+    }
+
+    pub fn typed<T: Component>(&self) -> &TypedColumn<T> {
+        // SAFETY: shared access only; writers either hold `&mut World` or a
+        // validated write access to this component type.
+        unsafe { &*self.data.get() }
+            .as_any()
+            .downcast_ref()
+            .expect("column accessed with the wrong component type")
+    }
+
+    pub fn typed_mut<T: Component>(&mut self) -> &mut TypedColumn<T> {
+        self.data
+            .get_mut()
+            .as_any_mut()
+            .downcast_mut()
+            .expect("column accessed with the wrong component type")
+    }
+
+    /// # Safety
+    ///
+    /// The caller must guarantee no other reference into this column is alive
+    /// for the returned lifetime.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn typed_mut_unchecked<T: Component>(&self) -> &mut TypedColumn<T> {
+        unsafe { &mut *self.data.get() }
+            .as_any_mut()
+            .downcast_mut()
+            .expect("column accessed with the wrong component type")
+    }
+
+    pub fn last_ticks(&self) -> ComponentTicks {
+        // SAFETY: as for `typed`.
+        unsafe { *self.last_ticks.get() }
+    }
+
+    /// Raw pointers to the row ticks and the column's newest ticks, for
+    /// queries that hand out `Mut`s or filter on changes. They're never
+    /// borrowed as a slice, since both can run over the same column at once.
+    pub fn ticks_ptr(&self) -> (*mut ComponentTicks, *mut ComponentTicks) {
+        // SAFETY: only the pointers are created here; writing through them is
+        // up to the caller's access.
+        (
+            unsafe { (*self.ticks.get()).as_mut_ptr() },
+            self.last_ticks.get(),
+        )
+    }
+
+    /// A `Mut` for `row`, through `&mut` access to the column.
+    pub fn get_mut<T: Component>(
+        &mut self,
+        row: usize,
+        change_ticks: ChangeTicks,
+    ) -> Option<Mut<'_, T>> {
+        let column_ticks = self.last_ticks.get();
+        let value = self
+            .data
+            .get_mut()
+            .as_any_mut()
+            .downcast_mut::<TypedColumn<T>>()
+            .expect("column accessed with the wrong component type")
+            .data
+            .get_mut(row)?;
+        let ticks = &mut self.ticks.get_mut()[row];
+
+        // SAFETY: `column_ticks` points into `self`, which stays mutably
+        // borrowed for the lifetime of the `Mut`.
+        Some(unsafe { Mut::new(value, ticks, column_ticks, change_ticks) })
+    }
+}
+
+/// A table holding every entity that has exactly the same set of component
+/// types.
+///
+/// Each component type gets its own contiguous column, so iterating a query
+/// over an archetype is a linear walk through a handful of arrays. Adding or
+/// removing a component moves the entity's row into a neighbouring archetype;
+/// those transitions are cached in `add_edges`/`remove_edges`.
+pub struct Archetype {
+    types: Box<[TypeId]>,
+    columns: Box<[Column]>,
+    entities: Vec<Entity>,
+    add_edges: HashMap<TypeId, usize>,
+    remove_edges: HashMap<TypeId, usize>,
+    bundle_edges: HashMap<TypeId, usize>,
+}
+
+impl Archetype {
+    /// `types` must be sorted and deduplicated.
+    fn new(types: Box<[TypeId]>, components: &Components) -> Self {
+        let columns = types
+            .iter()
+            .map(|id| Column::new(components.new_column(*id)))
+            .collect();
+
+        Self {
+            types,
+            columns,
+            entities: Vec::new(),
+            add_edges: HashMap::new(),
+            remove_edges: HashMap::new(),
+            bundle_edges: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub(crate) fn has(&self, id: TypeId) -> bool {
+        self.types.binary_search(&id).is_ok()
+    }
+
+    pub(crate) fn column(&self, id: TypeId) -> Option<&Column> {
+        let index = self.types.binary_search(&id).ok()?;
+        Some(&self.columns[index])
+    }
+
+    pub(crate) fn column_mut(&mut self, id: TypeId) -> Option<&mut Column> {
+        let index = self.types.binary_search(&id).ok()?;
+        Some(&mut self.columns[index])
+    }
+
+    pub(crate) fn types(&self) -> &[TypeId] {
+        &self.types
+    }
+
+    /// Pushes a component value, added at `tick`, for the row currently being
+    /// built by `Bundle::push_into`.
+    pub(crate) fn push_component<T: Component>(&mut self, component: T, tick: u64) {
+        let column = self
+            .column_mut(TypeId::of::<T>())
+            .expect("bundle component missing from its archetype");
+        column.typed_mut::<T>().data.push(component);
+        column.push_ticks(ComponentTicks::new(tick));
+    }
+
+    /// Finishes a row whose components were pushed by `push_component`.
+    pub(crate) fn push_entity(&mut self, entity: Entity) -> usize {
+        self.entities.push(entity);
+        self.entities.len() - 1
+    }
+
+    /// Drops every component of `row`, returning the entity that was
+    /// swapped into its place, if any.
+    pub(crate) fn swap_remove(&mut self, row: usize) -> Option<Entity> {
+        for column in self.columns.iter_mut() {
+            column.swap_remove_drop(row);
+        }
+
+        self.entities.swap_remove(row);
+        self.entities.get(row).copied()
+    }
+
+    /// Moves the components of `row` into `target`, dropping any type the
+    /// target doesn't have. `taken` names a column whose value the caller has
+    /// already swap-removed.
+    ///
+    /// Returns the new row in `target` and the entity that was swapped into
+    /// `row`, if any.
+    pub(crate) fn move_row(
+        &mut self,
+        row: usize,
+        target: &mut Archetype,
+        taken: Option<TypeId>,
+    ) -> (usize, Option<Entity>) {
+        for (id, column) in self.types.iter().zip(self.columns.iter_mut()) {
+            if Some(*id) == taken {
+                continue;
+            }
+
+            match target.column_mut(*id) {
+                Some(target) => column.swap_remove_into(row, target),
+                None => column.swap_remove_drop(row),
+            }
+        }
+
+        let entity = self.entities.swap_remove(row);
+        let new_row = target.push_entity(entity);
+
+        (new_row, self.entities.get(row).copied())
+    }
+}
+
+/// Every archetype in a `World`, indexed by position and by type set.
+///
+/// Archetype 0 is always the empty archetype, home to entities with no
+/// components.
+pub(crate) struct Archetypes {
+    archetypes: Vec<Archetype>,
+    by_types: HashMap<Box<[TypeId]>, usize>,
+}
+
+impl Archetypes {
+    pub const EMPTY: usize = 0;
+
+    pub fn new(components: &Components) -> Self {
+        let mut archetypes = Self {
+            archetypes: Vec::new(),
+            by_types: HashMap::new(),
+        };
+        archetypes.get_or_insert(Box::new([]), components);
+
+        archetypes
+    }
+
+    pub fn get(&self, index: usize) -> &Archetype {
+        &self.archetypes[index]
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> &mut Archetype {
+        &mut self.archetypes[index]
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Archetype> {
+        self.archetypes.iter()
+    }
+
+    /// `types` must be sorted and deduplicated.
+    pub fn get_or_insert(&mut self, types: Box<[TypeId]>, components: &Components) -> usize {
+        if let Some(&index) = self.by_types.get(&types) {
+            return index;
+        }
+
+        let index = self.archetypes.len();
+        self.archetypes
+            .push(Archetype::new(types.clone(), components));
+        self.by_types.insert(types, index);
+
+        index
+    }
+
+    /// The archetype reached by adding `id` to `source`.
+    pub fn with_component(&mut self, source: usize, id: TypeId, components: &Components) -> usize {
+        if let Some(&target) = self.archetypes[source].add_edges.get(&id) {
+            return target;
+        }
+
+        let mut types = self.archetypes[source].types.to_vec();
+        if let Err(position) = types.binary_search(&id) {
+            types.insert(position, id);
+        }
+
+        let target = self.get_or_insert(types.into_boxed_slice(), components);
+        self.archetypes[source].add_edges.insert(id, target);
+        self.archetypes[target].remove_edges.insert(id, source);
+
+        target
+    }
+
+    /// The archetype reached by adding the bundle `bundle`, made of the sorted
+    /// component types `ids`, to `source`.
+    pub fn with_bundle(
+        &mut self,
+        source: usize,
+        bundle: TypeId,
+        ids: &[TypeId],
+        components: &Components,
+    ) -> usize {
+        if let Some(&target) = self.archetypes[source].bundle_edges.get(&bundle) {
+            return target;
+        }
+
+        let mut types = self.archetypes[source].types.to_vec();
+        for id in ids {
+            if let Err(position) = types.binary_search(id) {
+                types.insert(position, *id);
+            }
+        }
+
+        let target = self.get_or_insert(types.into_boxed_slice(), components);
+        self.archetypes[source].bundle_edges.insert(bundle, target);
+
+        target
+    }
+
+    /// The archetype reached by removing `id` from `source`.
+    pub fn without_component(
+        &mut self,
+        source: usize,
+        id: TypeId,
+        components: &Components,
+    ) -> usize {
+        if let Some(&target) = self.archetypes[source].remove_edges.get(&id) {
+            return target;
+        }
+
+        let types: Box<[TypeId]> = self.archetypes[source]
+            .types
+            .iter()
+            .copied()
+            .filter(|other| *other != id)
+            .collect();
+
+        let target = self.get_or_insert(types, components);
+        self.archetypes[source].remove_edges.insert(id, target);
+        self.archetypes[target].add_edges.insert(id, source);
+
+        target
+    }
+
+    /// Mutably borrows two distinct archetypes at once.
+    pub fn pair_mut(&mut self, a: usize, b: usize) -> (&mut Archetype, &mut Archetype) {
+        assert_ne!(a, b, "cannot borrow the same archetype twice");
+
+        if a < b {
+            let (left, right) = self.archetypes.split_at_mut(b);
+            (&mut left[a], &mut right[0])
+        } else {
+            let (left, right) = self.archetypes.split_at_mut(a);
+            (&mut right[0], &mut left[b])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Counts how many times it's dropped.
+    struct Tracked(Arc<AtomicUsize>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn components() -> Components {
+        let mut components = Components::default();
+        components.register::<u32>();
+        components.register::<&'static str>();
+        components.register::<Tracked>();
+        components
+    }
+
+    #[test]
+    fn edges_lead_back_and_are_cached() {
+        let components = components();
+        let mut archetypes = Archetypes::new(&components);
+        let (number, name) = (TypeId::of::<u32>(), TypeId::of::<&'static str>());
+
+        let with_number = archetypes.with_component(Archetypes::EMPTY, number, &components);
+        let with_both = archetypes.with_component(with_number, name, &components);
+        assert_ne!(with_number, Archetypes::EMPTY);
+        assert!(archetypes.get(with_both).has(number) && archetypes.get(with_both).has(name));
+
+        // Reached the other way round, the type set is the same archetype.
+        let with_name = archetypes.with_component(Archetypes::EMPTY, name, &components);
+        assert_eq!(
+            archetypes.with_component(with_name, number, &components),
+            with_both
+        );
+        assert_eq!(
+            archetypes.without_component(with_both, name, &components),
+            with_number
+        );
+        assert_eq!(
+            archetypes.without_component(with_number, number, &components),
+            Archetypes::EMPTY
+        );
+
+        let mut ids = [number, name];
+        ids.sort();
+        let bundle = TypeId::of::<(u32, &'static str)>();
+        assert_eq!(
+            archetypes.with_bundle(Archetypes::EMPTY, bundle, &ids, &components),
+            with_both
+        );
+        assert_eq!(archetypes.iter().count(), 4);
+    }
+
+    #[test]
+    fn moving_a_row_fills_the_hole_and_drops_missing_types() {
+        let components = components();
+        let mut archetypes = Archetypes::new(&components);
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (number, tracked) = (TypeId::of::<u32>(), TypeId::of::<Tracked>());
+
+        let with_number = archetypes.with_component(Archetypes::EMPTY, number, &components);
+        let source = archetypes.with_component(with_number, tracked, &components);
+        let archetype = archetypes.get_mut(source);
+        for index in 0..3 {
+            archetype.push_component(index, 1);
+            archetype.push_component(Tracked(drops.clone()), 1);
+            archetype.push_entity(Entity::new(index, 0));
+        }
+
+        let (source, target) = archetypes.pair_mut(source, with_number);
+        let (row, moved) = source.move_row(0, target, None);
+
+        assert_eq!((row, moved), (0, Some(Entity::new(2, 0))));
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        assert_eq!(target.entities(), [Entity::new(0, 0)]);
+        assert_eq!(target.column(number).unwrap().typed::<u32>().data, [0]);
+        assert_eq!(source.entities(), [Entity::new(2, 0), Entity::new(1, 0)]);
+        assert_eq!(source.column(number).unwrap().typed::<u32>().data, [2, 1]);
+
+        // Removing the last row swaps nothing in.
+        assert_eq!(source.swap_remove(1), None);
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        assert_eq!(source.len(), 1);
+    }
+}
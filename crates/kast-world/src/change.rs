@@ -0,0 +1,202 @@
+use std::any::TypeId;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use crate::{Component, Entity, SystemMeta, SystemParam, World};
+
+/// When a component was added and when it was last mutably borrowed, in world
+/// change ticks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) struct ComponentTicks {
+    pub added: u64,
+    pub changed: u64,
+}
+
+impl ComponentTicks {
+    pub fn new(tick: u64) -> Self {
+        Self {
+            added: tick,
+            changed: tick,
+        }
+    }
+
+    /// Merges `other` into a running maximum.
+    pub fn max(&mut self, other: ComponentTicks) {
+        self.added = self.added.max(other.added);
+        self.changed = self.changed.max(other.changed);
+    }
+}
+
+/// The window of change ticks a system (or a query run directly on the world)
+/// compares against.
+///
+/// Anything stamped after `last_run` is new to it, and its own writes are
+/// stamped with `this_run`. Ticks are 64-bit, so they never wrap in practice.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChangeTicks {
+    pub last_run: u64,
+    pub this_run: u64,
+}
+
+impl ChangeTicks {
+    pub fn is_newer(&self, tick: u64) -> bool {
+        tick > self.last_run
+    }
+}
+
+/// A mutable component borrowed from a query or `World::get_mut`.
+///
+/// Dereferencing mutably marks the component as changed, which is what
+/// `Changed<T>` filters look at; reading through it doesn't.
+pub struct Mut<'w, T: ?Sized> {
+    value: &'w mut T,
+    ticks: &'w mut ComponentTicks,
+    /// Newest ticks of the whole column. Shared by every `Mut` of the same
+    /// column, hence the raw pointer.
+    column_ticks: *mut ComponentTicks,
+    change_ticks: ChangeTicks,
+}
+
+impl<'w, T: ?Sized> Mut<'w, T> {
+    /// # Safety
+    ///
+    /// `column_ticks` must stay valid for `'w`, and only be written through
+    /// `Mut`s of the same column.
+    pub(crate) unsafe fn new(
+        value: &'w mut T,
+        ticks: &'w mut ComponentTicks,
+        column_ticks: *mut ComponentTicks,
+        change_ticks: ChangeTicks,
+    ) -> Self {
+        Self {
+            value,
+            ticks,
+            column_ticks,
+            change_ticks,
+        }
+    }
+
+    /// Whether the component was added since the borrower last ran.
+    pub fn is_added(&self) -> bool {
+        self.change_ticks.is_newer(self.ticks.added)
+    }
+
+    /// Whether the component was added or mutably borrowed since the
+    /// borrower last ran.
+    pub fn is_changed(&self) -> bool {
+        self.change_ticks.is_newer(self.ticks.changed)
+    }
+
+    pub fn set_changed(&mut self) {
+        let tick = self.change_ticks.this_run;
+        self.ticks.changed = tick;
+
+        // SAFETY: guaranteed by the caller of `Mut::new`; the write can't
+        // race since the query holds write access to the whole column.
+        let column_ticks = unsafe { &mut *self.column_ticks };
+        column_ticks.changed = column_ticks.changed.max(tick);
+    }
+
+    /// Mutates the component without marking it as changed.
+    pub fn bypass_change_detection(&mut self) -> &mut T {
+        self.value
+    }
+
+    pub fn into_inner(mut self) -> &'w mut T {
+        self.set_changed();
+        self.value
+    }
+
+    /// Narrows the borrow, for example to a field or a trait object, without
+    /// marking the component as changed. Writes through the result still do.
+    pub fn map_unchanged<U: ?Sized>(self, f: impl FnOnce(&mut T) -> &mut U) -> Mut<'w, U> {
+        Mut {
+            value: f(self.value),
+            ticks: self.ticks,
+            column_ticks: self.column_ticks,
+            change_ticks: self.change_ticks,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for Mut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: ?Sized> DerefMut for Mut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.set_changed();
+        self.value
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Mut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// Entities that lost their `T` component, either through `World::remove`
+/// or by being despawned, since the system last ran.
+///
+/// Removals are kept for two calls to `World::clear_trackers` (once per
+/// frame in an `App`), so a system has to run every frame to see all of
+/// them.
+pub struct RemovedComponents<'w, T> {
+    removed: &'w [(Entity, u64)],
+    ticks: ChangeTicks,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'w, T> RemovedComponents<'w, T> {
+    pub(crate) fn new(removed: &'w [(Entity, u64)], ticks: ChangeTicks) -> Self {
+        Self {
+            removed,
+            ticks,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + 'w {
+        let ticks = self.ticks;
+        self.removed
+            .iter()
+            .filter(move |(_, tick)| ticks.is_newer(*tick))
+            .map(|(entity, _)| *entity)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+impl<'w, T> IntoIterator for RemovedComponents<'w, T> {
+    type Item = Entity;
+    type IntoIter = Box<dyn Iterator<Item = Entity> + 'w>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+unsafe impl<T: Component> SystemParam for RemovedComponents<'_, T> {
+    type State = ();
+    type Item<'w, 's> = RemovedComponents<'w, T>;
+
+    // The removal log is only written through `&mut World`, so reading it
+    // needs no declared access.
+    fn init_state(_world: &mut World, _meta: &mut SystemMeta) -> Self::State {}
+
+    unsafe fn get_param<'w, 's>(
+        _state: &'s mut Self::State,
+        world: &'w World,
+        ticks: ChangeTicks,
+    ) -> Self::Item<'w, 's> {
+        RemovedComponents::new(world.removed_log(TypeId::of::<T>()), ticks)
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{Bundle, ChangeTicks, Component, Entity, Resource, SystemMeta, SystemParam, World};
+
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+/// A list of structural changes waiting for `&mut World`.
+#[derive(Default)]
+pub struct CommandQueue {
+    commands: Vec<Command>,
+}
+
+impl CommandQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.commands.push(Box::new(command));
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Runs every queued command in order, after placing any reserved
+    /// entities into the world.
+    pub fn apply(&mut self, world: &mut World) {
+        world.flush();
+        for command in self.commands.drain(..) {
+            command(world);
+        }
+    }
+}
+
+/// Queues spawns, despawns and component changes from a system that only
+/// has shared access to the world.
+///
+/// Entities spawned through `Commands` get their id right away, so it can be
+/// stored or used in later commands, but they only become visible to queries
+/// once the schedule reaches a sync point.
+///
+/// ```
+/// # use kast_math::Transform;
+/// # use kast_world::{Commands, Query};
+/// # struct Gun;
+/// # struct Bullet;
+/// # impl Bullet {
+/// #     fn new(_: &Gun) -> Self {
+/// #         Bullet
+/// #     }
+/// # }
+/// fn spawn_bullets(mut commands: Commands, query: Query<(&Gun, &Transform)>) {
+///     for (gun, transform) in query {
+///         commands.spawn((Bullet::new(gun), *transform));
+///     }
+/// }
+/// # kast_world::Schedule::new().add_system(spawn_bullets);
+/// ```
+pub struct Commands<'w, 's> {
+    queue: &'s mut CommandQueue,
+    world: &'w World,
+}
+
+impl<'w, 's> Commands<'w, 's> {
+    pub fn new(queue: &'s mut CommandQueue, world: &'w World) -> Self {
+        Self { queue, world }
+    }
+
+    /// Reserves an entity and queues the insertion of `bundle` on it.
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let entity = self.world.reserve_entity();
+        self.queue.push(move |world| {
+            world.insert_bundle(entity, bundle);
+        });
+
+        entity
+    }
+
+    /// Reserves an entity with no components.
+    pub fn spawn_empty(&mut self) -> Entity {
+        self.world.reserve_entity()
+    }
+
+    pub fn despawn(&mut self, entity: Entity) {
+        self.queue.push(move |world| {
+            world.despawn(entity);
+        });
+    }
+
+    /// Queues `World::despawn_recursive`.
+    pub fn despawn_recursive(&mut self, entity: Entity) {
+        self.queue.push(move |world| {
+            world.despawn_recursive(entity);
+        });
+    }
+
+    /// Queues `World::set_parent`.
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) {
+        self.queue.push(move |world| {
+            world.set_parent(child, parent);
+        });
+    }
+
+    pub fn remove_parent(&mut self, child: Entity) {
+        self.queue.push(move |world| {
+            world.remove_parent(child);
+        });
+    }
+
+    /// Queues the insertion of `component`. Does nothing if `entity` has been
+    /// despawned by the time the command runs.
+    pub fn insert<T: Component>(&mut self, entity: Entity, component: T) {
+        self.queue.push(move |world| {
+            world.insert(entity, component);
+        });
+    }
+
+    pub fn insert_bundle<B: Bundle>(&mut self, entity: Entity, bundle: B) {
+        self.queue.push(move |world| {
+            world.insert_bundle(entity, bundle);
+        });
+    }
+
+    pub fn remove<T: Component>(&mut self, entity: Entity) {
+        self.queue.push(move |world| {
+            world.remove::<T>(entity);
+        });
+    }
+
+    pub fn insert_resource<T: Resource>(&mut self, value: T) {
+        self.queue.push(move |world| {
+            world.insert_resource(value);
+        });
+    }
+
+    pub fn remove_resource<T: Resource>(&mut self) {
+        self.queue.push(move |world| {
+            world.remove_resource::<T>();
+        });
+    }
+
+    /// Queues an arbitrary change to the world.
+    pub fn add(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.queue.push(command);
+    }
+}
+
+unsafe impl SystemParam for Commands<'_, '_> {
+    type State = CommandQueue;
+    type Item<'w, 's> = Commands<'w, 's>;
+
+    fn init_state(_world: &mut World, _meta: &mut SystemMeta) -> Self::State {
+        CommandQueue::new()
+    }
+
+    unsafe fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        world: &'w World,
+        _ticks: ChangeTicks,
+    ) -> Self::Item<'w, 's> {
+        Commands::new(state, world)
+    }
+
+    fn apply(state: &mut Self::State, world: &mut World) {
+        state.apply(world);
+    }
+}
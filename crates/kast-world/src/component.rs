@@ -0,0 +1,107 @@
+use std::any::{TypeId, type_name};
+use std::collections::HashMap;
+
+use crate::{
+    Entity, World,
+    archetype::{Archetype, ColumnData, TypedColumn},
+};
+
+/// Marker trait for data that can be attached to an entity.
+///
+/// Every `Send + Sync + 'static` type is a component, so plain structs can be
+/// inserted without any boilerplate.
+pub trait Component: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Component for T {}
+
+/// Type information the world needs to build columns for a component type it
+/// has only seen as a `TypeId`.
+struct ComponentInfo {
+    name: &'static str,
+    new_column: fn() -> Box<dyn ColumnData>,
+}
+
+/// Registry of every component type a `World` has stored.
+#[derive(Default)]
+pub struct Components {
+    infos: HashMap<TypeId, ComponentInfo>,
+}
+
+impl Components {
+    pub(crate) fn register<T: Component>(&mut self) -> TypeId {
+        let id = TypeId::of::<T>();
+        self.infos.entry(id).or_insert_with(|| ComponentInfo {
+            name: type_name::<T>(),
+            new_column: TypedColumn::<T>::new_erased,
+        });
+
+        id
+    }
+
+    pub(crate) fn name(&self, id: TypeId) -> &'static str {
+        self.infos
+            .get(&id)
+            .map_or("<unregistered>", |info| info.name)
+    }
+
+    pub(crate) fn new_column(&self, id: TypeId) -> Box<dyn ColumnData> {
+        let info = self
+            .infos
+            .get(&id)
+            .expect("archetype built from an unregistered component type");
+        (info.new_column)()
+    }
+}
+
+/// A set of components that can be spawned together.
+///
+/// Implemented for tuples of components, e.g.
+/// `world.spawn((Transform::default(), Velocity(Vec3::ZERO)))`. Use a
+/// one-element tuple `(component,)` to spawn a single component.
+pub trait Bundle: Send + Sync + 'static {
+    /// Registers every component type of the bundle, in declaration order.
+    #[doc(hidden)]
+    fn register(components: &mut Components, ids: &mut Vec<TypeId>);
+
+    /// Pushes every component onto the matching column of `archetype`.
+    #[doc(hidden)]
+    fn push_into(self, archetype: &mut Archetype, tick: u64);
+
+    /// Inserts the components one by one, for entities that already have
+    /// some of them.
+    #[doc(hidden)]
+    fn insert_each(self, world: &mut World, entity: Entity);
+}
+
+macro_rules! impl_bundle {
+    ($($name:ident),*) => {
+        impl<$($name: Component),*> Bundle for ($($name,)*) {
+            #[allow(unused_variables)]
+            fn register(components: &mut Components, ids: &mut Vec<TypeId>) {
+                $(ids.push(components.register::<$name>());)*
+            }
+
+            #[allow(non_snake_case, unused_variables)]
+            fn push_into(self, archetype: &mut Archetype, tick: u64) {
+                let ($($name,)*) = self;
+                $(archetype.push_component($name, tick);)*
+            }
+
+            #[allow(non_snake_case, unused_variables)]
+            fn insert_each(self, world: &mut World, entity: Entity) {
+                let ($($name,)*) = self;
+                $(world.insert(entity, $name);)*
+            }
+        }
+    };
+}
+
+impl_bundle!();
+impl_bundle!(A);
+impl_bundle!(A, B);
+impl_bundle!(A, B, C);
+impl_bundle!(A, B, C, D);
+impl_bundle!(A, B, C, D, E);
+impl_bundle!(A, B, C, D, E, F);
+impl_bundle!(A, B, C, D, E, F, G);
+impl_bundle!(A, B, C, D, E, F, G, H);
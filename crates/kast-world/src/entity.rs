@@ -0,0 +1,25 @@
+/// A lightweight, copyable identifier for an entity living in a `World`.
+///
+/// Entities follow the same index/generation scheme as `kast_resources::Pool`:
+/// when an entity is despawned its index may be recycled, but the generation is
+/// bumped so stale ids never resolve to the new occupant.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Entity {
+    pub index: u32,
+    pub generation: u32,
+}
+
+impl Entity {
+    /// An id no live entity has, for references that couldn't be resolved.
+    pub const PLACEHOLDER: Self = Self::new(u32::MAX, u32::MAX);
+
+    pub const fn new(index: u32, generation: u32) -> Self {
+        Self { index, generation }
+    }
+}
+
+impl core::fmt::Display for Entity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
@@ -0,0 +1,235 @@
+use std::ops::Deref;
+
+use kast_math::{Mat4, Transform, Vec3};
+
+use crate::{Commands, Entity, Query, With, Without, World};
+
+/// The entity this entity is attached to.
+///
+/// Kept in sync with the parent's `Children` by `World::set_parent`,
+/// `World::remove_parent` and `World::despawn_recursive`, so it can't be
+/// built directly.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Parent(Entity);
+
+impl Parent {
+    pub fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+/// The entities attached to this entity, in attachment order.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Children(Vec<Entity>);
+
+impl Deref for Children {
+    type Target = [Entity];
+
+    fn deref(&self) -> &[Entity] {
+        &self.0
+    }
+}
+
+/// The world-space matrix of an entity, written by `propagate_transforms`
+/// from its own `Transform` and those of its ancestors.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct GlobalTransform(pub Mat4);
+
+impl GlobalTransform {
+    pub fn matrix(&self) -> Mat4 {
+        self.0
+    }
+
+    pub fn translation(&self) -> Vec3 {
+        self.0.translation()
+    }
+}
+
+impl World {
+    /// Attaches `child` to `parent`, detaching it from its previous parent.
+    /// Returns `false` if either entity doesn't exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parent` is `child` or one of its descendants.
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) -> bool {
+        if !self.contains(child) || !self.contains(parent) {
+            return false;
+        }
+
+        let mut ancestor = Some(parent);
+        while let Some(entity) = ancestor {
+            if entity == child {
+                panic!("attaching {child} to {parent} would create a cycle");
+            }
+            ancestor = self.get::<Parent>(entity).map(Parent::get);
+        }
+
+        match self.get::<Parent>(child) {
+            Some(previous) if previous.get() == parent => return true,
+            Some(previous) => self.detach(child, previous.get()),
+            None => {}
+        }
+
+        self.insert(child, Parent(parent));
+        match self.get_mut::<Children>(parent) {
+            Some(mut children) => children.0.push(child),
+            None => {
+                self.insert(parent, Children(vec![child]));
+            }
+        }
+
+        true
+    }
+
+    /// Detaches `child` from its parent, making it a root. Returns `false` if
+    /// it had no parent.
+    pub fn remove_parent(&mut self, child: Entity) -> bool {
+        let Some(parent) = self.remove::<Parent>(child) else {
+            return false;
+        };
+
+        self.detach(child, parent.get());
+        true
+    }
+
+    /// Despawns `entity` along with all of its descendants, detaching it from
+    /// its parent first.
+    ///
+    /// A plain `despawn` leaves the children alive and the dead entity in its
+    /// parent's `Children`.
+    pub fn despawn_recursive(&mut self, entity: Entity) -> bool {
+        if !self.contains(entity) {
+            return false;
+        }
+
+        self.remove_parent(entity);
+
+        let mut stack = vec![entity];
+        while let Some(entity) = stack.pop() {
+            if let Some(children) = self.remove::<Children>(entity) {
+                stack.extend(children.0);
+            }
+            self.despawn(entity);
+        }
+
+        true
+    }
+
+    /// Removes `child` from the `Children` of `parent`, dropping the
+    /// component once it's empty.
+    fn detach(&mut self, child: Entity, parent: Entity) {
+        let Some(mut children) = self.get_mut::<Children>(parent) else {
+            return;
+        };
+
+        children.0.retain(|&other| other != child);
+        if children.0.is_empty() {
+            self.remove::<Children>(parent);
+        }
+    }
+}
+
+/// Computes every `GlobalTransform` from the local `Transform`s, walking down
+/// from each root.
+///
+/// Only entities whose ancestors all have a `Transform` are reached. Those
+/// missing a `GlobalTransform` get one through `commands`, so it shows up
+/// once they're applied. A `GlobalTransform` is only written when its value
+/// changes, so `Changed<GlobalTransform>` picks out the entities that
+/// actually moved.
+pub fn propagate_transforms(
+    mut commands: Commands,
+    roots: Query<Entity, (With<Transform>, Without<Parent>)>,
+    mut nodes: Query<(&Transform, Option<&mut GlobalTransform>, Option<&Children>)>,
+) {
+    let mut stack = Vec::new();
+    for root in roots {
+        stack.push((root, Mat4::IDENTITY));
+
+        while let Some((entity, parent)) = stack.pop() {
+            let Some((transform, global, children)) = nodes.get(entity) else {
+                continue;
+            };
+
+            let matrix = parent * transform.compute_matrix();
+            match global {
+                Some(mut global) if global.0 != matrix => global.0 = matrix,
+                Some(_) => {}
+                None => commands.insert(entity, GlobalTransform(matrix)),
+            }
+
+            if let Some(children) = children {
+                stack.extend(children.iter().map(|&child| (child, matrix)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kast_math::{Transform, Vec3};
+
+    use super::*;
+    use crate::Schedule;
+
+    fn translation(world: &World, entity: Entity) -> Option<Vec3> {
+        world
+            .get::<GlobalTransform>(entity)
+            .map(GlobalTransform::translation)
+    }
+
+    #[test]
+    fn global_transforms_are_inserted_and_propagated_down_three_levels() {
+        let mut world = World::new();
+        let root = world.spawn((Transform::from_translation(Vec3::new(1.0, 0.0, 0.0)),));
+        let child = world.spawn((Transform::from_translation(Vec3::new(0.0, 2.0, 0.0)),));
+        let grandchild = world.spawn((Transform::from_translation(Vec3::new(0.0, 0.0, 3.0)),));
+        world.set_parent(child, root);
+        world.set_parent(grandchild, child);
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(propagate_transforms);
+        schedule.run(&mut world);
+
+        assert_eq!(translation(&world, root), Some(Vec3::new(1.0, 0.0, 0.0)));
+        assert_eq!(translation(&world, child), Some(Vec3::new(1.0, 2.0, 0.0)));
+        assert_eq!(
+            translation(&world, grandchild),
+            Some(Vec3::new(1.0, 2.0, 3.0))
+        );
+
+        world.get_mut::<Transform>(root).unwrap().translation = Vec3::new(-1.0, 0.0, 0.0);
+        world.get_mut::<Transform>(grandchild).unwrap().scale = Vec3::splat(2.0);
+        schedule.run(&mut world);
+
+        assert_eq!(translation(&world, child), Some(Vec3::new(-1.0, 2.0, 0.0)));
+        assert_eq!(
+            translation(&world, grandchild),
+            Some(Vec3::new(-1.0, 2.0, 3.0))
+        );
+        assert_eq!(
+            world.get::<GlobalTransform>(grandchild).unwrap().matrix(),
+            world.get::<GlobalTransform>(child).unwrap().matrix()
+                * world.get::<Transform>(grandchild).unwrap().compute_matrix()
+        );
+    }
+
+    #[test]
+    fn entities_below_one_without_a_transform_are_skipped() {
+        let mut world = World::new();
+        let root = world.spawn((Transform::default(),));
+        let middle = world.spawn(());
+        let leaf = world.spawn((Transform::default(),));
+        world.set_parent(middle, root);
+        world.set_parent(leaf, middle);
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(propagate_transforms);
+        schedule.run(&mut world);
+
+        assert!(world.has::<GlobalTransform>(root));
+        assert!(!world.has::<GlobalTransform>(middle));
+        assert!(!world.has::<GlobalTransform>(leaf));
+    }
+}
@@ -0,0 +1,149 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{Component, Entity, World};
+
+type Hook = Arc<dyn Fn(&mut World, Entity) + Send + Sync>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum HookKind {
+    Add,
+    Replace,
+    Remove,
+}
+
+/// Callbacks registered through `World::on_add`, `on_replace`, `on_remove`
+/// and `on_despawn`.
+#[derive(Default)]
+pub(crate) struct Hooks {
+    component: HashMap<(HookKind, TypeId), Vec<Hook>>,
+    despawn: Vec<Hook>,
+}
+
+impl Hooks {
+    pub fn has(&self, kind: HookKind, id: TypeId) -> bool {
+        self.component.contains_key(&(kind, id))
+    }
+
+    pub fn has_despawn(&self) -> bool {
+        !self.despawn.is_empty()
+    }
+
+    fn push(&mut self, kind: HookKind, id: TypeId, hook: Hook) {
+        self.component.entry((kind, id)).or_default().push(hook);
+    }
+
+    /// Cloned so they can run with `&mut World`, which owns the list.
+    fn get(&self, kind: HookKind, id: TypeId) -> Vec<Hook> {
+        self.component.get(&(kind, id)).cloned().unwrap_or_default()
+    }
+}
+
+impl World {
+    /// Runs `hook` right after a `T` is added to an entity that didn't have
+    /// one, by spawning, inserting or loading a scene.
+    ///
+    /// Hooks receive the whole world and may make any change to it, including
+    /// to the entity they fired for.
+    pub fn on_add<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, Entity) + Send + Sync + 'static,
+    ) -> &mut Self {
+        let id = self.components.register::<T>();
+        self.hooks.push(HookKind::Add, id, Arc::new(hook));
+        self
+    }
+
+    /// Runs `hook` right before a `T` is overwritten by inserting another
+    /// one, while the old value can still be read.
+    pub fn on_replace<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, Entity) + Send + Sync + 'static,
+    ) -> &mut Self {
+        let id = self.components.register::<T>();
+        self.hooks.push(HookKind::Replace, id, Arc::new(hook));
+        self
+    }
+
+    /// Runs `hook` right before a `T` leaves an entity, by `World::remove`
+    /// or by despawning, while it can still be read. The hook must not remove
+    /// the `T` itself.
+    ///
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use kast_world::World;
+    /// # struct MeshRenderer {
+    /// #     buffers: Vec<u32>,
+    /// # }
+    /// # struct Context(Arc<Mutex<Vec<u32>>>);
+    /// # impl Context {
+    /// #     fn destroy_buffer(&mut self, buffer: u32) {
+    /// #         self.0.lock().unwrap().push(buffer);
+    /// #     }
+    /// # }
+    /// # struct Renderer(Option<Context>);
+    /// # impl Renderer {
+    /// #     fn context_mut(&mut self) -> Option<&mut Context> {
+    /// #         self.0.as_mut()
+    /// #     }
+    /// # }
+    /// # let destroyed = Arc::new(Mutex::new(Vec::new()));
+    /// # let mut world = World::new();
+    /// # world.insert_non_send_resource(Renderer(Some(Context(destroyed.clone()))));
+    /// world.on_remove::<MeshRenderer>(|world, entity| {
+    ///     let buffers = world.get::<MeshRenderer>(entity).unwrap().buffers.clone();
+    ///     let renderer = world.non_send_resource_mut::<Renderer>().unwrap();
+    ///     if let Some(context) = renderer.context_mut() {
+    ///         for buffer in buffers {
+    ///             context.destroy_buffer(buffer);
+    ///         }
+    ///     }
+    /// });
+    /// # let entity = world.spawn((MeshRenderer { buffers: vec![1, 2] },));
+    /// # world.despawn(entity);
+    /// # assert_eq!(*destroyed.lock().unwrap(), [1, 2]);
+    /// ```
+    pub fn on_remove<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, Entity) + Send + Sync + 'static,
+    ) -> &mut Self {
+        let id = self.components.register::<T>();
+        self.hooks.push(HookKind::Remove, id, Arc::new(hook));
+        self
+    }
+
+    /// Runs `hook` right before any entity is despawned, ahead of the
+    /// `on_remove` hooks of its components. The hook must not despawn the
+    /// entity itself.
+    pub fn on_despawn(
+        &mut self,
+        hook: impl Fn(&mut World, Entity) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.hooks.despawn.push(Arc::new(hook));
+        self
+    }
+
+    pub(crate) fn trigger(&mut self, kind: HookKind, id: TypeId, entity: Entity) {
+        if !self.hooks.has(kind, id) {
+            return;
+        }
+
+        for hook in self.hooks.get(kind, id) {
+            // An earlier hook may have despawned the entity.
+            if !self.contains(entity) {
+                return;
+            }
+            hook(self, entity);
+        }
+    }
+
+    pub(crate) fn trigger_despawn(&mut self, entity: Entity) {
+        for hook in self.hooks.despawn.clone() {
+            if !self.contains(entity) {
+                return;
+            }
+            hook(self, entity);
+        }
+    }
+}
@@ -1 +1,35 @@
+mod archetype;
+mod change;
+mod commands;
+mod component;
+mod entity;
+mod hierarchy;
+mod hooks;
+mod manager;
+mod query;
+mod reflect;
+mod resource;
+mod scene;
+mod schedule;
+mod system;
+mod world;
 
+pub use change::{ChangeTicks, Mut, RemovedComponents};
+pub use commands::{CommandQueue, Commands};
+pub use component::{Bundle, Component};
+pub use entity::Entity;
+pub use hierarchy::{Children, GlobalTransform, Parent, propagate_transforms};
+pub use query::{
+    Access, Added, Changed, Or, Query, QueryFilter, QueryIter, With, Without, WorldQuery,
+};
+pub use reflect::{FieldInfo, Reflect, ReflectError, TypeInfo, TypeRegistration, TypeRegistry};
+pub use resource::{NonSend, NonSendMut, Res, ResMut, Resource};
+pub use scene::{
+    EntityMap, Scene, SceneComponent, SceneEntity, SceneError, SceneRegistry, SceneValue, Value,
+};
+pub use schedule::{IntoSystemConfig, Schedule, SystemConfig};
+pub use system::{
+    ExclusiveFunctionSystem, FunctionSystem, IntoSystem, System, SystemMeta, SystemParam,
+    SystemParamFunction, SystemParamItem, apply_deferred,
+};
+pub use world::World;
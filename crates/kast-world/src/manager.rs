@@ -0,0 +1,125 @@
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use kast_resources::{Handle, Pool};
+
+use crate::{Entity, archetype::EntityLocation};
+
+struct Slots {
+    locations: Pool<EntityLocation, Entity>,
+    /// Ids handed out by `reserve` that `World::flush` hasn't placed yet.
+    pending: Vec<Entity>,
+}
+
+/// Allocates and recycles entity ids.
+///
+/// Liveness is delegated to a `Pool`, which gives us index recycling and
+/// generation bumps for free. Each slot stores where the entity's components
+/// live, so resolving an `Entity` to its row is a single lookup.
+///
+/// The pool sits behind a lock so `Commands` can reserve ids through a shared
+/// `&World` while systems run; everything going through `&mut self` bypasses
+/// the lock entirely.
+pub(crate) struct EntityManager {
+    slots: RwLock<Slots>,
+    len: usize,
+}
+
+impl Default for EntityManager {
+    fn default() -> Self {
+        Self {
+            slots: RwLock::new(Slots {
+                locations: Pool::new(),
+                pending: Vec::new(),
+            }),
+            len: 0,
+        }
+    }
+}
+
+impl EntityManager {
+    fn read(&self) -> RwLockReadGuard<'_, Slots> {
+        self.slots.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Slots> {
+        self.slots.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn slots_mut(&mut self) -> &mut Slots {
+        self.slots.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn spawn(&mut self, location: EntityLocation) -> Entity {
+        let handle = self.slots_mut().locations.insert(location);
+        self.len += 1;
+
+        entity(handle)
+    }
+
+    /// Hands out an id that stays unresolvable until `World::flush` gives it
+    /// a location.
+    pub fn reserve(&self) -> Entity {
+        let mut slots = self.write();
+        let entity = entity(slots.locations.insert(EntityLocation::RESERVED));
+        slots.pending.push(entity);
+
+        entity
+    }
+
+    pub fn take_pending(&mut self) -> Vec<Entity> {
+        core::mem::take(&mut self.slots_mut().pending)
+    }
+
+    pub fn has_pending(&mut self) -> bool {
+        !self.slots_mut().pending.is_empty()
+    }
+
+    /// Frees the entity's slot, returning where its components lived, or
+    /// `None` if it was already dead or never placed.
+    pub fn despawn(&mut self, entity: Entity) -> Option<EntityLocation> {
+        let location = self.slots_mut().locations.remove(handle(entity))?;
+        if location == EntityLocation::RESERVED {
+            return None;
+        }
+
+        self.len -= 1;
+        Some(location)
+    }
+
+    pub fn location(&self, entity: Entity) -> Option<EntityLocation> {
+        self.read()
+            .locations
+            .get(handle(entity))
+            .copied()
+            .filter(|location| *location != EntityLocation::RESERVED)
+    }
+
+    /// Places an entity; for a reserved entity this is what makes it alive.
+    pub fn set_location(&mut self, entity: Entity, location: EntityLocation) {
+        let Some(slot) = self.slots_mut().locations.get_mut(handle(entity)) else {
+            return;
+        };
+
+        let was_reserved = *slot == EntityLocation::RESERVED;
+        *slot = location;
+        if was_reserved {
+            self.len += 1;
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.location(entity).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
+
+fn handle(entity: Entity) -> Handle<Entity> {
+    Handle::from_raw_parts(entity.index, entity.generation)
+}
+
+fn entity(handle: Handle<Entity>) -> Entity {
+    Entity::new(handle.index(), handle.generation())
+}
@@ -0,0 +1,861 @@
+use std::any::{TypeId, type_name};
+use std::marker::PhantomData;
+
+use crate::{
+    ChangeTicks, Component, Entity, Mut, World, archetype::Archetype, change::ComponentTicks,
+};
+
+/// The component and resource types a query or system reads and writes.
+///
+/// Two accesses are compatible when neither writes a type the other touches.
+/// An exclusive access, taken by systems borrowing the whole `&mut World`,
+/// is compatible with nothing.
+#[derive(Clone, Debug, Default)]
+pub struct Access {
+    reads: Vec<TypeId>,
+    writes: Vec<TypeId>,
+    resource_reads: Vec<TypeId>,
+    resource_writes: Vec<TypeId>,
+    exclusive: bool,
+}
+
+impl Access {
+    pub fn add_read(&mut self, id: TypeId) {
+        self.reads.push(id);
+    }
+
+    pub fn add_write(&mut self, id: TypeId) {
+        self.writes.push(id);
+    }
+
+    pub fn add_resource_read(&mut self, id: TypeId) {
+        self.resource_reads.push(id);
+    }
+
+    pub fn add_resource_write(&mut self, id: TypeId) {
+        self.resource_writes.push(id);
+    }
+
+    pub fn set_exclusive(&mut self) {
+        self.exclusive = true;
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    pub fn reads(&self) -> &[TypeId] {
+        &self.reads
+    }
+
+    pub fn writes(&self) -> &[TypeId] {
+        &self.writes
+    }
+
+    pub fn resource_reads(&self) -> &[TypeId] {
+        &self.resource_reads
+    }
+
+    pub fn resource_writes(&self) -> &[TypeId] {
+        &self.resource_writes
+    }
+
+    /// Adds everything `other` touches to this access.
+    pub fn extend(&mut self, other: &Access) {
+        self.reads.extend_from_slice(&other.reads);
+        self.writes.extend_from_slice(&other.writes);
+        self.resource_reads.extend_from_slice(&other.resource_reads);
+        self.resource_writes
+            .extend_from_slice(&other.resource_writes);
+        self.exclusive |= other.exclusive;
+    }
+
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        if self.exclusive || other.exclusive {
+            return false;
+        }
+
+        let disjoint = |reads: &[TypeId],
+                        writes: &[TypeId],
+                        other_reads: &[TypeId],
+                        other_writes: &[TypeId]| {
+            !writes
+                .iter()
+                .any(|id| other_reads.contains(id) || other_writes.contains(id))
+                && !other_writes.iter().any(|id| reads.contains(id))
+        };
+
+        disjoint(&self.reads, &self.writes, &other.reads, &other.writes)
+            && disjoint(
+                &self.resource_reads,
+                &self.resource_writes,
+                &other.resource_reads,
+                &other.resource_writes,
+            )
+    }
+
+    /// Whether this access aliases itself, e.g. `(&mut T, &T)`.
+    fn is_self_conflicting(&self) -> bool {
+        self.writes
+            .iter()
+            .enumerate()
+            .any(|(i, id)| self.reads.contains(id) || self.writes[i + 1..].contains(id))
+    }
+}
+
+/// Types that can be fetched from a `World` by a `Query`.
+///
+/// Implemented for `&T`, `&mut T` (fetched as `Mut<T>`), `Entity`,
+/// `Option<Q>` and tuples of up to eight queries. Queries run archetype by archetype: `matches` picks the
+/// tables to visit, `init_fetch` resolves the columns once per table and
+/// `fetch` indexes into them by row.
+///
+/// # Safety
+///
+/// `access` must declare every component type `fetch` reads or writes, since
+/// it's what keeps two fetches from handing out aliasing `&mut` references.
+pub unsafe trait WorldQuery {
+    type Item<'w>;
+    #[doc(hidden)]
+    type Fetch<'w>;
+
+    fn access(access: &mut Access);
+
+    /// Whether entities of `archetype` can produce an item.
+    #[doc(hidden)]
+    fn matches(archetype: &Archetype) -> bool;
+
+    /// # Safety
+    ///
+    /// `archetype` must match, and the caller must hold the access declared by
+    /// `access` for `'w`.
+    #[doc(hidden)]
+    unsafe fn init_fetch(archetype: &Archetype, ticks: ChangeTicks) -> Self::Fetch<'_>;
+
+    /// # Safety
+    ///
+    /// `row` must be in bounds, and the caller must not hold another item for
+    /// the same row.
+    #[doc(hidden)]
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, row: usize) -> Self::Item<'w>;
+}
+
+unsafe impl WorldQuery for Entity {
+    type Item<'w> = Entity;
+    type Fetch<'w> = &'w [Entity];
+
+    fn access(_access: &mut Access) {}
+
+    fn matches(_archetype: &Archetype) -> bool {
+        true
+    }
+
+    unsafe fn init_fetch(archetype: &Archetype, _ticks: ChangeTicks) -> Self::Fetch<'_> {
+        archetype.entities()
+    }
+
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+        fetch[row]
+    }
+}
+
+#[doc(hidden)]
+pub struct ColumnFetch<'w, T> {
+    components: *mut T,
+    _marker: PhantomData<&'w mut T>,
+}
+
+unsafe impl<T: Component> WorldQuery for &T {
+    type Item<'w> = &'w T;
+    type Fetch<'w> = ColumnFetch<'w, T>;
+
+    fn access(access: &mut Access) {
+        access.add_read(TypeId::of::<T>());
+    }
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has(TypeId::of::<T>())
+    }
+
+    unsafe fn init_fetch(archetype: &Archetype, _ticks: ChangeTicks) -> Self::Fetch<'_> {
+        let column = archetype
+            .column(TypeId::of::<T>())
+            .expect("query fetched from an archetype it doesn't match");
+
+        ColumnFetch {
+            components: column.typed::<T>().data.as_ptr() as *mut T,
+            _marker: PhantomData,
+        }
+    }
+
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+        unsafe { &*fetch.components.add(row) }
+    }
+}
+
+#[doc(hidden)]
+pub struct WriteFetch<'w, T> {
+    components: *mut T,
+    ticks: *mut ComponentTicks,
+    column_ticks: *mut ComponentTicks,
+    change_ticks: ChangeTicks,
+    _marker: PhantomData<&'w mut T>,
+}
+
+unsafe impl<T: Component> WorldQuery for &mut T {
+    type Item<'w> = Mut<'w, T>;
+    type Fetch<'w> = WriteFetch<'w, T>;
+
+    fn access(access: &mut Access) {
+        access.add_write(TypeId::of::<T>());
+    }
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has(TypeId::of::<T>())
+    }
+
+    unsafe fn init_fetch(archetype: &Archetype, ticks: ChangeTicks) -> Self::Fetch<'_> {
+        let column = archetype
+            .column(TypeId::of::<T>())
+            .expect("query fetched from an archetype it doesn't match");
+        let (row_ticks, column_ticks) = column.ticks_ptr();
+
+        // SAFETY: `access` declares a write, so no other fetch of the running
+        // query touches this column.
+        WriteFetch {
+            components: unsafe { column.typed_mut_unchecked::<T>() }
+                .data
+                .as_mut_ptr(),
+            ticks: row_ticks,
+            column_ticks,
+            change_ticks: ticks,
+            _marker: PhantomData,
+        }
+    }
+
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+        unsafe {
+            Mut::new(
+                &mut *fetch.components.add(row),
+                &mut *fetch.ticks.add(row),
+                fetch.column_ticks,
+                fetch.change_ticks,
+            )
+        }
+    }
+}
+
+unsafe impl<Q: WorldQuery> WorldQuery for Option<Q> {
+    type Item<'w> = Option<Q::Item<'w>>;
+    type Fetch<'w> = Option<Q::Fetch<'w>>;
+
+    fn access(access: &mut Access) {
+        Q::access(access);
+    }
+
+    fn matches(_archetype: &Archetype) -> bool {
+        true
+    }
+
+    unsafe fn init_fetch(archetype: &Archetype, ticks: ChangeTicks) -> Self::Fetch<'_> {
+        Q::matches(archetype).then(|| unsafe { Q::init_fetch(archetype, ticks) })
+    }
+
+    unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+        fetch.as_mut().map(|fetch| unsafe { Q::fetch(fetch, row) })
+    }
+}
+
+macro_rules! impl_world_query {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case, unused_variables, clippy::unused_unit)]
+        unsafe impl<$($name: WorldQuery),*> WorldQuery for ($($name,)*) {
+            type Item<'w> = ($($name::Item<'w>,)*);
+            type Fetch<'w> = ($($name::Fetch<'w>,)*);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            fn matches(archetype: &Archetype) -> bool {
+                true $(&& $name::matches(archetype))*
+            }
+
+            unsafe fn init_fetch(archetype: &Archetype, ticks: ChangeTicks) -> Self::Fetch<'_> {
+                ($(unsafe { $name::init_fetch(archetype, ticks) },)*)
+            }
+
+            unsafe fn fetch<'w>(fetch: &mut Self::Fetch<'w>, row: usize) -> Self::Item<'w> {
+                let ($($name,)*) = fetch;
+                ($(unsafe { $name::fetch($name, row) },)*)
+            }
+        }
+    };
+}
+
+impl_world_query!();
+impl_world_query!(A);
+impl_world_query!(A, B);
+impl_world_query!(A, B, C);
+impl_world_query!(A, B, C, D);
+impl_world_query!(A, B, C, D, E);
+impl_world_query!(A, B, C, D, E, F);
+impl_world_query!(A, B, C, D, E, F, G);
+impl_world_query!(A, B, C, D, E, F, G, H);
+
+/// Narrows the entities a `Query` visits without fetching anything.
+///
+/// Implemented for `With<T>`, `Without<T>`, `Added<T>`, `Changed<T>`, `Or`
+/// and tuples, which require every filter to pass. Tables where no row can
+/// pass are skipped as a whole.
+///
+/// # Safety
+///
+/// `access` must declare every component type `filter` reads.
+pub unsafe trait QueryFilter {
+    #[doc(hidden)]
+    type Fetch<'w>;
+
+    fn access(access: &mut Access);
+
+    #[doc(hidden)]
+    fn matches(archetype: &Archetype) -> bool;
+
+    /// Returns `None` when no row of `archetype` can pass.
+    ///
+    /// # Safety
+    ///
+    /// `archetype` must match, and the caller must hold the access declared by
+    /// `access` for `'w`.
+    #[doc(hidden)]
+    unsafe fn init_fetch(archetype: &Archetype, ticks: ChangeTicks) -> Option<Self::Fetch<'_>>;
+
+    /// # Safety
+    ///
+    /// `row` must be in bounds.
+    #[doc(hidden)]
+    unsafe fn filter(fetch: &mut Self::Fetch<'_>, row: usize) -> bool;
+}
+
+/// Only visits entities that have a `T`, without fetching it.
+pub struct With<T>(PhantomData<fn() -> T>);
+
+/// Only visits entities that don't have a `T`.
+pub struct Without<T>(PhantomData<fn() -> T>);
+
+/// Only visits entities whose `T` was added since the system last ran.
+pub struct Added<T>(PhantomData<fn() -> T>);
+
+/// Only visits entities whose `T` was added or mutably borrowed since the
+/// system last ran.
+///
+/// ```
+/// # use kast_math::Transform;
+/// # use kast_world::{Changed, Query};
+/// # struct MeshRenderer;
+/// fn sync_transforms(query: Query<(&Transform, &MeshRenderer), Changed<Transform>>) {
+///     // Only the entities that moved this frame.
+/// }
+/// # kast_world::Schedule::new().add_system(sync_transforms);
+/// ```
+pub struct Changed<T>(PhantomData<fn() -> T>);
+
+/// Visits entities passing any of the filters in the tuple, e.g.
+/// `Or<(Changed<Transform>, Changed<Mesh>)>`.
+pub struct Or<F>(PhantomData<fn() -> F>);
+
+unsafe impl<T: Component> QueryFilter for With<T> {
+    type Fetch<'w> = ();
+
+    fn access(_access: &mut Access) {}
+
+    fn matches(archetype: &Archetype) -> bool {
+        archetype.has(TypeId::of::<T>())
+    }
+
+    unsafe fn init_fetch(_archetype: &Archetype, _ticks: ChangeTicks) -> Option<Self::Fetch<'_>> {
+        Some(())
+    }
+
+    unsafe fn filter(_fetch: &mut Self::Fetch<'_>, _row: usize) -> bool {
+        true
+    }
+}
+
+unsafe impl<T: Component> QueryFilter for Without<T> {
+    type Fetch<'w> = ();
+
+    fn access(_access: &mut Access) {}
+
+    fn matches(archetype: &Archetype) -> bool {
+        !archetype.has(TypeId::of::<T>())
+    }
+
+    unsafe fn init_fetch(_archetype: &Archetype, _ticks: ChangeTicks) -> Option<Self::Fetch<'_>> {
+        Some(())
+    }
+
+    unsafe fn filter(_fetch: &mut Self::Fetch<'_>, _row: usize) -> bool {
+        true
+    }
+}
+
+#[doc(hidden)]
+pub struct TickFetch<'w> {
+    // A pointer rather than a slice: `&mut T` in the same query writes these
+    // ticks while the filter still reads them.
+    ticks: *const ComponentTicks,
+    change_ticks: ChangeTicks,
+    _marker: PhantomData<&'w [ComponentTicks]>,
+}
+
+macro_rules! impl_tick_filter {
+    ($filter:ident, $field:ident) => {
+        unsafe impl<T: Component> QueryFilter for $filter<T> {
+            type Fetch<'w> = TickFetch<'w>;
+
+            fn access(access: &mut Access) {
+                access.add_read(TypeId::of::<T>());
+            }
+
+            fn matches(archetype: &Archetype) -> bool {
+                archetype.has(TypeId::of::<T>())
+            }
+
+            unsafe fn init_fetch(
+                archetype: &Archetype,
+                ticks: ChangeTicks,
+            ) -> Option<Self::Fetch<'_>> {
+                let column = archetype
+                    .column(TypeId::of::<T>())
+                    .expect("query fetched from an archetype it doesn't match");
+                if !ticks.is_newer(column.last_ticks().$field) {
+                    return None;
+                }
+
+                Some(TickFetch {
+                    ticks: column.ticks_ptr().0,
+                    change_ticks: ticks,
+                    _marker: PhantomData,
+                })
+            }
+
+            unsafe fn filter(fetch: &mut Self::Fetch<'_>, row: usize) -> bool {
+                // SAFETY: the caller passes rows of the archetype the fetch was
+                // initialized for, and nothing writes ticks while one is read.
+                let ticks = unsafe { fetch.ticks.add(row).read() };
+                fetch.change_ticks.is_newer(ticks.$field)
+            }
+        }
+    };
+}
+
+impl_tick_filter!(Added, added);
+impl_tick_filter!(Changed, changed);
+
+macro_rules! impl_query_filter {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case, unused_variables, clippy::unused_unit)]
+        unsafe impl<$($name: QueryFilter),*> QueryFilter for ($($name,)*) {
+            type Fetch<'w> = ($($name::Fetch<'w>,)*);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            fn matches(archetype: &Archetype) -> bool {
+                true $(&& $name::matches(archetype))*
+            }
+
+            unsafe fn init_fetch(
+                archetype: &Archetype,
+                ticks: ChangeTicks,
+            ) -> Option<Self::Fetch<'_>> {
+                Some(($(unsafe { $name::init_fetch(archetype, ticks) }?,)*))
+            }
+
+            unsafe fn filter(fetch: &mut Self::Fetch<'_>, row: usize) -> bool {
+                let ($($name,)*) = fetch;
+                true $(&& unsafe { $name::filter($name, row) })*
+            }
+        }
+    };
+}
+
+macro_rules! impl_or_filter {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case)]
+        unsafe impl<$($name: QueryFilter),*> QueryFilter for Or<($($name,)*)> {
+            type Fetch<'w> = ($(Option<$name::Fetch<'w>>,)*);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            fn matches(archetype: &Archetype) -> bool {
+                false $(|| $name::matches(archetype))*
+            }
+
+            unsafe fn init_fetch(
+                archetype: &Archetype,
+                ticks: ChangeTicks,
+            ) -> Option<Self::Fetch<'_>> {
+                let fetch = ($(
+                    $name::matches(archetype)
+                        .then(|| unsafe { $name::init_fetch(archetype, ticks) })
+                        .flatten(),
+                )*);
+
+                let ($($name,)*) = &fetch;
+                (false $(|| $name.is_some())*).then_some(fetch)
+            }
+
+            unsafe fn filter(fetch: &mut Self::Fetch<'_>, row: usize) -> bool {
+                let ($($name,)*) = fetch;
+                false $(|| $name
+                    .as_mut()
+                    .is_some_and(|fetch| unsafe { $name::filter(fetch, row) }))*
+            }
+        }
+    };
+}
+
+impl_query_filter!();
+impl_query_filter!(A);
+impl_query_filter!(A, B);
+impl_query_filter!(A, B, C);
+impl_query_filter!(A, B, C, D);
+impl_query_filter!(A, B, C, D, E);
+impl_query_filter!(A, B, C, D, E, F);
+impl_query_filter!(A, B, C, D, E, F, G);
+impl_query_filter!(A, B, C, D, E, F, G, H);
+
+impl_or_filter!(A);
+impl_or_filter!(A, B);
+impl_or_filter!(A, B, C);
+impl_or_filter!(A, B, C, D);
+impl_or_filter!(A, B, C, D, E);
+impl_or_filter!(A, B, C, D, E, F);
+impl_or_filter!(A, B, C, D, E, F, G);
+impl_or_filter!(A, B, C, D, E, F, G, H);
+
+/// A typed view over every entity matching `Q` and passing the filter `F`.
+///
+/// Obtained from `World::query`, e.g.
+/// `world.query::<(&mut Transform, &Velocity)>()`, or as a system parameter.
+pub struct Query<'w, Q: WorldQuery, F: QueryFilter = ()> {
+    world: &'w World,
+    ticks: ChangeTicks,
+    _marker: PhantomData<(Q, F)>,
+}
+
+impl<'w, Q: WorldQuery, F: QueryFilter> Query<'w, Q, F> {
+    /// # Panics
+    ///
+    /// Panics if `Q` requests mutable access to a component it also reads or
+    /// writes elsewhere, such as `(&mut T, &T)`.
+    pub(crate) fn new(world: &'w World, ticks: ChangeTicks) -> Self {
+        Self::validated_access();
+        Self::new_unchecked(world, ticks)
+    }
+
+    /// Builds a query without validating `Q`; used by systems, which check
+    /// their access once at initialization.
+    pub(crate) fn new_unchecked(world: &'w World, ticks: ChangeTicks) -> Self {
+        Self {
+            world,
+            ticks,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The access of `Q` and `F`. Filters only read change ticks, so they
+    /// may name a component `Q` writes, as in `Query<&mut T, Changed<T>>`.
+    pub(crate) fn validated_access() -> Access {
+        let mut access = Access::default();
+        Q::access(&mut access);
+        if access.is_self_conflicting() {
+            panic!(
+                "query `{}` accesses the same component mutably more than once",
+                type_name::<Q>()
+            );
+        }
+
+        F::access(&mut access);
+        access
+    }
+
+    pub fn iter(&mut self) -> QueryIter<'_, Q, F> {
+        QueryIter::new(self.world, self.ticks)
+    }
+
+    /// Fetches the query item for a single entity, if it matches.
+    pub fn get(&mut self, entity: Entity) -> Option<Q::Item<'_>> {
+        let (archetype, row) = self.world.archetype_of(entity)?;
+        if !Q::matches(archetype) || !F::matches(archetype) {
+            return None;
+        }
+
+        unsafe {
+            let mut filter = F::init_fetch(archetype, self.ticks)?;
+            if !F::filter(&mut filter, row) {
+                return None;
+            }
+
+            let mut fetch = Q::init_fetch(archetype, self.ticks);
+            Some(Q::fetch(&mut fetch, row))
+        }
+    }
+}
+
+impl<'w, Q: WorldQuery, F: QueryFilter> IntoIterator for Query<'w, Q, F> {
+    type Item = Q::Item<'w>;
+    type IntoIter = QueryIter<'w, Q, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        QueryIter::new(self.world, self.ticks)
+    }
+}
+
+/// Iterates the rows of every archetype matching `Q` and `F`, one table at a
+/// time.
+pub struct QueryIter<'w, Q: WorldQuery, F: QueryFilter = ()> {
+    archetypes: core::slice::Iter<'w, Archetype>,
+    ticks: ChangeTicks,
+    fetch: Option<(Q::Fetch<'w>, F::Fetch<'w>)>,
+    row: usize,
+    len: usize,
+}
+
+impl<'w, Q: WorldQuery, F: QueryFilter> QueryIter<'w, Q, F> {
+    fn new(world: &'w World, ticks: ChangeTicks) -> Self {
+        Self {
+            archetypes: world.archetypes.iter(),
+            ticks,
+            fetch: None,
+            row: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<'w, Q: WorldQuery, F: QueryFilter> Iterator for QueryIter<'w, Q, F> {
+    type Item = Q::Item<'w>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((fetch, filter)) = &mut self.fetch {
+                while self.row < self.len {
+                    let row = self.row;
+                    self.row += 1;
+
+                    // SAFETY: every row is visited once, so items never alias.
+                    unsafe {
+                        if F::filter(filter, row) {
+                            return Some(Q::fetch(fetch, row));
+                        }
+                    }
+                }
+            }
+
+            let archetype = self.archetypes.next()?;
+            if archetype.len() == 0 || !Q::matches(archetype) || !F::matches(archetype) {
+                continue;
+            }
+
+            self.fetch = unsafe {
+                F::init_fetch(archetype, self.ticks)
+                    .map(|filter| (Q::init_fetch(archetype, self.ticks), filter))
+            };
+            self.row = 0;
+            self.len = archetype.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RemovedComponents, ResMut, Schedule};
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+
+    /// What the systems below saw each time they ran.
+    #[derive(Default)]
+    struct Seen(Vec<Vec<Entity>>);
+
+    fn added(query: Query<Entity, Added<Position>>, mut seen: ResMut<Seen>) {
+        seen.0.push(query.into_iter().collect());
+    }
+
+    fn removed(removed: RemovedComponents<Position>, mut seen: ResMut<Seen>) {
+        seen.0.push(removed.iter().collect());
+    }
+
+    fn run(schedule: &mut Schedule, world: &mut World) -> Vec<Entity> {
+        schedule.run(world);
+        world.clear_trackers();
+        world.resource_mut::<Seen>().unwrap().0.pop().unwrap()
+    }
+
+    #[test]
+    fn filters_pick_matching_entities() {
+        let mut world = World::new();
+        let still = world.spawn((Position(1),));
+        let moving = world.spawn((Position(2), Velocity(1)));
+        let bare = world.spawn((Velocity(2),));
+
+        let with = world
+            .query_filtered::<Entity, With<Velocity>>()
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(with.len(), 2);
+        assert!(with.contains(&moving) && with.contains(&bare));
+        assert!(
+            world
+                .query_filtered::<Entity, Without<Velocity>>()
+                .iter()
+                .eq([still])
+        );
+        assert!(
+            world
+                .query_filtered::<Entity, (With<Position>, Without<Position>)>()
+                .iter()
+                .next()
+                .is_none()
+        );
+
+        let mut query = world.query::<(&Position, Option<&Velocity>)>();
+        assert_eq!(query.get(still), Some((&Position(1), None)));
+        assert_eq!(query.get(moving), Some((&Position(2), Some(&Velocity(1)))));
+        assert_eq!(query.get(bare), None);
+        assert_eq!(query.iter().count(), 2);
+
+        for mut velocity in world.query::<&mut Velocity>() {
+            velocity.0 *= 10;
+        }
+        assert_eq!(world.get::<Velocity>(bare), Some(&Velocity(20)));
+    }
+
+    #[test]
+    fn added_and_changed_last_until_the_trackers_are_cleared() {
+        let mut world = World::new();
+        let first = world.spawn((Position(1),));
+        let second = world.spawn((Position(2),));
+
+        assert_eq!(
+            world
+                .query_filtered::<Entity, Added<Position>>()
+                .iter()
+                .count(),
+            2
+        );
+        assert_eq!(
+            world
+                .query_filtered::<Entity, Changed<Position>>()
+                .iter()
+                .count(),
+            2
+        );
+
+        world.clear_trackers();
+        assert!(
+            world
+                .query_filtered::<Entity, Added<Position>>()
+                .iter()
+                .next()
+                .is_none()
+        );
+        assert!(
+            world
+                .query_filtered::<Entity, Changed<Position>>()
+                .iter()
+                .next()
+                .is_none()
+        );
+
+        world.get_mut::<Position>(second).unwrap().0 += 1;
+        world.insert(first, Velocity(0));
+        assert!(
+            world
+                .query_filtered::<Entity, Changed<Position>>()
+                .iter()
+                .eq([second])
+        );
+        assert!(
+            world
+                .query_filtered::<Entity, Added<Velocity>>()
+                .iter()
+                .eq([first])
+        );
+        assert!(
+            world
+                .query_filtered::<Entity, Added<Position>>()
+                .iter()
+                .next()
+                .is_none()
+        );
+
+        world.clear_trackers();
+        assert!(
+            world
+                .query_filtered::<Entity, Changed<Position>>()
+                .iter()
+                .next()
+                .is_none()
+        );
+        assert!(
+            world
+                .query_filtered::<Entity, Added<Velocity>>()
+                .iter()
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn systems_see_additions_made_since_they_last_ran() {
+        let mut world = World::new();
+        world.insert_resource(Seen::default());
+        let mut schedule = Schedule::new();
+        schedule.add_system(added);
+
+        let first = world.spawn((Position(1),));
+        assert_eq!(run(&mut schedule, &mut world), [first]);
+
+        // Made between frames, after the system ran.
+        let second = world.spawn((Position(2),));
+        world.clear_trackers();
+        assert_eq!(run(&mut schedule, &mut world), [second]);
+        assert_eq!(run(&mut schedule, &mut world), []);
+    }
+
+    #[test]
+    fn removals_are_kept_for_two_frames() {
+        let mut world = World::new();
+        world.insert_resource(Seen::default());
+        let mut schedule = Schedule::new();
+        schedule.add_system(removed);
+
+        let kept = world.spawn((Position(1),));
+        let despawned = world.spawn((Position(2),));
+        assert_eq!(run(&mut schedule, &mut world), []);
+
+        // Removed after the system ran, so it only sees them next frame.
+        world.remove::<Position>(kept);
+        world.despawn(despawned);
+        let mut direct = world.removed::<Position>().collect::<Vec<_>>();
+        direct.sort_by_key(|entity| entity.index);
+        assert_eq!(direct, [kept, despawned]);
+
+        world.clear_trackers();
+        assert!(world.removed::<Position>().next().is_none());
+        assert_eq!(run(&mut schedule, &mut world).len(), 2);
+        assert_eq!(run(&mut schedule, &mut world), []);
+        assert!(world.removed_log(TypeId::of::<Position>()).is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use crate::{Component, Entity, Mut, SceneError, Value, World};
+
+pub use kast_world_derive::Reflect;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReflectError {
+    /// No type is registered under this name.
+    UnknownType(String),
+    UnknownField(String),
+    /// The entity doesn't have the component.
+    MissingComponent(Entity),
+    InvalidValue(String),
+}
+
+impl core::fmt::Display for ReflectError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl core::error::Error for ReflectError {}
+
+impl From<SceneError> for ReflectError {
+    fn from(error: SceneError) -> Self {
+        match error {
+            SceneError::InvalidValue(message) => ReflectError::InvalidValue(message),
+            error => ReflectError::InvalidValue(error.to_string()),
+        }
+    }
+}
+
+/// The name and fields of a reflected type.
+#[derive(Debug)]
+pub struct TypeInfo {
+    pub name: &'static str,
+    pub fields: &'static [FieldInfo],
+}
+
+impl TypeInfo {
+    pub fn field(&self, name: &str) -> Option<&FieldInfo> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+#[derive(Debug)]
+pub struct FieldInfo {
+    /// The field name, or its index for tuple structs.
+    pub name: &'static str,
+    /// The field type as written in the source.
+    pub type_name: &'static str,
+}
+
+/// Runtime access to the fields of a type. Derive it rather than
+/// implementing it by hand; the derive also implements `SceneValue`.
+///
+/// ```
+/// # use kast_world::Reflect;
+/// # #[derive(Default)]
+/// # struct Timer(f32);
+/// #[derive(Reflect)]
+/// struct Health {
+///     current: f32,
+///     max: f32,
+///     #[reflect(ignore)]
+///     regen_timer: Timer,
+/// }
+/// # assert!(Health::type_info().field("regen_timer").is_none());
+/// ```
+pub trait Reflect: Any + Send + Sync {
+    fn type_info() -> &'static TypeInfo
+    where
+        Self: Sized;
+
+    fn info(&self) -> &'static TypeInfo;
+
+    fn field(&self, name: &str) -> Option<&dyn Any>;
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Any>;
+
+    /// The field converted to a `Value`.
+    fn field_value(&self, name: &str) -> Option<Value>;
+
+    fn set_field_value(&mut self, name: &str, value: &Value) -> Result<(), ReflectError>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl dyn Reflect {
+    pub fn downcast_ref<T: Reflect>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    pub fn downcast_mut<T: Reflect>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
+
+    /// A typed reference to a field.
+    pub fn get_field<T: Any>(&self, name: &str) -> Option<&T> {
+        self.field(name)?.downcast_ref()
+    }
+
+    pub fn get_field_mut<T: Any>(&mut self, name: &str) -> Option<&mut T> {
+        self.field_mut(name)?.downcast_mut()
+    }
+}
+
+/// A registered component type, with accessors that work on any entity.
+pub struct TypeRegistration {
+    info: &'static TypeInfo,
+    type_id: TypeId,
+    reflect: fn(&World, Entity) -> Option<&dyn Reflect>,
+    reflect_mut: fn(&mut World, Entity) -> Option<Mut<'_, dyn Reflect>>,
+}
+
+impl TypeRegistration {
+    pub fn info(&self) -> &'static TypeInfo {
+        self.info
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.info.name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn reflect<'w>(&self, world: &'w World, entity: Entity) -> Option<&'w dyn Reflect> {
+        (self.reflect)(world, entity)
+    }
+
+    /// Writes through the result mark the component as changed.
+    pub fn reflect_mut<'w>(
+        &self,
+        world: &'w mut World,
+        entity: Entity,
+    ) -> Option<Mut<'w, dyn Reflect>> {
+        (self.reflect_mut)(world, entity)
+    }
+
+    /// Reads one field of the entity's component.
+    pub fn get(&self, world: &World, entity: Entity, field: &str) -> Result<Value, ReflectError> {
+        let component = self
+            .reflect(world, entity)
+            .ok_or(ReflectError::MissingComponent(entity))?;
+
+        component
+            .field_value(field)
+            .ok_or_else(|| ReflectError::UnknownField(field.to_owned()))
+    }
+
+    /// Overwrites one field of the entity's component.
+    pub fn set(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        field: &str,
+        value: &Value,
+    ) -> Result<(), ReflectError> {
+        let mut component = self
+            .reflect_mut(world, entity)
+            .ok_or(ReflectError::MissingComponent(entity))?;
+
+        component.set_field_value(field, value)
+    }
+}
+
+/// The reflected component types, looked up by name or by type.
+///
+/// ```
+/// # use kast_world::{Reflect, TypeRegistry, Value, World};
+/// # #[derive(Reflect)]
+/// # struct Health {
+/// #     current: f32,
+/// # }
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let mut registry = TypeRegistry::new();
+/// # registry.register::<Health>();
+/// # let mut world = World::new();
+/// # let entity = world.spawn((Health { current: 10.0 },));
+/// // A console command such as `set 3v0 Health.current 50`.
+/// let health = registry.get("Health").unwrap();
+/// health.set(&mut world, entity, "current", &Value::from_json("50")?)?;
+/// # assert_eq!(world.get::<Health>(entity).unwrap().current, 50.0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct TypeRegistry {
+    registrations: Vec<TypeRegistration>,
+    by_name: HashMap<&'static str, usize>,
+    by_type: HashMap<TypeId, usize>,
+}
+
+impl TypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if another type is already registered under the same name.
+    pub fn register<T: Reflect + Component>(&mut self) -> &mut Self {
+        let type_id = TypeId::of::<T>();
+        if self.by_type.contains_key(&type_id) {
+            return self;
+        }
+
+        let info = T::type_info();
+        if self.by_name.contains_key(info.name) {
+            panic!("reflected type name `{}` is registered twice", info.name);
+        }
+
+        let index = self.registrations.len();
+        self.registrations.push(TypeRegistration {
+            info,
+            type_id,
+            reflect: |world, entity| world.get::<T>(entity).map(|value| value as &dyn Reflect),
+            reflect_mut: |world, entity| {
+                let component = world.get_mut::<T>(entity)?;
+                Some(component.map_unchanged(|value| value as &mut dyn Reflect))
+            },
+        });
+        self.by_name.insert(info.name, index);
+        self.by_type.insert(type_id, index);
+
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TypeRegistration> {
+        self.by_name
+            .get(name)
+            .map(|&index| &self.registrations[index])
+    }
+
+    pub fn get_by_type<T: 'static>(&self) -> Option<&TypeRegistration> {
+        self.by_type
+            .get(&TypeId::of::<T>())
+            .map(|&index| &self.registrations[index])
+    }
+
+    /// Like `get`, but with an error naming the type.
+    pub fn resolve(&self, name: &str) -> Result<&TypeRegistration, ReflectError> {
+        self.get(name)
+            .ok_or_else(|| ReflectError::UnknownType(name.to_owned()))
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.by_type.contains_key(&TypeId::of::<T>())
+    }
+
+    /// The reflected components `entity` has.
+    pub fn components<'a>(
+        &'a self,
+        world: &'a World,
+        entity: Entity,
+    ) -> impl Iterator<Item = (&'a TypeRegistration, &'a dyn Reflect)> + 'a {
+        self.registrations.iter().filter_map(move |registration| {
+            Some((registration, registration.reflect(world, entity)?))
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TypeRegistration> {
+        self.registrations.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+}
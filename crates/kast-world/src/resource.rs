@@ -0,0 +1,293 @@
+use std::any::{Any, TypeId, type_name};
+use std::cell::UnsafeCell;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::thread::ThreadId;
+
+use crate::{ChangeTicks, SystemMeta, SystemParam, World};
+
+/// Marker trait for singleton data stored in a `World` by type, such as
+/// frame timing or input state.
+///
+/// Like `Component`, every `Send + Sync + 'static` type is a resource. Data
+/// that can't cross threads (e.g. a graphics context) is stored with
+/// `World::insert_non_send_resource` instead.
+pub trait Resource: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Resource for T {}
+
+struct ResourceData {
+    value: UnsafeCell<Box<dyn Any>>,
+    /// The thread a non-send resource was inserted on; `None` for resources
+    /// that may be touched from any thread.
+    owner: Option<ThreadId>,
+}
+
+impl ResourceData {
+    fn check_thread<R>(&self) {
+        if let Some(owner) = self.owner
+            && owner != std::thread::current().id()
+        {
+            panic!(
+                "non-send resource `{}` accessed from a thread other than the one that inserted it",
+                type_name::<R>()
+            );
+        }
+    }
+}
+
+/// Type-keyed storage for a `World`'s resources.
+#[derive(Default)]
+pub(crate) struct Resources {
+    data: HashMap<TypeId, ResourceData>,
+}
+
+impl Resources {
+    pub fn insert<R: 'static>(&mut self, value: R, owner: Option<ThreadId>) -> Option<R> {
+        let previous = self.remove::<R>();
+        self.data.insert(
+            TypeId::of::<R>(),
+            ResourceData {
+                value: UnsafeCell::new(Box::new(value)),
+                owner,
+            },
+        );
+
+        previous
+    }
+
+    pub fn remove<R: 'static>(&mut self) -> Option<R> {
+        let data = self.data.get(&TypeId::of::<R>())?;
+        data.check_thread::<R>();
+
+        let data = self.data.remove(&TypeId::of::<R>())?;
+        data.value.into_inner().downcast().ok().map(|value| *value)
+    }
+
+    pub fn contains<R: 'static>(&self) -> bool {
+        self.data.contains_key(&TypeId::of::<R>())
+    }
+
+    pub fn get<R: 'static>(&self) -> Option<&R> {
+        let data = self.data.get(&TypeId::of::<R>())?;
+        data.check_thread::<R>();
+
+        // SAFETY: shared access only; writers either hold `&mut World` or a
+        // validated write access to this resource.
+        unsafe { &*data.value.get() }.downcast_ref()
+    }
+
+    pub fn get_mut<R: 'static>(&mut self) -> Option<&mut R> {
+        let data = self.data.get_mut(&TypeId::of::<R>())?;
+        data.check_thread::<R>();
+
+        data.value.get_mut().downcast_mut()
+    }
+
+    /// # Safety
+    ///
+    /// The caller must guarantee no other reference to this resource is alive
+    /// for the returned lifetime.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_mut_unchecked<R: 'static>(&self) -> Option<&mut R> {
+        let data = self.data.get(&TypeId::of::<R>())?;
+        data.check_thread::<R>();
+
+        unsafe { &mut *data.value.get() }.downcast_mut()
+    }
+}
+
+impl Drop for Resources {
+    fn drop(&mut self) {
+        // Dropping a non-send resource on the wrong thread could run its
+        // destructor somewhere it isn't allowed to; leaking is the lesser evil.
+        let current = std::thread::current().id();
+        for (_, data) in self.data.drain() {
+            if data.owner.is_some_and(|owner| owner != current) {
+                core::mem::forget(data);
+            }
+        }
+    }
+}
+
+/// Shared access to a resource from a system.
+///
+/// The system panics when it runs if the resource doesn't exist.
+pub struct Res<'w, R: Resource> {
+    value: &'w R,
+}
+
+impl<R: Resource> Deref for Res<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        self.value
+    }
+}
+
+/// Exclusive access to a resource from a system.
+///
+/// The system panics when it runs if the resource doesn't exist.
+pub struct ResMut<'w, R: Resource> {
+    value: &'w mut R,
+}
+
+impl<R: Resource> Deref for ResMut<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        self.value
+    }
+}
+
+impl<R: Resource> DerefMut for ResMut<'_, R> {
+    fn deref_mut(&mut self) -> &mut R {
+        self.value
+    }
+}
+
+/// Shared access to a non-send resource. Systems using it always run on the
+/// thread that steps the schedule.
+pub struct NonSend<'w, R: 'static> {
+    value: &'w R,
+}
+
+impl<R: 'static> Deref for NonSend<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        self.value
+    }
+}
+
+/// Exclusive access to a non-send resource. Systems using it always run on
+/// the thread that steps the schedule.
+pub struct NonSendMut<'w, R: 'static> {
+    value: &'w mut R,
+}
+
+impl<R: 'static> Deref for NonSendMut<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        self.value
+    }
+}
+
+impl<R: 'static> DerefMut for NonSendMut<'_, R> {
+    fn deref_mut(&mut self) -> &mut R {
+        self.value
+    }
+}
+
+fn add_resource_access<R: 'static>(meta: &mut SystemMeta, write: bool) {
+    let id = TypeId::of::<R>();
+    let access = meta.access();
+    let conflicts =
+        access.resource_writes().contains(&id) || (write && access.resource_reads().contains(&id));
+    if conflicts {
+        panic!(
+            "resource `{}` conflicts with another parameter of system `{}`",
+            type_name::<R>(),
+            meta.name()
+        );
+    }
+
+    if write {
+        meta.access_mut().add_resource_write(id);
+    } else {
+        meta.access_mut().add_resource_read(id);
+    }
+}
+
+fn missing_resource<R>() -> ! {
+    panic!(
+        "system requested resource `{}`, which doesn't exist in the world",
+        type_name::<R>()
+    )
+}
+
+unsafe impl<R: Resource> SystemParam for Res<'_, R> {
+    type State = ();
+    type Item<'w, 's> = Res<'w, R>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) -> Self::State {
+        add_resource_access::<R>(meta, false);
+    }
+
+    unsafe fn get_param<'w, 's>(
+        _state: &'s mut Self::State,
+        world: &'w World,
+        _ticks: ChangeTicks,
+    ) -> Self::Item<'w, 's> {
+        Res {
+            value: world
+                .resource::<R>()
+                .unwrap_or_else(|| missing_resource::<R>()),
+        }
+    }
+}
+
+unsafe impl<R: Resource> SystemParam for ResMut<'_, R> {
+    type State = ();
+    type Item<'w, 's> = ResMut<'w, R>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) -> Self::State {
+        add_resource_access::<R>(meta, true);
+    }
+
+    unsafe fn get_param<'w, 's>(
+        _state: &'s mut Self::State,
+        world: &'w World,
+        _ticks: ChangeTicks,
+    ) -> Self::Item<'w, 's> {
+        ResMut {
+            value: unsafe { world.resources.get_mut_unchecked::<R>() }
+                .unwrap_or_else(|| missing_resource::<R>()),
+        }
+    }
+}
+
+unsafe impl<R: 'static> SystemParam for NonSend<'_, R> {
+    type State = ();
+    type Item<'w, 's> = NonSend<'w, R>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) -> Self::State {
+        add_resource_access::<R>(meta, false);
+        meta.set_non_send();
+    }
+
+    unsafe fn get_param<'w, 's>(
+        _state: &'s mut Self::State,
+        world: &'w World,
+        _ticks: ChangeTicks,
+    ) -> Self::Item<'w, 's> {
+        NonSend {
+            value: world
+                .resources
+                .get::<R>()
+                .unwrap_or_else(|| missing_resource::<R>()),
+        }
+    }
+}
+
+unsafe impl<R: 'static> SystemParam for NonSendMut<'_, R> {
+    type State = ();
+    type Item<'w, 's> = NonSendMut<'w, R>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) -> Self::State {
+        add_resource_access::<R>(meta, true);
+        meta.set_non_send();
+    }
+
+    unsafe fn get_param<'w, 's>(
+        _state: &'s mut Self::State,
+        world: &'w World,
+        _ticks: ChangeTicks,
+    ) -> Self::Item<'w, 's> {
+        NonSendMut {
+            value: unsafe { world.resources.get_mut_unchecked::<R>() }
+                .unwrap_or_else(|| missing_resource::<R>()),
+        }
+    }
+}
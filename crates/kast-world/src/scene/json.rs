@@ -0,0 +1,510 @@
+use std::fmt::Write;
+
+use crate::scene::{SceneError, Value};
+
+/// How deeply arrays and objects can nest before parsing gives up, so a
+/// malicious document can't overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+impl Value {
+    /// Writes the value as indented JSON. Non-finite numbers become `null`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write_value(&mut out, self, 0);
+        out
+    }
+
+    /// Parses a JSON document.
+    pub fn from_json(text: &str) -> Result<Value, SceneError> {
+        let mut parser = Parser {
+            text,
+            position: 0,
+            depth: 0,
+        };
+
+        parser.skip_whitespace();
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position < text.len() {
+            return Err(parser.error("trailing characters after the document"));
+        }
+
+        Ok(value)
+    }
+}
+
+fn write_value(out: &mut String, value: &Value, depth: usize) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+        Value::Int(value) => {
+            let _ = write!(out, "{value}");
+        }
+        Value::UInt(value) => {
+            let _ = write!(out, "{value}");
+        }
+        // `Debug` keeps a `.0` on integral floats, so they read back as
+        // floats rather than integers.
+        Value::Number(value) if value.is_finite() => {
+            let _ = write!(out, "{value:?}");
+        }
+        Value::Number(_) => out.push_str("null"),
+        Value::String(value) => write_string(out, value),
+        Value::Array(items) => {
+            // Short arrays of scalars, such as vectors, stay on one line.
+            let inline = items.len() <= 4
+                && items
+                    .iter()
+                    .all(|item| !matches!(item, Value::Array(_) | Value::Object(_)));
+            if items.is_empty() || inline {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    write_value(out, item, depth);
+                }
+                out.push(']');
+                return;
+            }
+
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(if i == 0 { "\n" } else { ",\n" });
+                indent(out, depth + 1);
+                write_value(out, item, depth + 1);
+            }
+            out.push('\n');
+            indent(out, depth);
+            out.push(']');
+        }
+        Value::Object(fields) => {
+            if fields.is_empty() {
+                out.push_str("{}");
+                return;
+            }
+
+            out.push('{');
+            for (i, (name, value)) in fields.iter().enumerate() {
+                out.push_str(if i == 0 { "\n" } else { ",\n" });
+                indent(out, depth + 1);
+                write_string(out, name);
+                out.push_str(": ");
+                write_value(out, value, depth + 1);
+            }
+            out.push('\n');
+            indent(out, depth);
+            out.push('}');
+        }
+    }
+}
+
+fn indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    /// Arrays and objects currently open.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> SceneError {
+        let consumed = &self.text[..self.position];
+        let line = consumed.matches('\n').count() + 1;
+        let column = consumed.len() - consumed.rfind('\n').map_or(0, |i| i + 1) + 1;
+
+        SceneError::Parse {
+            line,
+            column,
+            message: message.to_owned(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), SceneError> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(&format!("expected `{}`", byte as char)));
+        }
+
+        self.position += 1;
+        Ok(())
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, SceneError> {
+        if !self.text[self.position..].starts_with(keyword) {
+            return Err(self.error("unexpected character"));
+        }
+
+        self.position += keyword.len();
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, SceneError> {
+        match self.peek() {
+            Some(b'n') => self.keyword("null", Value::Null),
+            Some(b't') => self.keyword("true", Value::Bool(true)),
+            Some(b'f') => self.keyword("false", Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'{') => self.nested(Self::object),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Value, SceneError>,
+    ) -> Result<Value, SceneError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn number(&mut self) -> Result<Value, SceneError> {
+        let start = self.position;
+        while matches!(
+            self.peek(),
+            Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+        ) {
+            self.position += 1;
+        }
+
+        let text = &self.text[start..self.position];
+        // Integers are parsed as such so they don't round through `f64`.
+        // `-0` stays a float to keep its sign.
+        if !text.contains(['.', 'e', 'E']) && text != "-0" {
+            if let Ok(value) = text.parse() {
+                return Ok(Value::UInt(value));
+            }
+            if let Ok(value) = text.parse() {
+                return Ok(Value::Int(value));
+            }
+        }
+
+        text.parse().map(Value::Number).map_err(|_| {
+            self.position = start;
+            self.error("invalid number")
+        })
+    }
+
+    fn string(&mut self) -> Result<String, SceneError> {
+        self.expect(b'"')?;
+
+        let mut value = String::new();
+        loop {
+            let Some(c) = self.text[self.position..].chars().next() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.position += c.len_utf8();
+
+            match c {
+                '"' => return Ok(value),
+                '\\' => value.push(self.escape()?),
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, SceneError> {
+        let Some(c) = self.peek() else {
+            return Err(self.error("unterminated string"));
+        };
+        self.position += 1;
+
+        Ok(match c {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let high = self.hex4()?;
+                let code = if (0xd800..0xdc00).contains(&high) {
+                    // A surrogate pair spells out a character outside the
+                    // basic multilingual plane.
+                    if !self.text[self.position..].starts_with("\\u") {
+                        return Err(self.error("unpaired surrogate"));
+                    }
+                    self.position += 2;
+                    let low = self.hex4()?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(self.error("unpaired surrogate"));
+                    }
+                    0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                } else {
+                    high
+                };
+
+                char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))?
+            }
+            _ => return Err(self.error("invalid escape")),
+        })
+    }
+
+    fn hex4(&mut self) -> Result<u32, SceneError> {
+        let digits = self
+            .text
+            .get(self.position..self.position + 4)
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        let code =
+            u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid unicode escape"))?;
+
+        self.position += 4;
+        Ok(code)
+    }
+
+    fn array(&mut self) -> Result<Value, SceneError> {
+        self.expect(b'[')?;
+        self.skip_whitespace();
+
+        let mut items = Vec::new();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            self.skip_whitespace();
+            items.push(self.value()?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, SceneError> {
+        self.expect(b'{')?;
+        self.skip_whitespace();
+
+        let mut fields = Vec::new();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Value::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let name = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.skip_whitespace();
+            fields.push((name, self.value()?));
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(fields));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MAX_DEPTH;
+    use crate::scene::{SceneError, Value};
+
+    fn parse_error(text: &str) -> (usize, usize, String) {
+        match Value::from_json(text) {
+            Err(SceneError::Parse {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            result => panic!("expected a parse error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn escapes_are_decoded() {
+        let value = Value::from_json(r#""a\"b\\c\/d\b\f\n\r\t\u00e9""#).unwrap();
+        assert_eq!(
+            value,
+            Value::String("a\"b\\c/d\u{8}\u{c}\n\r\té".to_owned())
+        );
+    }
+
+    #[test]
+    fn surrogate_pairs_are_decoded() {
+        let value = Value::from_json(r#""\ud83d\ude00""#).unwrap();
+        assert_eq!(value, Value::String("😀".to_owned()));
+
+        for text in [
+            r#""\ud83d""#,
+            r#""\ud83dx""#,
+            r#""\ud83d\u0041""#,
+            r#""\ude00""#,
+        ] {
+            let (_, _, message) = parse_error(text);
+            assert!(
+                message.contains("surrogate") || message.contains("unicode"),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn strings_round_trip() {
+        let text = "quote \" slash \\ newline \n tab \t bell \u{7} 😀";
+        let value = Value::String(text.to_owned());
+        assert_eq!(
+            value.to_json(),
+            r#""quote \" slash \\ newline \n tab \t bell \u0007 😀""#
+        );
+        assert_eq!(Value::from_json(&value.to_json()), Ok(value));
+    }
+
+    #[test]
+    fn numbers() {
+        let cases = [
+            ("0", Value::UInt(0)),
+            ("-7", Value::Int(-7)),
+            ("18446744073709551615", Value::UInt(u64::MAX)),
+            ("-9223372036854775808", Value::Int(i64::MIN)),
+            (
+                "18446744073709551616",
+                Value::Number(18446744073709551616.0),
+            ),
+            ("1.5", Value::Number(1.5)),
+            ("-2.5e-3", Value::Number(-2.5e-3)),
+            ("1E3", Value::Number(1000.0)),
+        ];
+        for (text, value) in cases {
+            assert_eq!(Value::from_json(text), Ok(value), "{text}");
+        }
+
+        for text in ["-", "1.2.3", "1e", "--1"] {
+            assert_eq!(parse_error(text).2, "invalid number", "{text}");
+        }
+    }
+
+    #[test]
+    fn floats_are_written_so_they_read_back_as_floats() {
+        let value = Value::Array(vec![
+            Value::Number(1.0),
+            Value::Number(-0.0),
+            Value::Number(0.1),
+            Value::Number(1e-7),
+        ]);
+        assert_eq!(value.to_json(), "[1.0, -0.0, 0.1, 1e-7]");
+
+        let value = Value::Array(vec![Value::Number(f64::NAN), Value::Number(f64::INFINITY)]);
+        assert_eq!(value.to_json(), "[null, null]");
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_character() {
+        assert_eq!(
+            parse_error("[1, 2"),
+            (1, 6, "expected `,` or `]`".to_owned())
+        );
+        assert_eq!(
+            parse_error("{\n  \"a\": 1,\n  \"b\" 2\n}"),
+            (3, 7, "expected `:`".to_owned())
+        );
+        assert_eq!(
+            parse_error("[\n  tru\n]"),
+            (2, 3, "unexpected character".to_owned())
+        );
+        assert_eq!(
+            parse_error("{} {}"),
+            (1, 4, "trailing characters after the document".to_owned())
+        );
+        assert_eq!(
+            parse_error("\"abc"),
+            (1, 5, "unterminated string".to_owned())
+        );
+    }
+
+    #[test]
+    fn documents_round_trip() {
+        let value = Value::Object(vec![
+            ("name".to_owned(), Value::String("thing".to_owned())),
+            ("empty".to_owned(), Value::Object(Vec::new())),
+            (
+                "items".to_owned(),
+                Value::Array(vec![
+                    Value::Null,
+                    Value::Bool(true),
+                    Value::Int(-1),
+                    Value::Number(0.25),
+                    Value::Number(2.0),
+                    Value::Number(-0.0),
+                    Value::Number(1e300),
+                    Value::Array(Vec::new()),
+                ]),
+            ),
+        ]);
+
+        assert_eq!(Value::from_json(&value.to_json()), Ok(value));
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(Value::from_json(&nested(MAX_DEPTH)).is_ok());
+
+        let error = Value::from_json(&nested(100_000)).unwrap_err();
+        assert_eq!(
+            error,
+            SceneError::Parse {
+                line: 1,
+                column: MAX_DEPTH + 1,
+                message: "nested too deeply".to_owned(),
+            }
+        );
+    }
+}
@@ -0,0 +1,543 @@
+mod json;
+mod value;
+
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+
+use kast_math::Transform;
+
+use crate::{Children, Component, Entity, World};
+
+pub use value::{SceneValue, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneError {
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The scene names a component that isn't in the `SceneRegistry`.
+    UnknownComponent(String),
+    InvalidValue(String),
+}
+
+impl core::fmt::Display for SceneError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl core::error::Error for SceneError {}
+
+/// A component that can be saved to and loaded from a `Scene`.
+///
+/// ```
+/// # use kast_world::{SceneComponent, SceneError, SceneValue, Value};
+/// struct Health(f32);
+///
+/// impl SceneValue for Health {
+///     fn to_value(&self) -> Value {
+///         self.0.to_value()
+///     }
+///
+///     fn from_value(value: &Value) -> Result<Self, SceneError> {
+///         f32::from_value(value).map(Health)
+///     }
+/// }
+///
+/// impl SceneComponent for Health {
+///     const NAME: &'static str = "Health";
+/// }
+/// # let value = Value::from_json("3.5").unwrap();
+/// # assert_eq!(Health::from_value(&value).unwrap().to_value(), value);
+/// ```
+pub trait SceneComponent: Component + SceneValue {
+    /// The key the component is stored under. Must be unique within a
+    /// registry and stable across versions, so saved files keep loading.
+    const NAME: &'static str;
+
+    /// Rewrites every `Entity` the component holds from its saved id to the
+    /// entity spawned for it. Components referencing entities must implement
+    /// this.
+    fn map_entities(&mut self, _entities: &EntityMap) {}
+}
+
+impl SceneComponent for Transform {
+    const NAME: &'static str = "Transform";
+}
+
+/// Maps the entity ids stored in a scene to the entities spawned for them.
+#[derive(Debug, Default)]
+pub struct EntityMap {
+    entities: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    pub fn get(&self, saved: Entity) -> Option<Entity> {
+        self.entities.get(&saved).copied()
+    }
+
+    /// Like `get`, but maps entities that weren't part of the scene to
+    /// `Entity::PLACEHOLDER`, so they can't alias whichever live entity has
+    /// the saved id.
+    pub fn map(&self, saved: Entity) -> Entity {
+        self.get(saved).unwrap_or(Entity::PLACEHOLDER)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+type InsertFn = Box<dyn FnOnce(&mut World, Entity, &EntityMap)>;
+
+struct Registration {
+    name: &'static str,
+    save: fn(&World, Entity) -> Option<Value>,
+    load: fn(&Value) -> Result<InsertFn, SceneError>,
+}
+
+/// The component types a `Scene` saves and loads. Components that aren't
+/// registered are skipped when saving.
+pub struct SceneRegistry {
+    registrations: Vec<Registration>,
+    by_name: HashMap<&'static str, usize>,
+    by_type: HashMap<TypeId, usize>,
+}
+
+impl Default for SceneRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SceneRegistry {
+    /// A registry with the built-in `Transform` already registered.
+    pub fn new() -> Self {
+        let mut registry = Self {
+            registrations: Vec::new(),
+            by_name: HashMap::new(),
+            by_type: HashMap::new(),
+        };
+        registry.register::<Transform>();
+
+        registry
+    }
+
+    /// # Panics
+    ///
+    /// Panics if another type is already registered under `T::NAME`.
+    pub fn register<T: SceneComponent>(&mut self) -> &mut Self {
+        let id = TypeId::of::<T>();
+        if self.by_type.contains_key(&id) {
+            return self;
+        }
+
+        if self.by_name.contains_key(T::NAME) {
+            panic!("scene component name `{}` is registered twice", T::NAME);
+        }
+
+        let index = self.registrations.len();
+        self.registrations.push(Registration {
+            name: T::NAME,
+            save: |world, entity| world.get::<T>(entity).map(T::to_value),
+            load: |value| {
+                let mut component = T::from_value(value).map_err(|error| match error {
+                    SceneError::InvalidValue(message) => {
+                        SceneError::InvalidValue(format!("`{}`: {message}", T::NAME))
+                    }
+                    error => error,
+                })?;
+
+                Ok(Box::new(move |world, entity, entities| {
+                    component.map_entities(entities);
+                    world.insert(entity, component);
+                }))
+            },
+        });
+        self.by_name.insert(T::NAME, index);
+        self.by_type.insert(id, index);
+
+        self
+    }
+
+    pub fn contains<T: SceneComponent>(&self) -> bool {
+        self.by_type.contains_key(&TypeId::of::<T>())
+    }
+}
+
+/// One saved entity: its id at save time, its children within the scene and
+/// its registered components.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneEntity {
+    pub id: Entity,
+    pub children: Vec<Entity>,
+    pub components: Vec<(String, Value)>,
+}
+
+/// A snapshot of entities that can be written to JSON and spawned back into
+/// a world.
+///
+/// ```json
+/// {
+///   "entities": [
+///     {
+///       "id": "0v0",
+///       "children": ["1v0"],
+///       "components": {
+///         "Transform": { "translation": [0.0, 1.0, 0.0], ... }
+///       }
+///     }
+///   ]
+/// }
+/// ```
+///
+/// The hierarchy is stored by the scene itself rather than through `Parent`
+/// and `Children`, so saving a subtree drops links to entities outside it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Scene {
+    pub entities: Vec<SceneEntity>,
+}
+
+impl Scene {
+    /// Saves every entity of `world`, ordered by id.
+    pub fn from_world(world: &World, registry: &SceneRegistry) -> Self {
+        let mut entities: Vec<_> = world.entities().collect();
+        entities.sort();
+
+        Self::from_entities(world, registry, entities)
+    }
+
+    /// Saves the given entities, skipping dead ones.
+    pub fn from_entities(
+        world: &World,
+        registry: &SceneRegistry,
+        entities: impl IntoIterator<Item = Entity>,
+    ) -> Self {
+        let ids: Vec<_> = entities
+            .into_iter()
+            .filter(|entity| world.contains(*entity))
+            .collect();
+        let saved: HashSet<_> = ids.iter().copied().collect();
+
+        let entities = ids
+            .iter()
+            .map(|&id| SceneEntity {
+                id,
+                children: world
+                    .get::<Children>(id)
+                    .map(|children| {
+                        children
+                            .iter()
+                            .copied()
+                            .filter(|child| saved.contains(child))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                components: registry
+                    .registrations
+                    .iter()
+                    .filter_map(|registration| {
+                        let value = (registration.save)(world, id)?;
+                        Some((registration.name.to_owned(), value))
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self { entities }
+    }
+
+    /// Spawns a copy of every entity in `world`, returning the mapping from
+    /// saved ids to the new entities.
+    ///
+    /// Every component is decoded and the hierarchy checked before anything
+    /// is spawned, so a scene that fails to load leaves the world untouched.
+    /// Children that aren't part of the scene are skipped.
+    pub fn spawn(
+        &self,
+        world: &mut World,
+        registry: &SceneRegistry,
+    ) -> Result<EntityMap, SceneError> {
+        self.validate()?;
+
+        let mut inserts = Vec::with_capacity(self.entities.len());
+        for entity in &self.entities {
+            for (name, value) in &entity.components {
+                let index = *registry
+                    .by_name
+                    .get(name.as_str())
+                    .ok_or_else(|| SceneError::UnknownComponent(name.clone()))?;
+                inserts.push((entity.id, (registry.registrations[index].load)(value)?));
+            }
+        }
+
+        let mut map = EntityMap::default();
+        for entity in &self.entities {
+            map.entities.insert(entity.id, world.spawn(()));
+        }
+
+        for (saved, insert) in inserts {
+            insert(world, map.map(saved), &map);
+        }
+
+        for entity in &self.entities {
+            let parent = map.map(entity.id);
+            for child in entity.children.iter().filter_map(|child| map.get(*child)) {
+                world.set_parent(child, parent);
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Rejects duplicate ids, and children that are their own ancestor or
+    /// have more than one parent.
+    fn validate(&self) -> Result<(), SceneError> {
+        let mut ids = HashSet::with_capacity(self.entities.len());
+        for entity in &self.entities {
+            if !ids.insert(entity.id) {
+                return Err(SceneError::InvalidValue(format!(
+                    "entity {} appears more than once",
+                    entity.id
+                )));
+            }
+        }
+
+        let mut parents = HashMap::new();
+        for entity in &self.entities {
+            for &child in entity.children.iter().filter(|child| ids.contains(child)) {
+                if parents.insert(child, entity.id).is_some() {
+                    return Err(SceneError::InvalidValue(format!(
+                        "entity {child} has more than one parent"
+                    )));
+                }
+            }
+        }
+
+        // With one parent each, a walk up the hierarchy that takes more steps
+        // than there are children has gone around a cycle.
+        for &start in parents.keys() {
+            let mut current = start;
+            let mut steps = 0;
+            while let Some(&parent) = parents.get(&current) {
+                steps += 1;
+                if parent == start || steps > parents.len() {
+                    return Err(SceneError::InvalidValue(format!(
+                        "entity {start} is its own ancestor"
+                    )));
+                }
+                current = parent;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_value(&self) -> Value {
+        let entities = self
+            .entities
+            .iter()
+            .map(|entity| {
+                let mut fields = vec![("id".to_owned(), entity.id.to_value())];
+                if !entity.children.is_empty() {
+                    fields.push(("children".to_owned(), entity.children.to_value()));
+                }
+                fields.push((
+                    "components".to_owned(),
+                    Value::Object(entity.components.clone()),
+                ));
+
+                Value::Object(fields)
+            })
+            .collect();
+
+        Value::Object(vec![("entities".to_owned(), Value::Array(entities))])
+    }
+
+    pub fn from_value(value: &Value) -> Result<Self, SceneError> {
+        let entities = value
+            .field("entities")?
+            .as_array()?
+            .iter()
+            .map(|entity| {
+                let components = match entity.field("components")? {
+                    Value::Object(fields) => fields.clone(),
+                    _ => {
+                        return Err(SceneError::InvalidValue(
+                            "`components` must be an object".to_owned(),
+                        ));
+                    }
+                };
+
+                Ok(SceneEntity {
+                    id: Entity::from_value(entity.field("id")?)?,
+                    children: entity
+                        .get("children")
+                        .map(Vec::from_value)
+                        .transpose()?
+                        .unwrap_or_default(),
+                    components,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { entities })
+    }
+
+    pub fn to_json(&self) -> String {
+        self.to_value().to_json()
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, SceneError> {
+        Self::from_value(&Value::from_json(text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kast_math::Vec3;
+
+    use super::*;
+    use crate::Parent;
+
+    /// Points at another entity, to check references are remapped.
+    #[derive(Debug, PartialEq)]
+    struct Target(Entity);
+
+    impl SceneValue for Target {
+        fn to_value(&self) -> Value {
+            self.0.to_value()
+        }
+
+        fn from_value(value: &Value) -> Result<Self, SceneError> {
+            Entity::from_value(value).map(Target)
+        }
+    }
+
+    impl SceneComponent for Target {
+        const NAME: &'static str = "Target";
+
+        fn map_entities(&mut self, entities: &EntityMap) {
+            self.0 = entities.map(self.0);
+        }
+    }
+
+    fn registry() -> SceneRegistry {
+        let mut registry = SceneRegistry::new();
+        registry.register::<Target>();
+        registry
+    }
+
+    fn entity(id: u32, children: &[u32]) -> SceneEntity {
+        SceneEntity {
+            id: Entity::new(id, 0),
+            children: children.iter().map(|&id| Entity::new(id, 0)).collect(),
+            components: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn scenes_round_trip_through_json_into_another_world() {
+        let registry = registry();
+        let mut world = World::new();
+        let root = world.spawn((Transform::from_translation(Vec3::new(1.0, 2.0, 3.0)),));
+        let child = world.spawn((Target(root),));
+        world.set_parent(child, root);
+        // Points outside the saved entities.
+        let outside = world.spawn(());
+        let stray = world.spawn((Target(outside),));
+
+        let scene = Scene::from_entities(&world, &registry, [root, child, stray]);
+        let loaded = Scene::from_json(&scene.to_json()).unwrap();
+        assert_eq!(loaded, scene);
+
+        let mut other = World::new();
+        // Taken ids, so the scene's entities can't keep theirs.
+        other.spawn(());
+        other.spawn(());
+        let map = loaded.spawn(&mut other, &registry).unwrap();
+        assert_eq!(map.len(), 3);
+
+        let (new_root, new_child) = (map.map(root), map.map(child));
+        assert_ne!(new_root, root);
+        assert_eq!(
+            other.get::<Transform>(new_root).unwrap().translation,
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(other.get::<Target>(new_child), Some(&Target(new_root)));
+        assert_eq!(other.get::<Parent>(new_child).unwrap().get(), new_root);
+        assert_eq!(
+            other.get::<Target>(map.map(stray)),
+            Some(&Target(Entity::PLACEHOLDER))
+        );
+    }
+
+    #[test]
+    fn unregistered_components_are_not_saved() {
+        let mut world = World::new();
+        let entity = world.spawn((Target(Entity::PLACEHOLDER),));
+
+        let scene = Scene::from_world(&world, &SceneRegistry::new());
+        assert_eq!(scene.entities.len(), 1);
+        assert_eq!(scene.entities[0].id, entity);
+        assert!(scene.entities[0].components.is_empty());
+    }
+
+    #[test]
+    fn invalid_scenes_leave_the_world_untouched() {
+        let registry = registry();
+        let mut bad_component = entity(0, &[]);
+        bad_component
+            .components
+            .push(("Target".to_owned(), Value::Bool(true)));
+        let mut unknown = entity(0, &[]);
+        unknown
+            .components
+            .push(("Health".to_owned(), Value::UInt(3)));
+
+        let cases = [
+            (vec![entity(0, &[]), entity(0, &[])], "more than once"),
+            (
+                vec![entity(0, &[2]), entity(1, &[2]), entity(2, &[])],
+                "more than one parent",
+            ),
+            (vec![entity(0, &[0])], "its own ancestor"),
+            (
+                vec![entity(0, &[1]), entity(1, &[2]), entity(2, &[0])],
+                "its own ancestor",
+            ),
+            // A lead-in to a cycle that doesn't include the entity itself.
+            (vec![entity(0, &[1]), entity(1, &[2]), entity(2, &[1])], ""),
+            (vec![bad_component], "`Target`: expected a string"),
+        ];
+
+        for (entities, expected) in cases {
+            let mut world = World::new();
+            let result = Scene { entities }.spawn(&mut world, &registry);
+            match result {
+                Err(SceneError::InvalidValue(message)) => {
+                    assert!(message.contains(expected), "{message}")
+                }
+                result => panic!("expected an error, got {result:?}"),
+            }
+            assert_eq!(world.entities().count(), 0);
+        }
+
+        let mut world = World::new();
+        let result = Scene {
+            entities: vec![unknown],
+        }
+        .spawn(&mut world, &registry);
+        assert_eq!(
+            result.unwrap_err(),
+            SceneError::UnknownComponent("Health".to_owned())
+        );
+        assert_eq!(world.entities().count(), 0);
+    }
+}
@@ -0,0 +1,353 @@
+use kast_math::{Color, IVec2, Mat4, Quat, Transform, UVec2, Vec2, Vec3, Vec4};
+
+use crate::{Entity, scene::SceneError};
+
+/// A loosely typed tree that scene data goes through on its way to and from
+/// text. Mirrors the JSON data model; object fields keep their order so saved
+/// files diff cleanly.
+///
+/// Integers are kept apart from other numbers so ids and large counts don't
+/// lose precision going through `f64`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    /// A negative integer.
+    Int(i64),
+    /// A non-negative integer.
+    UInt(u64),
+    /// Any other number, including integers too large for the above.
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Looks up a field of an object.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Looks up a field of an object, failing if it's missing.
+    pub fn field(&self, key: &str) -> Result<&Value, SceneError> {
+        self.get(key)
+            .ok_or_else(|| SceneError::InvalidValue(format!("missing field `{key}`")))
+    }
+
+    pub fn as_array(&self) -> Result<&[Value], SceneError> {
+        match self {
+            Value::Array(items) => Ok(items),
+            other => Err(unexpected("an array", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&str, SceneError> {
+        match self {
+            Value::String(value) => Ok(value),
+            other => Err(unexpected("a string", other)),
+        }
+    }
+
+    /// Reads a number; `null` reads as NaN, which is how non-finite numbers
+    /// are written.
+    pub fn as_f64(&self) -> Result<f64, SceneError> {
+        match self {
+            Value::Int(value) => Ok(*value as f64),
+            Value::UInt(value) => Ok(*value as f64),
+            Value::Number(value) => Ok(*value),
+            Value::Null => Ok(f64::NAN),
+            other => Err(unexpected("a number", other)),
+        }
+    }
+}
+
+fn unexpected(expected: &str, found: &Value) -> SceneError {
+    let found = match found {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Int(_) | Value::UInt(_) | Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    };
+
+    SceneError::InvalidValue(format!("expected {expected}, found {found}"))
+}
+
+/// Conversion between a type and a scene `Value`.
+///
+/// Implemented for primitives, `String`, `Option`, `Vec`, `Entity` and the
+/// `kast-math` types; structs usually implement it field by field.
+pub trait SceneValue: Sized {
+    fn to_value(&self) -> Value;
+    fn from_value(value: &Value) -> Result<Self, SceneError>;
+}
+
+impl SceneValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, SceneError> {
+        match value {
+            Value::Bool(value) => Ok(*value),
+            other => Err(unexpected("a boolean", other)),
+        }
+    }
+}
+
+impl SceneValue for f64 {
+    fn to_value(&self) -> Value {
+        Value::Number(*self)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, SceneError> {
+        value.as_f64()
+    }
+}
+
+impl SceneValue for f32 {
+    fn to_value(&self) -> Value {
+        // Going through the shortest `f32` representation keeps `0.1` from
+        // being written as `0.10000000149011612`.
+        Value::Number(self.to_string().parse().unwrap_or(f64::NAN))
+    }
+
+    fn from_value(value: &Value) -> Result<Self, SceneError> {
+        value.as_f64().map(|value| value as f32)
+    }
+}
+
+/// `UInt` when the integer isn't negative, so equal numbers compare equal
+/// whichever type wrote them.
+fn integer(value: i128) -> Value {
+    match u64::try_from(value) {
+        Ok(value) => Value::UInt(value),
+        Err(_) => Value::Int(value as i64),
+    }
+}
+
+/// The largest integer every smaller one of which `f64` holds exactly.
+const MAX_EXACT_INTEGER: f64 = (1u64 << f64::MANTISSA_DIGITS) as f64;
+
+macro_rules! impl_scene_value_int {
+    ($($ty:ty),*) => {
+        $(
+            impl SceneValue for $ty {
+                fn to_value(&self) -> Value {
+                    integer(*self as i128)
+                }
+
+                fn from_value(value: &Value) -> Result<Self, SceneError> {
+                    let invalid = |number: &dyn core::fmt::Display| {
+                        SceneError::InvalidValue(format!(
+                            "{number} is not a valid `{}`",
+                            stringify!($ty)
+                        ))
+                    };
+
+                    match *value {
+                        Value::Int(number) => <$ty>::try_from(number).map_err(|_| invalid(&number)),
+                        Value::UInt(number) => <$ty>::try_from(number).map_err(|_| invalid(&number)),
+                        // Integral floats such as `1e3` are fine as long as
+                        // `f64` holds them exactly; larger ones were rounded.
+                        Value::Number(number)
+                            if number.fract() == 0.0 && number.abs() <= MAX_EXACT_INTEGER =>
+                        {
+                            <$ty>::try_from(number as i64).map_err(|_| invalid(&number))
+                        }
+                        Value::Number(number) => Err(invalid(&number)),
+                        ref other => Err(unexpected("an integer", other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_scene_value_int!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl SceneValue for String {
+    fn to_value(&self) -> Value {
+        Value::String(self.clone())
+    }
+
+    fn from_value(value: &Value) -> Result<Self, SceneError> {
+        value.as_str().map(str::to_owned)
+    }
+}
+
+impl<T: SceneValue> SceneValue for Option<T> {
+    fn to_value(&self) -> Value {
+        self.as_ref().map_or(Value::Null, T::to_value)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, SceneError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+impl<T: SceneValue> SceneValue for Vec<T> {
+    fn to_value(&self) -> Value {
+        Value::Array(self.iter().map(T::to_value).collect())
+    }
+
+    fn from_value(value: &Value) -> Result<Self, SceneError> {
+        value.as_array()?.iter().map(T::from_value).collect()
+    }
+}
+
+/// Written like its `Display` form, e.g. `"3v1"`. References are remapped
+/// when a scene is loaded, see `SceneComponent::map_entities`.
+impl SceneValue for Entity {
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+
+    fn from_value(value: &Value) -> Result<Self, SceneError> {
+        let text = value.as_str()?;
+        text.split_once('v')
+            .and_then(|(index, generation)| {
+                Some(Entity::new(index.parse().ok()?, generation.parse().ok()?))
+            })
+            .ok_or_else(|| SceneError::InvalidValue(format!("`{text}` is not an entity")))
+    }
+}
+
+/// Writes `[x, y, ...]` arrays for the vector-like math types.
+macro_rules! impl_scene_value_array {
+    ($ty:ident, $element:ty, $constructor:path, $($field:ident),*) => {
+        impl SceneValue for $ty {
+            fn to_value(&self) -> Value {
+                Value::Array(vec![$(self.$field.to_value()),*])
+            }
+
+            fn from_value(value: &Value) -> Result<Self, SceneError> {
+                let [$($field),*] = value.as_array()? else {
+                    return Err(SceneError::InvalidValue(format!(
+                        "expected {} numbers for `{}`",
+                        [$(stringify!($field)),*].len(),
+                        stringify!($ty)
+                    )));
+                };
+
+                Ok($constructor($(<$element>::from_value($field)?),*))
+            }
+        }
+    };
+}
+
+impl_scene_value_array!(Vec2, f32, Vec2::new, x, y);
+impl_scene_value_array!(Vec3, f32, Vec3::new, x, y, z);
+impl_scene_value_array!(Vec4, f32, Vec4::new, x, y, z, w);
+impl_scene_value_array!(IVec2, i32, IVec2::new, x, y);
+impl_scene_value_array!(UVec2, u32, UVec2::new, x, y);
+impl_scene_value_array!(Quat, f32, Quat::from_xyzw, x, y, z, w);
+impl_scene_value_array!(Color, f32, Color::rgba, r, g, b, a);
+
+/// Written as its four columns.
+impl SceneValue for Mat4 {
+    fn to_value(&self) -> Value {
+        Value::Array(vec![
+            self.x_axis.to_value(),
+            self.y_axis.to_value(),
+            self.z_axis.to_value(),
+            self.w_axis.to_value(),
+        ])
+    }
+
+    fn from_value(value: &Value) -> Result<Self, SceneError> {
+        match value.as_array()? {
+            [x, y, z, w] => Ok(Mat4::from_cols(
+                Vec4::from_value(x)?,
+                Vec4::from_value(y)?,
+                Vec4::from_value(z)?,
+                Vec4::from_value(w)?,
+            )),
+            items => Err(SceneError::InvalidValue(format!(
+                "expected 4 columns for `Mat4`, found {}",
+                items.len()
+            ))),
+        }
+    }
+}
+
+impl SceneValue for Transform {
+    fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("translation".to_owned(), self.translation.to_value()),
+            ("rotation".to_owned(), self.rotation.to_value()),
+            ("scale".to_owned(), self.scale.to_value()),
+        ])
+    }
+
+    fn from_value(value: &Value) -> Result<Self, SceneError> {
+        Ok(Transform {
+            translation: Vec3::from_value(value.field("translation")?)?,
+            rotation: Quat::from_value(value.field("rotation")?)?,
+            scale: Vec3::from_value(value.field("scale")?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse<T: SceneValue>(json: &str) -> Result<T, SceneError> {
+        T::from_value(&Value::from_json(json)?)
+    }
+
+    #[test]
+    fn integers_round_trip_exactly() {
+        for value in [u64::MAX, (1 << 53) + 1, 0] {
+            let json = value.to_value().to_json();
+            assert_eq!(json, value.to_string());
+            assert_eq!(parse::<u64>(&json), Ok(value));
+        }
+        for value in [i64::MIN, i64::MAX, -1] {
+            assert_eq!(parse::<i64>(&value.to_value().to_json()), Ok(value));
+        }
+    }
+
+    #[test]
+    fn integers_compare_equal_whatever_wrote_them() {
+        assert_eq!(5i8.to_value(), 5u64.to_value());
+        assert_eq!(Value::from_json("5"), Ok(5i32.to_value()));
+    }
+
+    #[test]
+    fn out_of_range_integers_are_rejected() {
+        assert!(parse::<u64>("18446744073709551616").is_err());
+        assert!(parse::<i64>("-9223372036854775809").is_err());
+        assert!(parse::<u8>("256").is_err());
+        assert!(parse::<u32>("-1").is_err());
+        assert!(parse::<i8>("-129").is_err());
+    }
+
+    #[test]
+    fn integral_floats_read_as_integers_only_while_exact() {
+        assert_eq!(parse::<u32>("1e3"), Ok(1000));
+        assert_eq!(parse::<i32>("-2.0"), Ok(-2));
+        assert!(parse::<u32>("1.5").is_err());
+        assert!(parse::<u64>("1e19").is_err());
+        assert!(parse::<u32>("null").is_err());
+    }
+
+    #[test]
+    fn floats_read_integers() {
+        assert_eq!(parse::<f32>("3"), Ok(3.0));
+        assert_eq!(parse::<f64>("-3"), Ok(-3.0));
+        assert!(parse::<f64>("-0").unwrap().is_sign_negative());
+    }
+}
@@ -0,0 +1,637 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::mpsc;
+
+use kast_resources::TaskPool;
+
+use crate::{IntoSystem, System, World};
+
+/// A system together with its ordering constraints, ready to be added to a
+/// `Schedule`.
+pub struct SystemConfig {
+    system: Box<dyn System>,
+    labels: Vec<&'static str>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+}
+
+/// Attaches labels and explicit ordering to a system.
+///
+/// ```
+/// # use kast_world::{IntoSystemConfig, Schedule, World};
+/// # fn read_input() {}
+/// # fn movement() {}
+/// # let mut schedule = Schedule::new();
+/// schedule.add_system(read_input.label("input"));
+/// schedule.add_system(movement.after("input"));
+/// # schedule.run(&mut World::new());
+/// ```
+pub trait IntoSystemConfig<Marker>: Sized {
+    fn into_config(self) -> SystemConfig;
+
+    /// Names the system so other systems can order themselves around it.
+    /// Several systems may share a label.
+    fn label(self, label: &'static str) -> SystemConfig {
+        let mut config = self.into_config();
+        config.labels.push(label);
+        config
+    }
+
+    /// Runs this system before every system carrying `label`.
+    fn before(self, label: &'static str) -> SystemConfig {
+        let mut config = self.into_config();
+        config.before.push(label);
+        config
+    }
+
+    /// Runs this system after every system carrying `label`.
+    fn after(self, label: &'static str) -> SystemConfig {
+        let mut config = self.into_config();
+        config.after.push(label);
+        config
+    }
+}
+
+impl<Marker, S: IntoSystem<Marker>> IntoSystemConfig<Marker> for S {
+    fn into_config(self) -> SystemConfig {
+        SystemConfig {
+            system: Box::new(self.into_system()),
+            labels: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+}
+
+impl IntoSystemConfig<()> for SystemConfig {
+    fn into_config(self) -> SystemConfig {
+        self
+    }
+}
+
+/// Systems that run between two sync points, followed by the exclusive
+/// system that ends the segment, if any.
+struct Segment {
+    /// Topological order, used when running on a single thread.
+    systems: Vec<usize>,
+    exclusive: Option<usize>,
+}
+
+/// Execution order derived from the systems' access and explicit ordering.
+struct Graph {
+    segments: Vec<Segment>,
+    /// Edges between systems of the same segment; later segments always wait
+    /// for earlier ones.
+    dependents: Vec<Vec<usize>>,
+    dependency_counts: Vec<usize>,
+}
+
+/// An ordered collection of systems stepped once per frame.
+///
+/// Systems whose access doesn't conflict run in parallel on a `TaskPool`.
+/// Conflicting systems run one after the other: in the order required by
+/// `before`/`after` constraints, or else in the order they were added.
+/// Systems using non-send resources run on the thread calling `run`.
+///
+/// Deferred changes such as `Commands` are applied at sync points: before
+/// each exclusive system, and once every system has run.
+pub struct Schedule {
+    systems: Vec<SystemConfig>,
+    initialized: usize,
+    graph: Option<Graph>,
+    task_pool: Option<TaskPool>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+            initialized: 0,
+            graph: None,
+            task_pool: None,
+        }
+    }
+
+    /// Uses `task_pool` for parallel execution instead of one sized to the
+    /// machine's available parallelism.
+    pub fn with_task_pool(mut self, task_pool: TaskPool) -> Self {
+        self.task_pool = Some(task_pool);
+        self
+    }
+
+    pub fn add_system<Marker>(&mut self, system: impl IntoSystemConfig<Marker>) -> &mut Self {
+        self.systems.push(system.into_config());
+        self.graph = None;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Runs every system once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `before`/`after` constraints form a cycle or name a
+    /// label no system carries, or re-raises the panic of a system that
+    /// failed.
+    pub fn run(&mut self, world: &mut World) {
+        for config in &mut self.systems[self.initialized..] {
+            config.system.initialize(world);
+        }
+        self.initialized = self.systems.len();
+
+        if self.graph.is_none() {
+            self.graph = Some(build_graph(&self.systems));
+        }
+
+        let task_pool = self
+            .task_pool
+            .get_or_insert_with(TaskPool::with_available_parallelism);
+        let graph = self.graph.as_ref().unwrap();
+
+        for segment in &graph.segments {
+            if segment.systems.len() <= 1 || task_pool.thread_count() <= 1 {
+                for &index in &segment.systems {
+                    // SAFETY: `&mut World` rules out any other access.
+                    unsafe { self.systems[index].system.run_unchecked(world) };
+                }
+            } else {
+                run_parallel(&mut self.systems, segment, graph, task_pool, world);
+            }
+
+            for &index in &segment.systems {
+                self.systems[index].system.apply_deferred(world);
+            }
+
+            if let Some(index) = segment.exclusive {
+                self.systems[index].system.run(world);
+            }
+        }
+    }
+}
+
+fn run_parallel(
+    systems: &mut [SystemConfig],
+    segment: &Segment,
+    graph: &Graph,
+    task_pool: &TaskPool,
+    world: &mut World,
+) {
+    let world: &World = world;
+    let mut slots: Vec<Option<&mut Box<dyn System>>> = systems
+        .iter_mut()
+        .enumerate()
+        .map(|(index, config)| {
+            segment
+                .systems
+                .contains(&index)
+                .then_some(&mut config.system)
+        })
+        .collect();
+    let mut remaining = graph.dependency_counts.clone();
+    let mut ready: Vec<usize> = segment
+        .systems
+        .iter()
+        .copied()
+        .filter(|&i| remaining[i] == 0)
+        .collect();
+
+    let (sender, receiver) = mpsc::channel::<(usize, Result<(), &'static str>)>();
+    let failure = task_pool.scope(|scope| {
+        // Non-send systems can't be handed to a worker, so this thread runs
+        // them itself between waiting on the pool.
+        let mut local = Vec::new();
+        let mut finished = 0;
+
+        while finished < segment.systems.len() {
+            for index in ready.drain(..) {
+                let system = slots[index].take().expect("system dispatched twice");
+                if !system.is_send() {
+                    local.push((index, system));
+                    continue;
+                }
+
+                let sender = sender.clone();
+                scope.spawn(move || {
+                    let _ = sender.send((index, run_caught(system, world)));
+                });
+            }
+
+            let (index, result) = match local.pop() {
+                Some((index, system)) => (index, run_caught(system, world)),
+                None => receiver.recv().expect("system executor disconnected"),
+            };
+            if let Err(name) = result {
+                return Some(name);
+            }
+
+            finished += 1;
+            for &dependent in &graph.dependents[index] {
+                remaining[dependent] -= 1;
+                if remaining[dependent] == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+
+        None
+    });
+
+    if let Some(name) = failure {
+        panic!("system `{name}` panicked");
+    }
+}
+
+/// Runs a system, catching a panic so the executor can report which system
+/// failed.
+fn run_caught(system: &mut Box<dyn System>, world: &World) -> Result<(), &'static str> {
+    let name = system.name();
+    // SAFETY: the graph only makes two systems ready at the same time when
+    // their access doesn't conflict.
+    catch_unwind(AssertUnwindSafe(|| unsafe { system.run_unchecked(world) })).map_err(|_| name)
+}
+
+/// Orders systems so that explicit constraints are honoured and conflicting
+/// systems never overlap.
+///
+/// Explicit `before`/`after` edges are sorted first, breaking ties by
+/// insertion order. Every conflicting pair is then ordered along that
+/// sequence, which keeps the final graph acyclic. Exclusive systems conflict
+/// with everything, so they split the sequence into segments.
+fn build_graph(systems: &[SystemConfig]) -> Graph {
+    let count = systems.len();
+    let labeled = |label: &'static str| {
+        systems
+            .iter()
+            .enumerate()
+            .filter(move |(_, config)| config.labels.contains(&label))
+            .map(|(index, _)| index)
+    };
+
+    let mut explicit = vec![Vec::new(); count];
+    for (index, config) in systems.iter().enumerate() {
+        // A mistyped label would otherwise drop the ordering silently.
+        for &label in config.before.iter().chain(&config.after) {
+            if labeled(label).next().is_none() {
+                panic!(
+                    "system `{}` is ordered against `{label}`, which no system is labeled",
+                    config.system.name()
+                );
+            }
+        }
+        for label in &config.before {
+            explicit[index].extend(labeled(label).filter(|&other| other != index));
+        }
+        for label in &config.after {
+            for other in labeled(label).filter(|&other| other != index) {
+                explicit[other].push(index);
+            }
+        }
+    }
+
+    let order = topological_order(&explicit).unwrap_or_else(|cycle| {
+        let names: Vec<_> = cycle.iter().map(|&i| systems[i].system.name()).collect();
+        panic!(
+            "system ordering contains a cycle between: {}",
+            names.join(", ")
+        )
+    });
+
+    let mut position = vec![0; count];
+    for (rank, &index) in order.iter().enumerate() {
+        position[index] = rank;
+    }
+
+    let mut dependents = explicit;
+    for a in 0..count {
+        for b in a + 1..count {
+            let compatible = systems[a]
+                .system
+                .access()
+                .is_compatible(systems[b].system.access());
+            if compatible {
+                continue;
+            }
+
+            let (first, second) = if position[a] < position[b] {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            dependents[first].push(second);
+        }
+    }
+
+    let mut segments = vec![Segment {
+        systems: Vec::new(),
+        exclusive: None,
+    }];
+    // Exclusive systems belong to no segment, so no edge ever leads to them.
+    let mut segment_of = vec![None; count];
+    for &index in &order {
+        if systems[index].system.access().is_exclusive() {
+            segments.last_mut().unwrap().exclusive = Some(index);
+            segments.push(Segment {
+                systems: Vec::new(),
+                exclusive: None,
+            });
+        } else {
+            segment_of[index] = Some(segments.len() - 1);
+            segments.last_mut().unwrap().systems.push(index);
+        }
+    }
+
+    let mut dependency_counts = vec![0; count];
+    for (index, list) in dependents.iter_mut().enumerate() {
+        list.retain(|&dependent| {
+            segment_of[index].is_some() && segment_of[dependent] == segment_of[index]
+        });
+        list.sort_unstable();
+        list.dedup();
+        for &dependent in list.iter() {
+            dependency_counts[dependent] += 1;
+        }
+    }
+
+    Graph {
+        segments,
+        dependents,
+        dependency_counts,
+    }
+}
+
+/// Kahn's algorithm, always picking the lowest ready index. Returns the nodes
+/// left unsorted if the edges contain a cycle.
+fn topological_order(edges: &[Vec<usize>]) -> Result<Vec<usize>, Vec<usize>> {
+    let mut incoming = vec![0; edges.len()];
+    for targets in edges {
+        for &target in targets {
+            incoming[target] += 1;
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..edges.len())
+        .filter(|&i| incoming[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(edges.len());
+
+    while let Some(Reverse(index)) = ready.pop() {
+        order.push(index);
+        for &target in &edges[index] {
+            incoming[target] -= 1;
+            if incoming[target] == 0 {
+                ready.push(Reverse(target));
+            }
+        }
+    }
+
+    if order.len() == edges.len() {
+        Ok(order)
+    } else {
+        Err((0..edges.len()).filter(|&i| incoming[i] > 0).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::sync::Mutex;
+    use std::thread::{self, ThreadId};
+    use std::time::Duration;
+
+    use kast_resources::TaskPool;
+
+    use crate::{Commands, IntoSystemConfig, NonSend, Res, ResMut, World};
+
+    use super::Schedule;
+
+    /// Which systems ran, in order, and on which thread.
+    #[derive(Default)]
+    struct Log(Mutex<Vec<(&'static str, ThreadId)>>);
+
+    impl Log {
+        fn push(&self, name: &'static str) {
+            self.0.lock().unwrap().push((name, thread::current().id()));
+        }
+    }
+
+    struct A;
+    struct B;
+    struct Count(usize);
+    /// Only usable from the thread that created it.
+    struct Local;
+
+    fn write_a_1(log: Res<Log>, _: ResMut<A>) {
+        log.push("write_a_1");
+    }
+
+    fn write_a_2(log: Res<Log>, _: ResMut<A>) {
+        log.push("write_a_2");
+    }
+
+    fn read_a(log: Res<Log>, _: Res<A>) {
+        log.push("read_a");
+    }
+
+    fn write_b(log: Res<Log>, _: ResMut<B>) {
+        log.push("write_b");
+    }
+
+    fn non_send(log: Res<Log>, _: NonSend<Local>) {
+        log.push("non_send");
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        world.insert_resource(A);
+        world.insert_resource(B);
+        world.insert_non_send_resource(Local);
+        world
+    }
+
+    fn parallel() -> Schedule {
+        Schedule::new().with_task_pool(TaskPool::new(4))
+    }
+
+    fn order(world: &World) -> Vec<&'static str> {
+        let log = world.resource::<Log>().unwrap().0.lock().unwrap();
+        log.iter().map(|&(name, _)| name).collect()
+    }
+
+    /// Every edge within a segment, as system indices.
+    fn edges(schedule: &Schedule) -> Vec<(usize, usize)> {
+        let graph = schedule.graph.as_ref().unwrap();
+        let mut edges: Vec<_> = (0..graph.dependents.len())
+            .flat_map(|from| graph.dependents[from].iter().map(move |&to| (from, to)))
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    #[test]
+    fn conflicting_systems_run_in_the_order_they_were_added() {
+        let mut world = world();
+        let mut schedule = parallel();
+        schedule
+            .add_system(write_a_2)
+            .add_system(read_a)
+            .add_system(write_b)
+            .add_system(write_a_1);
+
+        for _ in 0..20 {
+            world.resource::<Log>().unwrap().0.lock().unwrap().clear();
+            schedule.run(&mut world);
+
+            let order = order(&world);
+            let position = |name| order.iter().position(|&other| other == name).unwrap();
+            assert!(position("write_a_2") < position("read_a"));
+            assert!(position("read_a") < position("write_a_1"));
+            assert_eq!(order.len(), 4);
+        }
+
+        // `write_b` conflicts with nothing, so it's free to run anywhere.
+        assert_eq!(edges(&schedule), [(0, 1), (0, 3), (1, 3)]);
+    }
+
+    #[test]
+    fn readers_and_unrelated_writers_are_not_ordered() {
+        let mut world = world();
+        let mut schedule = parallel();
+        schedule
+            .add_system(read_a)
+            .add_system(read_a)
+            .add_system(write_b);
+        schedule.run(&mut world);
+
+        assert!(edges(&schedule).is_empty());
+    }
+
+    #[test]
+    fn before_and_after_override_the_order_they_were_added() {
+        let mut world = world();
+        let mut schedule = parallel();
+        schedule
+            .add_system(write_a_1.after("second"))
+            .add_system(write_a_2.label("second").after("first"))
+            .add_system(write_b.label("first"))
+            .add_system(read_a.before("first"));
+        schedule.run(&mut world);
+
+        assert_eq!(
+            order(&world),
+            ["read_a", "write_b", "write_a_2", "write_a_1"]
+        );
+        assert_eq!(edges(&schedule), [(1, 0), (2, 1), (3, 0), (3, 1), (3, 2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ordered against `missing`, which no system is labeled")]
+    fn unknown_labels_panic() {
+        let mut world = world();
+        let mut schedule = Schedule::new();
+        schedule.add_system(write_a_1.after("missing"));
+        schedule.run(&mut world);
+    }
+
+    #[test]
+    #[should_panic(expected = "system ordering contains a cycle")]
+    fn ordering_cycles_panic() {
+        let mut world = world();
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(write_a_1.label("a").after("b"))
+            .add_system(write_b.label("b").after("a"));
+        schedule.run(&mut world);
+    }
+
+    #[test]
+    fn exclusive_systems_split_the_schedule_and_see_deferred_changes() {
+        fn spawn(mut commands: Commands) {
+            commands.spawn(());
+        }
+
+        fn count(world: &mut World) {
+            let count = world.len();
+            world.insert_resource(Count(count));
+        }
+
+        let mut world = World::new();
+        let mut schedule = parallel();
+        schedule
+            .add_system(spawn)
+            .add_system(spawn)
+            .add_system(count)
+            .add_system(spawn);
+        schedule.run(&mut world);
+
+        // The last spawn is applied once everything has run.
+        assert_eq!(world.resource::<Count>().unwrap().0, 2);
+        assert_eq!(world.len(), 3);
+
+        let graph = schedule.graph.as_ref().unwrap();
+        assert_eq!(graph.segments.len(), 2);
+        assert_eq!(graph.segments[0].systems, [0, 1]);
+        assert_eq!(graph.segments[0].exclusive, Some(2));
+        assert_eq!(graph.segments[1].systems, [3]);
+        assert_eq!(graph.segments[1].exclusive, None);
+    }
+
+    #[test]
+    fn non_send_systems_stay_on_the_calling_thread() {
+        let mut world = world();
+        let mut schedule = parallel();
+        schedule
+            .add_system(write_a_1)
+            .add_system(non_send)
+            .add_system(write_b);
+        schedule.run(&mut world);
+
+        let log = world.resource::<Log>().unwrap().0.lock().unwrap();
+        let caller = thread::current().id();
+        for &(name, thread) in log.iter() {
+            assert_eq!(thread == caller, name == "non_send", "{name}");
+        }
+        assert_eq!(log.len(), 3);
+    }
+
+    #[test]
+    fn panics_are_raised_once_running_systems_finish() {
+        #[derive(Default)]
+        struct Finished(Mutex<bool>);
+
+        fn panics(_: ResMut<A>) {
+            panic!("boom");
+        }
+
+        // Still borrowing the world when the other system panics.
+        fn slow(_: ResMut<B>, finished: Res<Finished>) {
+            thread::sleep(Duration::from_millis(50));
+            *finished.0.lock().unwrap() = true;
+        }
+
+        let mut world = world();
+        world.insert_resource(Finished::default());
+        let mut schedule = parallel();
+        schedule.add_system(slow).add_system(panics);
+
+        let payload = catch_unwind(AssertUnwindSafe(|| schedule.run(&mut world))).unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.contains("panics` panicked"), "{message}");
+        assert!(*world.resource::<Finished>().unwrap().0.lock().unwrap());
+    }
+}
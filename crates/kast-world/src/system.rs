@@ -0,0 +1,388 @@
+use std::any::type_name;
+use std::marker::PhantomData;
+
+use crate::{Access, ChangeTicks, Query, QueryFilter, World, WorldQuery};
+
+/// Information about a system gathered from its parameters.
+pub struct SystemMeta {
+    name: &'static str,
+    access: Access,
+    is_send: bool,
+    last_run: u64,
+}
+
+impl SystemMeta {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            access: Access::default(),
+            is_send: true,
+            last_run: 0,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn access(&self) -> &Access {
+        &self.access
+    }
+
+    pub fn access_mut(&mut self) -> &mut Access {
+        &mut self.access
+    }
+
+    /// Whether the system may run on a worker thread.
+    pub fn is_send(&self) -> bool {
+        self.is_send
+    }
+
+    /// The world change tick of the system's previous run, or 0 if it never
+    /// ran.
+    pub fn last_run(&self) -> u64 {
+        self.last_run
+    }
+
+    /// Pins the system to the thread stepping the schedule, for parameters
+    /// like `NonSend` that can't cross threads.
+    pub fn set_non_send(&mut self) {
+        self.is_send = false;
+    }
+}
+
+/// A value a system can request as a function parameter, such as a `Query`.
+///
+/// Each parameter declares the data it touches in `init_state`; the scheduler
+/// uses the combined access of all parameters to decide which systems may run
+/// at the same time.
+///
+/// # Safety
+///
+/// `init_state` must add every component and resource `get_param` reads or
+/// writes to the system's access, and mark the system non-send if the
+/// parameter can't be used off the main thread.
+pub unsafe trait SystemParam {
+    /// Data kept between runs of the system, built once by `init_state`.
+    type State: Send + 'static;
+    type Item<'w, 's>;
+
+    /// # Panics
+    ///
+    /// Implementations panic if the parameter conflicts with access already
+    /// declared by the same system.
+    fn init_state(world: &mut World, meta: &mut SystemMeta) -> Self::State;
+
+    /// `ticks` spans the previous and current run of the system, for change
+    /// detection.
+    ///
+    /// # Safety
+    ///
+    /// The caller must hold the access declared in `init_state` for `'w`.
+    unsafe fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        world: &'w World,
+        ticks: ChangeTicks,
+    ) -> Self::Item<'w, 's>;
+
+    /// Applies work the parameter deferred while the system ran, such as
+    /// queued `Commands`. Called by the schedule at the next sync point.
+    fn apply(_state: &mut Self::State, _world: &mut World) {}
+}
+
+/// Shorthand for the item type a parameter resolves to.
+pub type SystemParamItem<'w, 's, P> = <P as SystemParam>::Item<'w, 's>;
+
+unsafe impl<Q: WorldQuery + 'static, F: QueryFilter + 'static> SystemParam for Query<'_, Q, F> {
+    type State = ();
+    type Item<'w, 's> = Query<'w, Q, F>;
+
+    fn init_state(_world: &mut World, meta: &mut SystemMeta) -> Self::State {
+        let query_access = Query::<Q, F>::validated_access();
+        if !meta.access().is_compatible(&query_access) {
+            panic!(
+                "`Query<{}, {}>` conflicts with another parameter of system `{}`",
+                type_name::<Q>(),
+                type_name::<F>(),
+                meta.name()
+            );
+        }
+
+        meta.access_mut().extend(&query_access);
+    }
+
+    unsafe fn get_param<'w, 's>(
+        _state: &'s mut Self::State,
+        world: &'w World,
+        ticks: ChangeTicks,
+    ) -> Self::Item<'w, 's> {
+        Query::new_unchecked(world, ticks)
+    }
+}
+
+macro_rules! impl_system_param {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case, unused_variables, clippy::unused_unit)]
+        unsafe impl<$($name: SystemParam),*> SystemParam for ($($name,)*) {
+            type State = ($($name::State,)*);
+            type Item<'w, 's> = ($($name::Item<'w, 's>,)*);
+
+            fn init_state(world: &mut World, meta: &mut SystemMeta) -> Self::State {
+                ($($name::init_state(world, meta),)*)
+            }
+
+            unsafe fn get_param<'w, 's>(
+                state: &'s mut Self::State,
+                world: &'w World,
+                ticks: ChangeTicks,
+            ) -> Self::Item<'w, 's> {
+                let ($($name,)*) = state;
+                ($(unsafe { $name::get_param($name, world, ticks) },)*)
+            }
+
+            fn apply(state: &mut Self::State, world: &mut World) {
+                let ($($name,)*) = state;
+                $($name::apply($name, world);)*
+            }
+        }
+    };
+}
+
+impl_system_param!();
+impl_system_param!(A);
+impl_system_param!(A, B);
+impl_system_param!(A, B, C);
+impl_system_param!(A, B, C, D);
+impl_system_param!(A, B, C, D, E);
+impl_system_param!(A, B, C, D, E, F);
+impl_system_param!(A, B, C, D, E, F, G);
+impl_system_param!(A, B, C, D, E, F, G, H);
+
+/// A unit of game logic that the `Schedule` can run against a `World`.
+///
+/// Usually built from a plain function through `IntoSystem`, e.g.
+/// `fn movement(query: Query<(&mut Transform, &Velocity)>)`.
+pub trait System: Send + 'static {
+    fn name(&self) -> &'static str;
+
+    /// Builds parameter state and the system's access. Called once before the
+    /// first run.
+    fn initialize(&mut self, world: &mut World);
+
+    /// The data this system reads and writes. Only valid once initialized.
+    fn access(&self) -> &Access;
+
+    /// Whether the system may run on a worker thread. Only valid once
+    /// initialized.
+    fn is_send(&self) -> bool;
+
+    /// # Safety
+    ///
+    /// The caller must guarantee nothing else accesses the data declared by
+    /// `access` while the system runs.
+    unsafe fn run_unchecked(&mut self, world: &World);
+
+    /// Applies structural changes deferred by the last run, e.g. `Commands`.
+    fn apply_deferred(&mut self, _world: &mut World) {}
+
+    /// Runs the system and immediately applies its deferred changes.
+    fn run(&mut self, world: &mut World) {
+        // SAFETY: `&mut World` rules out any other access.
+        unsafe { self.run_unchecked(world) };
+        self.apply_deferred(world);
+    }
+}
+
+/// Functions whose parameters are all `SystemParam`s.
+///
+/// `Marker` is the function pointer type matching the signature; it only
+/// exists to keep the blanket implementations for each arity apart.
+pub trait SystemParamFunction<Marker>: Send + 'static {
+    type Param: SystemParam;
+
+    fn run(&mut self, param: SystemParamItem<'_, '_, Self::Param>);
+}
+
+macro_rules! impl_system_param_function {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<Func, $($name: SystemParam),*> SystemParamFunction<fn($($name,)*)> for Func
+        where
+            Func: Send + 'static,
+            for<'a> &'a mut Func: FnMut($($name),*) + FnMut($(SystemParamItem<$name>),*),
+        {
+            type Param = ($($name,)*);
+
+            fn run(&mut self, param: SystemParamItem<'_, '_, Self::Param>) {
+                // Calling through a generic helper lets the compiler pick the
+                // `FnMut(SystemParamItem<..>)` signature instead of `FnMut(P)`.
+                #[allow(clippy::too_many_arguments)]
+                fn call_inner<$($name),*>(mut f: impl FnMut($($name),*), $($name: $name),*) {
+                    f($($name),*)
+                }
+
+                let ($($name,)*) = param;
+                call_inner(self, $($name),*)
+            }
+        }
+    };
+}
+
+impl_system_param_function!();
+impl_system_param_function!(A);
+impl_system_param_function!(A, B);
+impl_system_param_function!(A, B, C);
+impl_system_param_function!(A, B, C, D);
+impl_system_param_function!(A, B, C, D, E);
+impl_system_param_function!(A, B, C, D, E, F);
+impl_system_param_function!(A, B, C, D, E, F, G);
+impl_system_param_function!(A, B, C, D, E, F, G, H);
+
+/// A `System` that calls a function with its parameters fetched from the
+/// world.
+pub struct FunctionSystem<Marker, F: SystemParamFunction<Marker>> {
+    func: F,
+    state: Option<<F::Param as SystemParam>::State>,
+    meta: SystemMeta,
+    _marker: PhantomData<fn() -> Marker>,
+}
+
+impl<Marker: 'static, F: SystemParamFunction<Marker>> System for FunctionSystem<Marker, F> {
+    fn name(&self) -> &'static str {
+        type_name::<F>()
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        if self.state.is_none() {
+            self.state = Some(F::Param::init_state(world, &mut self.meta));
+        }
+    }
+
+    fn access(&self) -> &Access {
+        self.meta.access()
+    }
+
+    fn is_send(&self) -> bool {
+        self.meta.is_send()
+    }
+
+    unsafe fn run_unchecked(&mut self, world: &World) {
+        let state = self
+            .state
+            .as_mut()
+            .unwrap_or_else(|| panic!("system `{}` ran before initialize", type_name::<F>()));
+
+        let this_run = world.increment_change_tick();
+        let ticks = ChangeTicks {
+            last_run: self.meta.last_run,
+            this_run,
+        };
+
+        let param = unsafe { F::Param::get_param(state, world, ticks) };
+        self.func.run(param);
+        self.meta.last_run = this_run;
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        if let Some(state) = &mut self.state {
+            F::Param::apply(state, world);
+        }
+    }
+}
+
+/// A `System` that takes `&mut World` directly.
+///
+/// Exclusive systems can make structural changes on the spot, so the
+/// schedule runs them alone: every system ordered before one has finished and
+/// had its deferred changes applied, making it a sync point.
+pub struct ExclusiveFunctionSystem<F> {
+    func: F,
+    meta: SystemMeta,
+}
+
+impl<F: FnMut(&mut World) + Send + 'static> System for ExclusiveFunctionSystem<F> {
+    fn name(&self) -> &'static str {
+        self.meta.name()
+    }
+
+    fn initialize(&mut self, _world: &mut World) {}
+
+    fn access(&self) -> &Access {
+        self.meta.access()
+    }
+
+    fn is_send(&self) -> bool {
+        self.meta.is_send()
+    }
+
+    unsafe fn run_unchecked(&mut self, _world: &World) {
+        panic!(
+            "exclusive system `{}` can't run with shared world access",
+            self.meta.name()
+        );
+    }
+
+    fn run(&mut self, world: &mut World) {
+        (self.func)(world);
+    }
+}
+
+/// An exclusive system that does nothing, to force a sync point where
+/// pending `Commands` get applied.
+///
+/// ```
+/// # use kast_world::{Commands, IntoSystemConfig, Query, Schedule, World, apply_deferred};
+/// # struct Enemy;
+/// # fn spawn_enemies(mut commands: Commands) {
+/// #     commands.spawn((Enemy,));
+/// # }
+/// # fn target_enemies(enemies: Query<&Enemy>) {
+/// #     assert_eq!(enemies.into_iter().count(), 1);
+/// # }
+/// # let mut schedule = Schedule::new();
+/// schedule.add_system(spawn_enemies.label("spawn"));
+/// schedule.add_system(apply_deferred.after("spawn").label("sync"));
+/// schedule.add_system(target_enemies.after("sync"));
+/// # schedule.run(&mut World::new());
+/// ```
+pub fn apply_deferred(_world: &mut World) {}
+
+#[doc(hidden)]
+pub struct IsFunctionSystem;
+
+#[doc(hidden)]
+pub struct IsExclusiveFunctionSystem;
+
+/// Conversion into a boxed-up `System`, implemented for every function whose
+/// parameters are `SystemParam`s and for every `FnMut(&mut World)`.
+pub trait IntoSystem<Marker> {
+    type System: System;
+
+    fn into_system(self) -> Self::System;
+}
+
+impl<Marker: 'static, F: SystemParamFunction<Marker>> IntoSystem<(IsFunctionSystem, Marker)> for F {
+    type System = FunctionSystem<Marker, F>;
+
+    fn into_system(self) -> Self::System {
+        FunctionSystem {
+            func: self,
+            state: None,
+            meta: SystemMeta::new(type_name::<F>()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FnMut(&mut World) + Send + 'static> IntoSystem<IsExclusiveFunctionSystem> for F {
+    type System = ExclusiveFunctionSystem<F>;
+
+    fn into_system(self) -> Self::System {
+        let mut meta = SystemMeta::new(type_name::<F>());
+        meta.access_mut().set_exclusive();
+        // Exclusive systems already run on the thread stepping the schedule.
+        meta.set_non_send();
+
+        ExclusiveFunctionSystem { func: self, meta }
+    }
+}
@@ -0,0 +1,661 @@
+use std::any::{TypeId, type_name};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{
+    Bundle, ChangeTicks, Component, Entity, Mut, Query, QueryFilter, WorldQuery,
+    archetype::{Archetype, Archetypes, EntityLocation},
+    component::Components,
+    hooks::{HookKind, Hooks},
+    manager::EntityManager,
+    resource::{Resource, Resources},
+};
+
+/// The container for every entity and component in a scene.
+///
+/// Entities are grouped into archetypes by their exact set of component
+/// types, and each archetype stores its components in contiguous columns (see
+/// `Archetype`). Queries walk the matching archetypes column by column.
+///
+/// The world also holds resources: singletons keyed by type, such as frame
+/// timing or the renderer.
+///
+/// Every component carries the change tick it was added and last mutably
+/// borrowed at. Systems advance the tick each time they run, which is what
+/// `Added<T>`/`Changed<T>` filters compare against.
+///
+/// Hooks registered with `on_add`, `on_replace`, `on_remove` and
+/// `on_despawn` run whenever those changes happen, however they're made.
+pub struct World {
+    pub(crate) entities: EntityManager,
+    pub(crate) archetypes: Archetypes,
+    pub(crate) resources: Resources,
+    pub(crate) components: Components,
+    pub(crate) hooks: Hooks,
+    /// Sorted component types of every bundle spawned so far.
+    bundles: HashMap<TypeId, Box<[TypeId]>>,
+    change_tick: AtomicU64,
+    last_change_tick: u64,
+    /// Entities that lost a component, with the tick they lost it at.
+    removed: HashMap<TypeId, Vec<(Entity, u64)>>,
+}
+
+// SAFETY: the only mutation reachable through `&World` is via queries and
+// system parameters, whose declared `Access` the scheduler checks before
+// running them concurrently. Components and resources are required to be
+// `Send + Sync`; non-send resources check the accessing thread at runtime.
+unsafe impl Send for World {}
+unsafe impl Sync for World {}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        let components = Components::default();
+
+        Self {
+            entities: EntityManager::default(),
+            archetypes: Archetypes::new(&components),
+            resources: Resources::default(),
+            components,
+            hooks: Hooks::default(),
+            bundles: HashMap::new(),
+            change_tick: AtomicU64::new(1),
+            last_change_tick: 0,
+            removed: HashMap::new(),
+        }
+    }
+
+    /// Spawns a new entity with the given components.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bundle contains the same component type twice.
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        self.flush();
+
+        let archetype_index = self.bundle_archetype::<B>(Archetypes::EMPTY);
+        let tick = self.change_tick();
+        let archetype = self.archetypes.get_mut(archetype_index);
+
+        bundle.push_into(archetype, tick);
+        let row = archetype.len();
+        let entity = self.entities.spawn(EntityLocation {
+            archetype: archetype_index,
+            row,
+        });
+        archetype.push_entity(entity);
+
+        self.trigger_bundle_add::<B>(entity);
+        entity
+    }
+
+    /// Reserves an entity id without needing `&mut World`.
+    ///
+    /// The entity stays invisible until the next `flush` (which every
+    /// structural change, and every applied `CommandQueue`, performs first),
+    /// after which it exists with no components.
+    pub fn reserve_entity(&self) -> Entity {
+        self.entities.reserve()
+    }
+
+    /// Turns every reserved entity into a live, component-less entity.
+    pub fn flush(&mut self) {
+        if !self.entities.has_pending() {
+            return;
+        }
+
+        for entity in self.entities.take_pending() {
+            let row = self
+                .archetypes
+                .get_mut(Archetypes::EMPTY)
+                .push_entity(entity);
+            self.entities.set_location(
+                entity,
+                EntityLocation {
+                    archetype: Archetypes::EMPTY,
+                    row,
+                },
+            );
+        }
+    }
+
+    /// Despawns `entity` and drops all of its components, after running the
+    /// `on_despawn` hooks and the `on_remove` hooks of its components.
+    ///
+    /// Returns `false` if the entity was already dead.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        self.flush();
+
+        let Some(location) = self.entities.location(entity) else {
+            return false;
+        };
+
+        if self.hooks.has_despawn() {
+            self.trigger_despawn(entity);
+        }
+        let removed: Vec<_> = self
+            .archetypes
+            .get(location.archetype)
+            .types()
+            .iter()
+            .copied()
+            .filter(|id| self.hooks.has(HookKind::Remove, *id))
+            .collect();
+        for id in removed {
+            self.trigger(HookKind::Remove, id, entity);
+        }
+
+        // Hooks may have moved the entity, or despawned it themselves.
+        let Some(location) = self.entities.despawn(entity) else {
+            return true;
+        };
+
+        let tick = self.change_tick();
+        for id in self.archetypes.get(location.archetype).types() {
+            self.removed.entry(*id).or_default().push((entity, tick));
+        }
+
+        let moved = self
+            .archetypes
+            .get_mut(location.archetype)
+            .swap_remove(location.row);
+        if let Some(moved) = moved {
+            self.entities.set_location(moved, location);
+        }
+
+        true
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(entity)
+    }
+
+    /// Every living entity, grouped by archetype.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.archetypes
+            .iter()
+            .flat_map(|archetype| archetype.entities().iter().copied())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Attaches `component` to `entity`, replacing any existing value.
+    ///
+    /// Adding a new component type moves the entity into the archetype that
+    /// includes it. Returns `false` if the entity doesn't exist.
+    pub fn insert<T: Component>(&mut self, entity: Entity, component: T) -> bool {
+        self.flush();
+
+        let Some(mut location) = self.entities.location(entity) else {
+            return false;
+        };
+
+        let id = self.components.register::<T>();
+        if self.hooks.has(HookKind::Replace, id) && self.archetypes.get(location.archetype).has(id)
+        {
+            self.trigger(HookKind::Replace, id, entity);
+            let Some(current) = self.entities.location(entity) else {
+                return false;
+            };
+            location = current;
+        }
+
+        let tick = self.change_tick();
+        if let Some(column) = self.archetypes.get_mut(location.archetype).column_mut(id) {
+            column.replace(location.row, component, tick);
+            return true;
+        }
+
+        let target = self
+            .archetypes
+            .with_component(location.archetype, id, &self.components);
+        let (source, destination) = self.archetypes.pair_mut(location.archetype, target);
+
+        destination.push_component(component, tick);
+        let (row, moved) = source.move_row(location.row, destination, None);
+
+        self.relocate(entity, moved, location, target, row);
+        self.trigger(HookKind::Add, id, entity);
+        true
+    }
+
+    /// Attaches every component of `bundle` to `entity`, replacing existing
+    /// values. Returns `false` if the entity doesn't exist.
+    ///
+    /// When the entity has none of the bundle's types this is a single move
+    /// to the target archetype.
+    pub fn insert_bundle<B: Bundle>(&mut self, entity: Entity, bundle: B) -> bool {
+        self.flush();
+
+        let Some(location) = self.entities.location(entity) else {
+            return false;
+        };
+
+        let target = self.bundle_archetype::<B>(location.archetype);
+        let overlaps = self.bundles[&TypeId::of::<B>()]
+            .iter()
+            .any(|id| self.archetypes.get(location.archetype).has(*id));
+        if overlaps {
+            bundle.insert_each(self, entity);
+            return true;
+        }
+
+        if target == location.archetype {
+            return true;
+        }
+
+        let tick = self.change_tick();
+        let (source, destination) = self.archetypes.pair_mut(location.archetype, target);
+        bundle.push_into(destination, tick);
+        let (row, moved) = source.move_row(location.row, destination, None);
+
+        self.relocate(entity, moved, location, target, row);
+        self.trigger_bundle_add::<B>(entity);
+        true
+    }
+
+    /// Detaches and returns the `T` component of `entity`, if any, after
+    /// running its `on_remove` hooks.
+    ///
+    /// The entity moves into the archetype without `T`.
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        self.flush();
+
+        let mut location = self.entities.location(entity)?;
+        let id = TypeId::of::<T>();
+        if !self.archetypes.get(location.archetype).has(id) {
+            return None;
+        }
+
+        if self.hooks.has(HookKind::Remove, id) {
+            self.trigger(HookKind::Remove, id, entity);
+            location = self.entities.location(entity)?;
+            if !self.archetypes.get(location.archetype).has(id) {
+                return None;
+            }
+        }
+
+        let target = self
+            .archetypes
+            .without_component(location.archetype, id, &self.components);
+        let (source, destination) = self.archetypes.pair_mut(location.archetype, target);
+
+        let component = source.column_mut(id)?.swap_remove_typed::<T>(location.row);
+        let (row, moved) = source.move_row(location.row, destination, Some(id));
+
+        self.relocate(entity, moved, location, target, row);
+        let tick = self.change_tick();
+        self.removed.entry(id).or_default().push((entity, tick));
+        Some(component)
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        let location = self.entities.location(entity)?;
+        self.archetypes
+            .get(location.archetype)
+            .column(TypeId::of::<T>())?
+            .typed::<T>()
+            .data
+            .get(location.row)
+    }
+
+    /// Mutably borrows the `T` component of `entity`. Writing through the
+    /// returned `Mut` marks the component as changed.
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<Mut<'_, T>> {
+        let location = self.entities.location(entity)?;
+        let ticks = self.change_ticks();
+        self.archetypes
+            .get_mut(location.archetype)
+            .column_mut(TypeId::of::<T>())?
+            .get_mut(location.row, ticks)
+    }
+
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        self.entities.location(entity).is_some_and(|location| {
+            self.archetypes
+                .get(location.archetype)
+                .has(TypeId::of::<T>())
+        })
+    }
+
+    /// Queries every entity matching `Q`.
+    ///
+    /// ```
+    /// # use kast_math::{Transform, Vec3};
+    /// # use kast_world::World;
+    /// # struct Velocity(Vec3);
+    /// # let mut world = World::new();
+    /// # let entity = world.spawn((Transform::IDENTITY, Velocity(Vec3::X)));
+    /// for (mut transform, velocity) in world.query::<(&mut Transform, &Velocity)>() {
+    ///     transform.translation = transform.translation + velocity.0;
+    /// }
+    /// # assert_eq!(world.get::<Transform>(entity).unwrap().translation, Vec3::X);
+    /// ```
+    pub fn query<Q: WorldQuery>(&mut self) -> Query<'_, Q> {
+        self.query_filtered()
+    }
+
+    /// Queries every entity matching `Q` and passing `F`. Change filters
+    /// compare against the last `clear_trackers`.
+    pub fn query_filtered<Q: WorldQuery, F: QueryFilter>(&mut self) -> Query<'_, Q, F> {
+        let ticks = self.change_ticks();
+        Query::new(self, ticks)
+    }
+
+    /// The current change tick, stamped on changes made directly through the
+    /// world.
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick.load(Ordering::Relaxed)
+    }
+
+    /// Advances the change tick, returning the tick to stamp the caller's
+    /// changes with. Called by systems each time they run.
+    pub fn increment_change_tick(&self) -> u64 {
+        self.change_tick.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// The tick of the last `clear_trackers`.
+    pub fn last_change_tick(&self) -> u64 {
+        self.last_change_tick
+    }
+
+    /// Ends a frame of change tracking: direct queries start comparing against
+    /// the current tick, and removals older than the previous frame are
+    /// dropped. An `App` calls this once per frame, after its schedule.
+    pub fn clear_trackers(&mut self) {
+        let cutoff = self.last_change_tick;
+        for removed in self.removed.values_mut() {
+            removed.retain(|(_, tick)| *tick > cutoff);
+        }
+
+        self.last_change_tick = self.increment_change_tick();
+    }
+
+    /// Entities that lost their `T` since the last `clear_trackers`.
+    pub fn removed<T: Component>(&self) -> impl Iterator<Item = Entity> + '_ {
+        let last_change_tick = self.last_change_tick;
+        self.removed_log(TypeId::of::<T>())
+            .iter()
+            .filter(move |(_, tick)| *tick > last_change_tick)
+            .map(|(entity, _)| *entity)
+    }
+
+    pub(crate) fn removed_log(&self, id: TypeId) -> &[(Entity, u64)] {
+        self.removed.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    fn change_ticks(&self) -> ChangeTicks {
+        ChangeTicks {
+            last_run: self.last_change_tick,
+            this_run: self.change_tick(),
+        }
+    }
+
+    /// Stores `resource`, returning the previous value of the same type.
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> Option<R> {
+        self.resources.insert(resource, None)
+    }
+
+    /// Stores a resource that must stay on the current thread, such as a
+    /// graphics context. Only systems using `NonSend`/`NonSendMut` can reach
+    /// it, and they always run on the thread stepping the schedule.
+    pub fn insert_non_send_resource<R: 'static>(&mut self, resource: R) -> Option<R> {
+        self.resources
+            .insert(resource, Some(std::thread::current().id()))
+    }
+
+    /// Removes and returns a resource, send or not.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `R` is a non-send resource owned by another thread.
+    pub fn remove_resource<R: 'static>(&mut self) -> Option<R> {
+        self.resources.remove()
+    }
+
+    pub fn contains_resource<R: 'static>(&self) -> bool {
+        self.resources.contains::<R>()
+    }
+
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.resources.get()
+    }
+
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if called from a thread other than the one that inserted `R`.
+    pub fn non_send_resource<R: 'static>(&self) -> Option<&R> {
+        self.resources.get()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if called from a thread other than the one that inserted `R`.
+    pub fn non_send_resource_mut<R: 'static>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut()
+    }
+
+    pub(crate) fn archetype_of(&self, entity: Entity) -> Option<(&Archetype, usize)> {
+        let location = self.entities.location(entity)?;
+        Some((self.archetypes.get(location.archetype), location.row))
+    }
+
+    /// Updates bookkeeping after `entity` moved from `from` to row `row` of
+    /// archetype `archetype`, and `moved` was swapped into its old row.
+    fn relocate(
+        &mut self,
+        entity: Entity,
+        moved: Option<Entity>,
+        from: EntityLocation,
+        archetype: usize,
+        row: usize,
+    ) {
+        if let Some(moved) = moved {
+            self.entities.set_location(moved, from);
+        }
+
+        self.entities
+            .set_location(entity, EntityLocation { archetype, row });
+    }
+
+    /// Runs the `on_add` hooks of every component type in bundle `B`.
+    fn trigger_bundle_add<B: Bundle>(&mut self, entity: Entity) {
+        let ids = &self.bundles[&TypeId::of::<B>()];
+        if !ids.iter().any(|id| self.hooks.has(HookKind::Add, *id)) {
+            return;
+        }
+
+        for id in ids.clone() {
+            self.trigger(HookKind::Add, id, entity);
+        }
+    }
+
+    /// The archetype reached by adding bundle `B` to `source`.
+    fn bundle_archetype<B: Bundle>(&mut self, source: usize) -> usize {
+        let bundle = TypeId::of::<B>();
+        if !self.bundles.contains_key(&bundle) {
+            let mut ids = Vec::new();
+            B::register(&mut self.components, &mut ids);
+            ids.sort();
+
+            if let Some(duplicate) = ids.windows(2).find(|pair| pair[0] == pair[1]) {
+                panic!(
+                    "bundle `{}` contains `{}` more than once",
+                    type_name::<B>(),
+                    self.components.name(duplicate[0])
+                );
+            }
+
+            self.bundles.insert(bundle, ids.into_boxed_slice());
+        }
+
+        self.archetypes
+            .with_bundle(source, bundle, &self.bundles[&bundle], &self.components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Added, Changed, CommandQueue, Commands};
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Name(&'static str);
+
+    #[test]
+    fn structural_changes_move_entities_between_archetypes() {
+        let mut world = World::new();
+        let first = world.spawn((Position(1), Velocity(10)));
+        let second = world.spawn((Position(2), Velocity(20)));
+        let third = world.spawn((Position(3), Velocity(30)));
+        let archetype = world.entities.location(first).unwrap().archetype;
+
+        // Moving the first entity out swaps the last one into its row.
+        assert!(world.insert(first, Name("first")));
+        assert_ne!(world.entities.location(first).unwrap().archetype, archetype);
+        assert_eq!(world.entities.location(third).unwrap().row, 0);
+        assert_eq!(world.get::<Position>(third), Some(&Position(3)));
+        assert_eq!(world.get::<Velocity>(first), Some(&Velocity(10)));
+
+        assert_eq!(world.remove::<Velocity>(second), Some(Velocity(20)));
+        assert_eq!(world.remove::<Velocity>(second), None);
+        assert!(!world.has::<Velocity>(second));
+        assert_eq!(world.get::<Position>(second), Some(&Position(2)));
+
+        // Back to its first set of types, it lands in the same archetype.
+        assert_eq!(world.remove::<Name>(first), Some(Name("first")));
+        assert_eq!(world.entities.location(first).unwrap().archetype, archetype);
+
+        assert!(world.despawn(third));
+        assert!(!world.despawn(third));
+        assert_eq!(world.len(), 2);
+        assert_eq!(world.get::<Velocity>(first), Some(&Velocity(10)));
+        assert_eq!(world.get::<Position>(second), Some(&Position(2)));
+    }
+
+    #[test]
+    fn despawned_ids_are_reused_with_a_new_generation() {
+        let mut world = World::new();
+        let old = world.spawn((Position(1),));
+        world.despawn(old);
+
+        let new = world.spawn((Position(2),));
+        assert_eq!(new.index, old.index);
+        assert_ne!(new.generation, old.generation);
+
+        assert!(!world.contains(old));
+        assert_eq!(world.get::<Position>(old), None);
+        assert!(!world.insert(old, Velocity(1)));
+        assert!(!world.despawn(old));
+        assert_eq!(world.get::<Position>(new), Some(&Position(2)));
+        assert!(!world.has::<Velocity>(new));
+    }
+
+    #[test]
+    fn overlapping_bundles_replace_and_add() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(1),));
+        world.clear_trackers();
+
+        assert!(world.insert_bundle(entity, (Position(2), Velocity(3))));
+        assert_eq!(world.get::<Position>(entity), Some(&Position(2)));
+        assert_eq!(world.get::<Velocity>(entity), Some(&Velocity(3)));
+        assert!(
+            world
+                .query_filtered::<Entity, Changed<Position>>()
+                .iter()
+                .eq([entity])
+        );
+        assert!(
+            world
+                .query_filtered::<Entity, Added<Position>>()
+                .iter()
+                .next()
+                .is_none()
+        );
+
+        // Already holding every type, nothing moves.
+        let location = world.entities.location(entity).unwrap();
+        assert!(world.insert_bundle(entity, (Velocity(4), Position(5))));
+        assert_eq!(world.entities.location(entity), Some(location));
+        assert_eq!(world.get::<Velocity>(entity), Some(&Velocity(4)));
+        assert_eq!(world.get::<Position>(entity), Some(&Position(5)));
+    }
+
+    #[test]
+    fn reserved_entities_exist_after_a_flush() {
+        let mut world = World::new();
+        let entity = world.reserve_entity();
+        assert!(!world.contains(entity));
+        assert_eq!(world.len(), 0);
+
+        world.flush();
+        assert!(world.contains(entity));
+        assert_eq!(world.len(), 1);
+        assert_eq!(world.entities().collect::<Vec<_>>(), [entity]);
+
+        // Structural changes flush first, so a reserved id can be used right away.
+        let reserved = world.reserve_entity();
+        assert!(world.insert(reserved, Position(1)));
+        assert_eq!(world.get::<Position>(reserved), Some(&Position(1)));
+    }
+
+    #[test]
+    fn commands_use_reserved_entities() {
+        let mut world = World::new();
+        let existing = world.spawn((Position(0),));
+        let mut queue = CommandQueue::new();
+
+        let (spawned, empty) = {
+            let mut commands = Commands::new(&mut queue, &world);
+            let spawned = commands.spawn((Position(1),));
+            let empty = commands.spawn_empty();
+            commands.insert(empty, Velocity(2));
+            commands.insert_bundle(spawned, (Position(3), Name("spawned")));
+            commands.despawn(existing);
+            (spawned, empty)
+        };
+
+        assert!(!world.contains(spawned) && !world.contains(empty));
+        assert!(world.contains(existing));
+
+        queue.apply(&mut world);
+        assert!(queue.is_empty());
+        assert!(!world.contains(existing));
+        assert_eq!(world.get::<Position>(spawned), Some(&Position(3)));
+        assert_eq!(world.get::<Name>(spawned), Some(&Name("spawned")));
+        assert_eq!(world.get::<Velocity>(empty), Some(&Velocity(2)));
+        assert_eq!(world.len(), 2);
+
+        // Flushed before the queue ran, a reserved entity takes its commands
+        // just the same.
+        let mut commands = Commands::new(&mut queue, &world);
+        let late = commands.spawn_empty();
+        commands.insert(late, Name("late"));
+        world.flush();
+        assert!(world.contains(late));
+        queue.apply(&mut world);
+        assert_eq!(world.get::<Name>(late), Some(&Name("late")));
+    }
+}
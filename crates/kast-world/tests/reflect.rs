@@ -0,0 +1,147 @@
+//! Checks what `#[derive(Reflect)]` generates for each shape of struct.
+
+use kast_world::{Reflect, ReflectError, SceneValue, TypeRegistry, Value, World};
+
+#[derive(Reflect, Debug, PartialEq)]
+struct Health {
+    current: f32,
+    max: f32,
+    #[reflect(ignore)]
+    regen_timer: Timer,
+}
+
+#[derive(Default, Debug, PartialEq)]
+struct Timer(u32);
+
+#[derive(Reflect, Debug, PartialEq)]
+struct Range(i32, #[reflect(ignore)] Timer, i32);
+
+#[derive(Reflect, Debug, PartialEq)]
+struct Name(String);
+
+#[derive(Reflect, Debug, PartialEq)]
+struct Player;
+
+fn field_names<T: Reflect>() -> Vec<&'static str> {
+    T::type_info()
+        .fields
+        .iter()
+        .map(|field| field.name)
+        .collect()
+}
+
+#[test]
+fn named_structs_are_objects_without_their_ignored_fields() {
+    let health = Health {
+        current: 5.0,
+        max: 10.0,
+        regen_timer: Timer(3),
+    };
+
+    assert_eq!(Health::type_info().name, "Health");
+    assert_eq!(field_names::<Health>(), ["current", "max"]);
+    assert_eq!(Health::type_info().field("max").unwrap().type_name, "f32");
+    assert_eq!(
+        health.to_value(),
+        Value::Object(vec![
+            ("current".into(), Value::Number(5.0)),
+            ("max".into(), Value::Number(10.0)),
+        ])
+    );
+
+    let loaded = Health::from_value(&health.to_value()).unwrap();
+    assert_eq!(loaded.regen_timer, Timer::default());
+    assert_eq!((loaded.current, loaded.max), (5.0, 10.0));
+}
+
+#[test]
+fn tuple_structs_are_arrays_of_their_reflected_fields() {
+    let range = Range(-2, Timer(1), 7);
+
+    assert_eq!(field_names::<Range>(), ["0", "2"]);
+    assert_eq!(
+        range.to_value(),
+        Value::Array(vec![Value::Int(-2), Value::UInt(7)])
+    );
+    assert_eq!(
+        Range::from_value(&range.to_value()),
+        Ok(Range(-2, Timer::default(), 7))
+    );
+    assert!(Range::from_value(&Value::Array(vec![Value::UInt(1)])).is_err());
+}
+
+#[test]
+fn newtypes_and_unit_structs_are_plain_values() {
+    let name = Name("Ada".into());
+    assert_eq!(field_names::<Name>(), ["0"]);
+    assert_eq!(name.to_value(), Value::String("Ada".into()));
+    assert_eq!(Name::from_value(&name.to_value()), Ok(name));
+
+    assert!(field_names::<Player>().is_empty());
+    assert_eq!(Player.to_value(), Value::Null);
+    assert_eq!(Player::from_value(&Value::Null), Ok(Player));
+}
+
+#[test]
+fn fields_are_reachable_by_name() {
+    let mut health = Health {
+        current: 5.0,
+        max: 10.0,
+        regen_timer: Timer(3),
+    };
+
+    let reflected: &mut dyn Reflect = &mut health;
+    assert_eq!(reflected.get_field::<f32>("current"), Some(&5.0));
+    assert!(reflected.field("regen_timer").is_none());
+    *reflected.get_field_mut::<f32>("max").unwrap() = 20.0;
+    reflected
+        .set_field_value("current", &Value::UInt(15))
+        .unwrap();
+    assert_eq!(
+        reflected.set_field_value("regen_timer", &Value::UInt(1)),
+        Err(ReflectError::UnknownField("regen_timer".into()))
+    );
+    assert!(
+        reflected
+            .set_field_value("max", &Value::Bool(true))
+            .is_err()
+    );
+    assert_eq!((health.current, health.max), (15.0, 20.0));
+}
+
+#[test]
+fn registered_components_are_edited_through_the_registry() {
+    let mut registry = TypeRegistry::new();
+    registry.register::<Health>().register::<Name>();
+
+    let mut world = World::new();
+    let entity = world.spawn((Name("Ada".into()),));
+    let health = registry.get("Health").unwrap();
+    assert_eq!(
+        health.get(&world, entity, "current"),
+        Err(ReflectError::MissingComponent(entity))
+    );
+
+    world.insert(
+        entity,
+        Health {
+            current: 5.0,
+            max: 10.0,
+            regen_timer: Timer(3),
+        },
+    );
+    health
+        .set(
+            &mut world,
+            entity,
+            "current",
+            &Value::from_json("8").unwrap(),
+        )
+        .unwrap();
+    assert_eq!(world.get::<Health>(entity).unwrap().current, 8.0);
+    assert_eq!(
+        registry.get("Name").unwrap().get(&world, entity, "0"),
+        Ok(Value::String("Ada".into()))
+    );
+    assert_eq!(registry.components(&world, entity).count(), 2);
+}
@@ -1,25 +1,22 @@
 use std::path::Path;
-use std::process::Command;
 
-fn compile(shader_path: &str, output_name: &str, out_dir: &str) {
-    println!("cargo:rerun-if-changed={shader_path}");
-
-    let output_path = Path::new(out_dir).join(output_name);
-    let status = Command::new("glslangValidator")
-        .args(["-V", "-o"])
-        .arg(&output_path)
-        .arg(shader_path)
-        .status()
-        .expect("failed to run glslangValidator (are you in the nix shell?)");
-
-    if !status.success() {
-        panic!("shader compilation failed for {shader_path}");
-    }
-}
+use kast_pack::Packer;
 
 fn main() {
+    println!("cargo:rerun-if-changed=shaders");
+
     let out_dir = std::env::var("OUT_DIR").unwrap();
+    let packer = Packer::new("shaders");
+
+    // Release builds embed a packed archive instead of shipping loose files;
+    // debug builds load the baked files directly so they hot reload.
+    let result = if std::env::var_os("CARGO_CFG_DEBUG_ASSERTIONS").is_some() {
+        packer.pack_directory(Path::new(&out_dir).join("assets"))
+    } else {
+        packer.pack(Path::new(&out_dir).join("assets.kpak"))
+    };
 
-    compile("shaders/triangle.vert", "triangle.vert.spv", &out_dir);
-    compile("shaders/triangle.frag", "triangle.frag.spv", &out_dir);
+    if let Err(error) = result {
+        panic!("failed to pack assets (is glslangValidator installed?): {error}");
+    }
 }
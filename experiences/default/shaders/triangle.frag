@@ -1,9 +1,9 @@
 #version 460
 
-layout(location = 0) in vec3 vertex_color;
+layout(location = 0) in vec4 vertex_color;
 
 layout(location = 0) out vec4 out_color;
 
 void main() {
-    out_color = vec4(vertex_color, 1.0);
+    out_color = vertex_color;
 }
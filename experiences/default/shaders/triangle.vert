@@ -1,9 +1,9 @@
 #version 460
 
 layout(location = 0) in vec2 position;
-layout(location = 1) in vec3 color;
+layout(location = 1) in vec4 color;
 
-layout(location = 0) out vec3 vertex_color;
+layout(location = 0) out vec4 vertex_color;
 
 void main() {
     vertex_color = color;
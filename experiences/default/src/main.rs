@@ -1,62 +1,68 @@
 use kast::prelude::*;
 
-const TRIANGLE_VERT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/triangle.vert.spv"));
-const TRIANGLE_FRAG: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/triangle.frag.spv"));
-
 #[repr(C)]
 #[derive(Clone, Copy)]
 struct Vertex {
     position: [f32; 2],
-    color: [f32; 3],
+    color: Color,
 }
 
 const TRIANGLE_VERTICES: [Vertex; 3] = [
     Vertex {
         position: [0.0, -0.5],
-        color: [1.0, 0.0, 0.0],
+        color: Color::RED,
     },
     Vertex {
         position: [0.5, 0.5],
-        color: [0.0, 1.0, 0.0],
+        color: Color::GREEN,
     },
     Vertex {
         position: [-0.5, 0.5],
-        color: [0.0, 0.0, 1.0],
+        color: Color::BLUE,
     },
 ];
 
 fn main() {
-    App::builder()
-        .with_window(WindowConfig {
-            title: "Default Window".to_owned(),
-            size: PhysicalSize {
-                width: 800,
-                height: 600,
-            },
-            position: None,
-            mode: WindowMode::Windowed,
-        })
-        .build_with(DefaultExperience::default())
-        .run();
+    let builder = App::builder().with_window(WindowConfig {
+        title: "Default Window".to_owned(),
+        size: PhysicalSize {
+            width: 800,
+            height: 600,
+        },
+        position: None,
+        mode: WindowMode::Windowed,
+    });
+
+    // build.rs bakes the assets here; rebuilding while the app runs reloads
+    // them.
+    #[cfg(debug_assertions)]
+    let builder = builder.with_asset_root(concat!(env!("OUT_DIR"), "/assets"));
+
+    // Release builds carry their assets packed into the executable.
+    #[cfg(not(debug_assertions))]
+    let builder = builder.with_asset_source(
+        "",
+        ArchiveSource::from_bytes(&include_bytes!(concat!(env!("OUT_DIR"), "/assets.kpak"))[..])
+            .expect("the packed assets are corrupt"),
+    );
+
+    builder.build_with(DefaultExperience::default()).run();
 }
 
 #[derive(Default)]
 struct DefaultExperience {
-    pipeline: Option<PipelineHandle>,
-    vertex_buffer: Option<BufferHandle>,
+    pipeline: Option<StrongHandle<ShaderPipeline>>,
+    vertex_buffer: Option<StrongHandle<GpuBuffer>>,
 }
 
 impl AppState for DefaultExperience {
-    fn on_resume(&mut self, context: &mut AppContext) {
-        if self.pipeline.is_some() {
-            return;
-        }
-
-        let Some(gfx) = context.renderer.context_mut() else {
-            return;
-        };
-
-        let vertex_layout = [
+    fn on_init(&mut self, context: &mut AppContext) {
+        let assets = context.assets_mut();
+        let mut pipeline = ShaderPipelineDescriptor::new(
+            assets.load("triangle.vert.spv"),
+            assets.load("triangle.frag.spv"),
+        );
+        pipeline.vertex_layout = vec![
             VertexAttribute {
                 format: VertexFormat {
                     size: 8,
@@ -66,22 +72,25 @@ impl AppState for DefaultExperience {
             },
             VertexAttribute {
                 format: VertexFormat {
-                    size: 12,
-                    components: 3,
+                    size: 16,
+                    components: 4,
                 },
                 offset: 8,
             },
         ];
+        pipeline.cull_mode = CullMode::None;
 
-        let pipeline = gfx
-            .create_pipeline(&PipelineDescriptor {
-                vertex_shader: TRIANGLE_VERT,
-                fragment_shader: TRIANGLE_FRAG,
-                vertex_layout: &vertex_layout,
-                cull_mode: CullMode::None,
-                ..Default::default()
-            })
-            .expect("failed to create triangle pipeline");
+        self.pipeline = Some(assets.add(ShaderPipeline::new(pipeline)));
+    }
+
+    fn on_resume(&mut self, context: &mut AppContext) {
+        if self.vertex_buffer.is_some() {
+            return;
+        }
+
+        let Some(gfx) = context.renderer_mut().context_mut() else {
+            return;
+        };
 
         let buffer_size = core::mem::size_of_val(&TRIANGLE_VERTICES) as u64;
         let vertex_buffer = gfx
@@ -98,29 +107,37 @@ impl AppState for DefaultExperience {
         gfx.upload_buffer(vertex_buffer, vertex_bytes)
             .expect("failed to upload vertex data");
 
-        self.pipeline = Some(pipeline);
-        self.vertex_buffer = Some(vertex_buffer);
+        let resources = context.renderer().resources();
+        self.vertex_buffer = Some(resources.track_buffer(vertex_buffer));
     }
 
     fn on_render(&mut self, context: &mut AppContext) {
-        let (Some(pipeline), Some(vertex_buffer)) = (self.pipeline, self.vertex_buffer) else {
+        let (Some(pipeline), Some(vertex_buffer)) = (&self.pipeline, &self.vertex_buffer) else {
+            return;
+        };
+        // Not built until both shaders have loaded.
+        let Some(pipeline) = context
+            .assets()
+            .get(pipeline.id())
+            .and_then(ShaderPipeline::handle)
+        else {
             return;
         };
-        let Some(gfx) = context.renderer.context_mut() else {
+        let Some(gfx) = context.renderer_mut().context_mut() else {
             return;
         };
 
         let mut pass = RenderPass::new(RenderPassDescriptor {
             color_attachments: vec![ColorAttachment {
                 target: None,
-                clear_color: Some([0.02, 0.02, 0.05, 1.0]),
+                clear_color: Some(Color::rgb(0.02, 0.02, 0.05)),
             }],
             depth_attachment: None,
         });
 
         pass.draw_list.push(DrawCall {
             pipeline,
-            vertex_buffer,
+            vertex_buffer: vertex_buffer.id(),
             index_buffer: None,
             index_count: 3,
             instance_count: 1,
@@ -131,4 +148,10 @@ impl AppState for DefaultExperience {
             eprintln!("Failed to submit frame: {error}");
         }
     }
+
+    fn on_exit(&mut self, context: &mut AppContext) {
+        self.pipeline = None;
+        self.vertex_buffer = None;
+        context.renderer_mut().release_unused();
+    }
 }